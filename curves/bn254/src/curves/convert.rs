@@ -0,0 +1,173 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversions between Arkworks-Ext types and Arkworks upstream types.
+//!
+//! Both share the very same `Fq`, `Fq2` and `Fq12` representations and only differ
+//! in their configuration marker, thus coordinates are copied as they are.
+
+use ark_bn254::{
+    g1::Config as ArkG1Config, g2::Config as ArkG2Config, Bn254 as ArkBn254, Config as ArkConfig,
+};
+use ark_ec::bn::{G1Prepared as ArkG1Prepared, G2Prepared as ArkG2Prepared};
+use ark_models_ext::{
+    bn::{G1Prepared, G2Prepared},
+    pairing::{MillerLoopOutput, PairingOutput},
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    AffineRepr,
+};
+
+use crate::{g1, g2, Bn254, Config, CurveHooks};
+
+/// Conversion into the equivalent Arkworks upstream type.
+pub trait ToArk {
+    /// Arkworks upstream type.
+    type Ark;
+
+    /// Converts into the Arkworks upstream type.
+    fn to_ark(self) -> Self::Ark;
+}
+
+/// Conversion from the equivalent Arkworks upstream type.
+pub trait FromArk: Sized {
+    /// Arkworks upstream type.
+    type Ark;
+
+    /// Converts from the Arkworks upstream type.
+    fn from_ark(value: Self::Ark) -> Self;
+}
+
+pub(crate) fn convert_affine<S: SWCurveConfig, D: SWCurveConfig<BaseField = S::BaseField>>(
+    point: Affine<S>,
+) -> Affine<D> {
+    match point.xy() {
+        Some((x, y)) => Affine::new_unchecked(x, y),
+        None => Affine::identity(),
+    }
+}
+
+pub(crate) fn convert_projective<S: SWCurveConfig, D: SWCurveConfig<BaseField = S::BaseField>>(
+    point: Projective<S>,
+) -> Projective<D> {
+    Projective::new_unchecked(point.x, point.y, point.z)
+}
+
+macro_rules! impl_sw_conversions {
+    ($ext:ty, $ark:ty) => {
+        impl<H: CurveHooks> ToArk for Affine<$ext> {
+            type Ark = Affine<$ark>;
+
+            #[inline(always)]
+            fn to_ark(self) -> Self::Ark {
+                convert_affine(self)
+            }
+        }
+
+        impl<H: CurveHooks> FromArk for Affine<$ext> {
+            type Ark = Affine<$ark>;
+
+            #[inline(always)]
+            fn from_ark(value: Self::Ark) -> Self {
+                convert_affine(value)
+            }
+        }
+
+        impl<H: CurveHooks> ToArk for Projective<$ext> {
+            type Ark = Projective<$ark>;
+
+            #[inline(always)]
+            fn to_ark(self) -> Self::Ark {
+                convert_projective(self)
+            }
+        }
+
+        impl<H: CurveHooks> FromArk for Projective<$ext> {
+            type Ark = Projective<$ark>;
+
+            #[inline(always)]
+            fn from_ark(value: Self::Ark) -> Self {
+                convert_projective(value)
+            }
+        }
+    };
+}
+
+impl_sw_conversions!(g1::Config<H>, ArkG1Config);
+impl_sw_conversions!(g2::Config<H>, ArkG2Config);
+
+impl<H: CurveHooks> ToArk for G1Prepared<Config<H>> {
+    type Ark = ArkG1Prepared<ArkConfig>;
+
+    #[inline(always)]
+    fn to_ark(self) -> Self::Ark {
+        ArkG1Prepared(self.0.to_ark())
+    }
+}
+
+impl<H: CurveHooks> FromArk for G1Prepared<Config<H>> {
+    type Ark = ArkG1Prepared<ArkConfig>;
+
+    #[inline(always)]
+    fn from_ark(value: Self::Ark) -> Self {
+        G1Prepared(FromArk::from_ark(value.0))
+    }
+}
+
+/// Upstream G2 preparation precomputes the line coefficients, thus the conversion
+/// is not free and can't be reverted.
+impl<H: CurveHooks> ToArk for G2Prepared<Config<H>> {
+    type Ark = ArkG2Prepared<ArkConfig>;
+
+    #[inline(always)]
+    fn to_ark(self) -> Self::Ark {
+        ArkG2Prepared::from(self.0.to_ark())
+    }
+}
+
+impl<H: CurveHooks> ToArk for MillerLoopOutput<Bn254<H>> {
+    type Ark = MillerLoopOutput<ArkBn254>;
+
+    #[inline(always)]
+    fn to_ark(self) -> Self::Ark {
+        MillerLoopOutput(self.0)
+    }
+}
+
+impl<H: CurveHooks> FromArk for MillerLoopOutput<Bn254<H>> {
+    type Ark = MillerLoopOutput<ArkBn254>;
+
+    #[inline(always)]
+    fn from_ark(value: Self::Ark) -> Self {
+        MillerLoopOutput(value.0)
+    }
+}
+
+impl<H: CurveHooks> ToArk for PairingOutput<Bn254<H>> {
+    type Ark = PairingOutput<ArkBn254>;
+
+    #[inline(always)]
+    fn to_ark(self) -> Self::Ark {
+        PairingOutput(self.0)
+    }
+}
+
+impl<H: CurveHooks> FromArk for PairingOutput<Bn254<H>> {
+    type Ark = PairingOutput<ArkBn254>;
+
+    #[inline(always)]
+    fn from_ark(value: Self::Ark) -> Self {
+        PairingOutput(value.0)
+    }
+}
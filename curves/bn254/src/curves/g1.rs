@@ -14,10 +14,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::CurveHooks;
+use crate::{CurveHooks, HookError, TryCurveOps};
 
 use ark_bn254::g1::Config as ArkConfig;
-use ark_models_ext::{bn, short_weierstrass::SWCurveConfig, CurveConfig};
+use ark_ff::PrimeField;
+use ark_models_ext::{
+    bn,
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    CurveConfig,
+};
 use ark_std::marker::PhantomData;
 
 pub use ark_bn254::g1::{G1_GENERATOR_X, G1_GENERATOR_Y};
@@ -28,6 +33,24 @@ pub type G1Projective<H> = bn::G1Projective<crate::Config<H>>;
 #[derive(Clone, Copy)]
 pub struct Config<H: CurveHooks>(PhantomData<fn() -> H>);
 
+impl<H: CurveHooks> TryCurveOps for Projective<Config<H>> {
+    #[inline(always)]
+    fn try_msm(
+        bases: &[Affine<Config<H>>],
+        scalars: &[Self::ScalarField],
+    ) -> Result<Self, HookError> {
+        if bases.len() != scalars.len() {
+            return Err(HookError::LengthMismatch);
+        }
+        H::bn254_msm_g1(bases, scalars)
+    }
+
+    #[inline(always)]
+    fn try_mul(&self, scalar: &Self::ScalarField) -> Result<Self, HookError> {
+        H::bn254_mul_projective_g1(self, scalar.into_bigint().as_ref())
+    }
+}
+
 impl<H: CurveHooks> CurveConfig for Config<H> {
     const COFACTOR: &'static [u64] = <ArkConfig as CurveConfig>::COFACTOR;
     const COFACTOR_INV: Self::ScalarField = <ArkConfig as CurveConfig>::COFACTOR_INV;
@@ -14,15 +14,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::native;
 use ark_bn254::Config as ArkConfig;
 use ark_ec::bn::BnConfig as ArkBnConfig;
+use ark_ff::One;
 use ark_models_ext::{
     bn::{Bn, BnConfig, G1Prepared, G2Prepared, TwistType},
     pairing::{MillerLoopOutput, Pairing, PairingOutput},
-    CurveConfig,
+    CurveConfig, CurveGroup,
 };
 use ark_std::marker::PhantomData;
 
+pub mod convert;
 pub mod g1;
 pub mod g2;
 
@@ -30,46 +33,203 @@ pub mod g2;
 mod tests;
 
 pub use self::{
+    convert::{FromArk, ToArk},
     g1::{G1Affine, G1Projective},
     g2::{G2Affine, G2Projective},
 };
 
+/// Error returned by [`CurveHooks`] methods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HookError {
+    /// Failed to encode or decode a value crossing the hook boundary.
+    Encoding,
+    /// Input is not valid for the requested operation.
+    InvalidInput,
+    /// Bases and scalars have different lengths.
+    LengthMismatch,
+    /// Backend specific failure identified by an implementation defined code.
+    Backend(u32),
+}
+
+impl core::fmt::Display for HookError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            HookError::Encoding => write!(f, "encoding failure"),
+            HookError::InvalidInput => write!(f, "invalid input"),
+            HookError::LengthMismatch => write!(f, "length mismatch"),
+            HookError::Backend(code) => write!(f, "backend failure (code {code})"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HookError {}
+
 /// Hooks for *BN-254* curve.
+///
+/// Every hook has a provided software implementation, thus implementers are only
+/// required to override the operations accelerated by their backend.
+///
+/// G2 subgroup checks never go through the hooks: they always run the upstream
+/// software check, so hooks decoding validated points can't re-enter themselves.
+/// Every G2 point read with validation (e.g. by the proof parsers or by
+/// [`crate::eth`]) thus pays for a software scalar multiplication, whatever the
+/// backend.
 pub trait CurveHooks: 'static + Sized {
     /// Pairing multi Miller loop.
+    ///
+    /// Defaults to the upstream *Arkworks* implementation.
     fn bn254_multi_miller_loop(
         g1: impl Iterator<Item = <Bn254<Self> as Pairing>::G1Prepared>,
         g2: impl Iterator<Item = <Bn254<Self> as Pairing>::G2Prepared>,
-    ) -> Result<<Bn254<Self> as Pairing>::TargetField, ()>;
+    ) -> Result<<Bn254<Self> as Pairing>::TargetField, HookError> {
+        native::multi_miller_loop(g1, g2)
+    }
 
     /// Pairing final exponentiation.
+    ///
+    /// Defaults to the upstream *Arkworks* implementation.
     fn bn254_final_exponentiation(
         target: <Bn254<Self> as Pairing>::TargetField,
-    ) -> Result<<Bn254<Self> as Pairing>::TargetField, ()>;
+    ) -> Result<<Bn254<Self> as Pairing>::TargetField, HookError> {
+        native::final_exponentiation(target)
+    }
+
+    /// Pairing product check.
+    ///
+    /// Returns `true` if the product of the pairings of the given pairs is one.
+    ///
+    /// Defaults to a multi Miller loop followed by a final exponentiation.
+    fn bn254_pairing_check(
+        g1: impl Iterator<Item = <Bn254<Self> as Pairing>::G1Prepared>,
+        g2: impl Iterator<Item = <Bn254<Self> as Pairing>::G2Prepared>,
+    ) -> Result<bool, HookError> {
+        let target = Self::bn254_multi_miller_loop(g1, g2)?;
+        let res = Self::bn254_final_exponentiation(target)?;
+        Ok(res.is_one())
+    }
 
     /// Multi scalar multiplication on G1.
+    ///
+    /// Defaults to the upstream *Arkworks* implementation.
     fn bn254_msm_g1(
         bases: &[g1::G1Affine<Self>],
         scalars: &[<g1::Config<Self> as CurveConfig>::ScalarField],
-    ) -> Result<G1Projective<Self>, ()>;
+    ) -> Result<G1Projective<Self>, HookError> {
+        native::msm_g1(bases, scalars)
+    }
 
     /// Multi scalar multiplication on G2.
+    ///
+    /// Defaults to the upstream *Arkworks* implementation.
     fn bn254_msm_g2(
         bases: &[g2::G2Affine<Self>],
         scalars: &[<g2::Config<Self> as CurveConfig>::ScalarField],
-    ) -> Result<G2Projective<Self>, ()>;
+    ) -> Result<G2Projective<Self>, HookError> {
+        native::msm_g2(bases, scalars)
+    }
 
     /// Projective multiplication on G1.
+    ///
+    /// Defaults to the upstream *Arkworks* implementation.
     fn bn254_mul_projective_g1(
         base: &G1Projective<Self>,
         scalar: &[u64],
-    ) -> Result<G1Projective<Self>, ()>;
+    ) -> Result<G1Projective<Self>, HookError> {
+        native::mul_projective_g1(base, scalar)
+    }
 
     /// Projective multiplication on G2.
+    ///
+    /// Defaults to the upstream *Arkworks* implementation.
     fn bn254_mul_projective_g2(
         base: &G2Projective<Self>,
         scalar: &[u64],
-    ) -> Result<G2Projective<Self>, ()>;
+    ) -> Result<G2Projective<Self>, HookError> {
+        native::mul_projective_g2(base, scalar)
+    }
+}
+
+/// Fallible pairing operations.
+///
+/// Unlike the [`Pairing`] methods, these surface the [`HookError`] returned by the
+/// hooks instead of silently replacing the result with a default value.
+pub trait TryPairing: Pairing {
+    /// Multi Miller loop jumping into the user-defined `multi_miller_loop` hook.
+    fn try_multi_miller_loop(
+        a: impl IntoIterator<Item = impl Into<Self::G1Prepared>>,
+        b: impl IntoIterator<Item = impl Into<Self::G2Prepared>>,
+    ) -> Result<MillerLoopOutput<Self>, HookError>;
+
+    /// Final exponentiation jumping into the user-defined `final_exponentiation` hook.
+    fn try_final_exponentiation(
+        f: MillerLoopOutput<Self>,
+    ) -> Result<PairingOutput<Self>, HookError>;
+
+    /// Computes the product of pairings of the given pairs of points.
+    fn try_multi_pairing(
+        a: impl IntoIterator<Item = impl Into<Self::G1Prepared>>,
+        b: impl IntoIterator<Item = impl Into<Self::G2Prepared>>,
+    ) -> Result<PairingOutput<Self>, HookError> {
+        Self::try_final_exponentiation(Self::try_multi_miller_loop(a, b)?)
+    }
+
+    /// Checks whether the product of the pairings of the given pairs of points is one.
+    fn pairing_check(
+        a: impl IntoIterator<Item = impl Into<Self::G1Prepared>>,
+        b: impl IntoIterator<Item = impl Into<Self::G2Prepared>>,
+    ) -> Result<bool, HookError>;
+
+    /// Computes the pairing of the given points.
+    fn try_pairing(
+        p: impl Into<Self::G1Prepared>,
+        q: impl Into<Self::G2Prepared>,
+    ) -> Result<PairingOutput<Self>, HookError> {
+        Self::try_multi_pairing([p], [q])
+    }
+}
+
+impl<H: CurveHooks> TryPairing for Bn254<H> {
+    #[inline(always)]
+    fn try_multi_miller_loop(
+        a: impl IntoIterator<Item = impl Into<Self::G1Prepared>>,
+        b: impl IntoIterator<Item = impl Into<Self::G2Prepared>>,
+    ) -> Result<MillerLoopOutput<Self>, HookError> {
+        let a = a.into_iter().map(|item| item.into());
+        let b = b.into_iter().map(|item| item.into());
+        H::bn254_multi_miller_loop(a, b).map(MillerLoopOutput)
+    }
+
+    #[inline(always)]
+    fn try_final_exponentiation(
+        f: MillerLoopOutput<Self>,
+    ) -> Result<PairingOutput<Self>, HookError> {
+        H::bn254_final_exponentiation(f.0).map(PairingOutput)
+    }
+
+    #[inline(always)]
+    fn pairing_check(
+        a: impl IntoIterator<Item = impl Into<Self::G1Prepared>>,
+        b: impl IntoIterator<Item = impl Into<Self::G2Prepared>>,
+    ) -> Result<bool, HookError> {
+        let a = a.into_iter().map(|item| item.into());
+        let b = b.into_iter().map(|item| item.into());
+        H::bn254_pairing_check(a, b)
+    }
+}
+
+/// Fallible group operations.
+///
+/// Unlike the [`SWCurveConfig`](ark_models_ext::short_weierstrass::SWCurveConfig)
+/// methods, these surface the [`HookError`] returned by the hooks instead of
+/// silently replacing the result with a default value.
+pub trait TryCurveOps: CurveGroup {
+    /// Multi scalar multiplication jumping into the user-defined `msm` hook.
+    fn try_msm(bases: &[Self::Affine], scalars: &[Self::ScalarField]) -> Result<Self, HookError>;
+
+    /// Scalar multiplication jumping into the user-defined `mul_projective` hook.
+    fn try_mul(&self, scalar: &Self::ScalarField) -> Result<Self, HookError>;
 }
 
 #[derive(Clone, Copy)]
@@ -16,13 +16,23 @@
 
 #![cfg(test)]
 
-use crate::base_impl::*;
+use crate::{native::*, CurveHooks, HookError, TryCurveOps, TryPairing};
 use ark_algebra_test_templates::*;
-use ark_models_ext::pairing::PairingOutput;
+use ark_ff::{One, UniformRand, Zero};
+use ark_models_ext::{
+    pairing::{Pairing, PairingOutput},
+    AffineRepr, CurveGroup, VariableBaseMSM,
+};
+use ark_std::test_rng;
 
 #[cfg(not(feature = "std"))]
 extern crate std;
 
+type Bn254 = Bn254Native;
+type G1Projective = G1Native;
+type G2Projective = G2Native;
+type G1Affine = G1AffineNative;
+
 const fn iterations() -> usize {
     match std::option_env!("FAST_TESTS") {
         Some(_) => 2,
@@ -33,4 +43,110 @@ const fn iterations() -> usize {
 test_group!(iterations(); g1; G1Projective; sw);
 test_group!(iterations(); g2; G2Projective; sw);
 test_group!(iterations(); pairing_output; PairingOutput<Bn254>; msm);
-test_pairing!(pairing; crate::Bn254<()>);
+test_pairing!(pairing; crate::native::Bn254Native);
+
+#[test]
+fn hook_errors_are_reported() {
+    let bases = [G1Affine::generator()];
+    assert_eq!(
+        <NativeHooks as CurveHooks>::bn254_msm_g1(&bases, &[]),
+        Err(HookError::LengthMismatch)
+    );
+
+    let target = <Bn254 as Pairing>::TargetField::zero();
+    assert_eq!(
+        <NativeHooks as CurveHooks>::bn254_final_exponentiation(target),
+        Err(HookError::InvalidInput)
+    );
+}
+
+#[test]
+fn fallible_api_matches_infallible_api() {
+    let mut rng = test_rng();
+    let p = G1Projective::rand(&mut rng);
+    let q = G2Projective::rand(&mut rng);
+    let s = <Bn254 as Pairing>::ScalarField::rand(&mut rng);
+
+    assert_eq!(Bn254::try_pairing(p, q).unwrap(), Bn254::pairing(p, q));
+    assert_eq!(p.try_mul(&s).unwrap(), p * s);
+    assert_eq!(q.try_mul(&s).unwrap(), q * s);
+
+    let bases = [p.into_affine()];
+    assert_eq!(
+        G1Projective::try_msm(&bases, &[s]).unwrap(),
+        G1Projective::msm(&bases, &[s]).unwrap()
+    );
+    assert_eq!(
+        G1Projective::try_msm(&bases, &[]),
+        Err(HookError::LengthMismatch)
+    );
+}
+
+#[test]
+fn pairing_check_works() {
+    let mut rng = test_rng();
+    let p = G1Projective::rand(&mut rng);
+    let q = G2Projective::rand(&mut rng);
+
+    assert_eq!(Bn254::pairing_check([p, -p], [q, q]), Ok(true));
+    assert_eq!(Bn254::pairing_check([p, p], [q, q]), Ok(false));
+}
+
+#[test]
+fn hooks_can_be_partially_overridden() {
+    struct MsmOnlyHooks;
+
+    impl CurveHooks for MsmOnlyHooks {
+        fn bn254_msm_g1(
+            _bases: &[crate::G1Affine<Self>],
+            _scalars: &[crate::Fr],
+        ) -> Result<crate::G1Projective<Self>, HookError> {
+            Err(HookError::Backend(7))
+        }
+    }
+
+    let mut rng = test_rng();
+    let p = crate::G1Projective::<MsmOnlyHooks>::rand(&mut rng);
+    let q = crate::G2Projective::<MsmOnlyHooks>::rand(&mut rng);
+
+    assert_eq!(
+        crate::Bn254::<MsmOnlyHooks>::pairing_check([p, -p], [q, q]),
+        Ok(true)
+    );
+    assert_eq!(
+        crate::G1Projective::<MsmOnlyHooks>::try_msm(&[p.into_affine()], &[crate::Fr::one()]),
+        Err(HookError::Backend(7))
+    );
+}
+
+#[test]
+fn ark_conversions_round_trip() {
+    use crate::{FromArk, ToArk};
+    use ark_serialize::CanonicalSerialize;
+
+    fn bytes(value: impl CanonicalSerialize) -> std::vec::Vec<u8> {
+        let mut buf = std::vec::Vec::new();
+        value.serialize_compressed(&mut buf).unwrap();
+        buf
+    }
+
+    let mut rng = test_rng();
+    let p = G1Projective::rand(&mut rng);
+    let q = G2Projective::rand(&mut rng);
+
+    for p in [p, G1Projective::zero()] {
+        assert_eq!(G1Projective::from_ark(p.to_ark()), p);
+        assert_eq!(bytes(p.into_affine().to_ark()), bytes(p.into_affine()));
+        assert_eq!(
+            G1Affine::from_ark(p.into_affine().to_ark()),
+            p.into_affine()
+        );
+    }
+    for q in [q, G2Projective::zero()] {
+        assert_eq!(G2Projective::from_ark(q.to_ark()), q);
+        assert_eq!(bytes(q.into_affine().to_ark()), bytes(q.into_affine()));
+    }
+
+    let expected = ark_bn254::Bn254::pairing(p.to_ark(), q.to_ark());
+    assert_eq!(Bn254::pairing(p, q).to_ark(), expected);
+}
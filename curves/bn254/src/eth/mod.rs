@@ -0,0 +1,215 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Ethereum precompiles compatible API.
+//!
+//! Implements the semantics of the `ecAdd` (`0x06`), `ecMul` (`0x07`) and `ecPairing`
+//! (`0x08`) precompiles as specified by [EIP-196](https://eips.ethereum.org/EIPS/eip-196)
+//! and [EIP-197](https://eips.ethereum.org/EIPS/eip-197), dispatching the expensive
+//! operations through the [`CurveHooks`].
+//!
+//! Encoding:
+//! * field elements are 32 bytes big-endian integers, which must be lower than the
+//!   base field modulus;
+//! * G1 points are encoded as `x || y` (64 bytes), with `(0, 0)` representing the
+//!   point at infinity;
+//! * G2 points are encoded as `x || y` (128 bytes), where each `Fq2` coordinate
+//!   `c0 + c1 * u` is encoded as `c1 || c0`, with all zeros representing the point
+//!   at infinity;
+//! * scalars are 32 bytes big-endian integers, not required to be canonical.
+
+use ark_ff::{BigInt, PrimeField, Zero};
+use ark_models_ext::{AffineRepr, CurveGroup};
+
+use crate::{
+    Bn254, CurveHooks, Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine, HookError, TryCurveOps,
+    TryPairing,
+};
+use ark_std::vec::Vec;
+
+#[cfg(test)]
+mod tests;
+
+/// Length of an encoded field element.
+pub const FQ_LEN: usize = 32;
+/// Length of an encoded scalar.
+pub const SCALAR_LEN: usize = 32;
+/// Length of an encoded G1 point.
+pub const G1_LEN: usize = 2 * FQ_LEN;
+/// Length of an encoded G2 point.
+pub const G2_LEN: usize = 4 * FQ_LEN;
+/// Input length of [`ec_add`], shorter inputs are right padded with zeros.
+pub const ADD_INPUT_LEN: usize = 2 * G1_LEN;
+/// Input length of [`ec_mul`], shorter inputs are right padded with zeros.
+pub const MUL_INPUT_LEN: usize = G1_LEN + SCALAR_LEN;
+/// Length of each `(G1, G2)` pair in the [`ec_pairing`] input.
+pub const PAIR_ELEMENT_LEN: usize = G1_LEN + G2_LEN;
+
+/// Error returned by the precompiles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// Coordinate is not lower than the base field modulus.
+    FieldPointNotAMember,
+    /// Point is not on the curve.
+    PointNotOnCurve,
+    /// Point is not in the prime order subgroup.
+    PointNotInSubgroup,
+    /// Pairing input length is not a multiple of [`PAIR_ELEMENT_LEN`].
+    PairLength,
+    /// Failure reported by the hooks.
+    Hook(HookError),
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::FieldPointNotAMember => write!(f, "field point not a member"),
+            Error::PointNotOnCurve => write!(f, "point not on curve"),
+            Error::PointNotInSubgroup => write!(f, "point not in subgroup"),
+            Error::PairLength => write!(f, "invalid pairing input length"),
+            Error::Hook(err) => write!(f, "hook failure: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl From<HookError> for Error {
+    fn from(err: HookError) -> Self {
+        Error::Hook(err)
+    }
+}
+
+/// Point addition on G1 (`ecAdd`).
+///
+/// Input is right padded with zeros, or truncated, to [`ADD_INPUT_LEN`].
+pub fn ec_add<H: CurveHooks>(input: &[u8]) -> Result<[u8; G1_LEN], Error> {
+    let input = right_pad::<ADD_INPUT_LEN>(input);
+    let p1 = read_g1::<H>(&input[..G1_LEN])?;
+    let p2 = read_g1::<H>(&input[G1_LEN..])?;
+
+    let res = G1Projective::<H>::from(p1) + p2;
+    Ok(write_g1(&res.into_affine()))
+}
+
+/// Scalar multiplication on G1 (`ecMul`).
+///
+/// Input is right padded with zeros, or truncated, to [`MUL_INPUT_LEN`].
+pub fn ec_mul<H: CurveHooks>(input: &[u8]) -> Result<[u8; G1_LEN], Error> {
+    let input = right_pad::<MUL_INPUT_LEN>(input);
+    let point = read_g1::<H>(&input[..G1_LEN])?;
+    // Points have prime order `r`, thus reducing the scalar doesn't change the result.
+    let scalar = Fr::from_be_bytes_mod_order(&input[G1_LEN..]);
+
+    let res = G1Projective::<H>::from(point).try_mul(&scalar)?;
+    Ok(write_g1(&res.into_affine()))
+}
+
+/// Pairing product check (`ecPairing`).
+///
+/// Input is a sequence of `(G1, G2)` pairs, each [`PAIR_ELEMENT_LEN`] bytes long.
+/// Returns one, encoded as a 32 bytes big-endian integer, if the product of the
+/// pairings is one, zero otherwise. Empty input succeeds.
+#[allow(clippy::manual_is_multiple_of)]
+pub fn ec_pairing<H: CurveHooks>(input: &[u8]) -> Result<[u8; 32], Error> {
+    if input.len() % PAIR_ELEMENT_LEN != 0 {
+        return Err(Error::PairLength);
+    }
+
+    let mut g1 = Vec::with_capacity(input.len() / PAIR_ELEMENT_LEN);
+    let mut g2 = Vec::with_capacity(input.len() / PAIR_ELEMENT_LEN);
+    for chunk in input.chunks_exact(PAIR_ELEMENT_LEN) {
+        let p = read_g1::<H>(&chunk[..G1_LEN])?;
+        let q = read_g2::<H>(&chunk[G1_LEN..])?;
+        // Pairs containing the point at infinity don't contribute to the product.
+        if !p.is_zero() && !q.is_zero() {
+            g1.push(p);
+            g2.push(q);
+        }
+    }
+
+    let success = g1.is_empty() || Bn254::<H>::pairing_check(g1, g2)?;
+    let mut output = [0; 32];
+    output[31] = success as u8;
+    Ok(output)
+}
+
+fn right_pad<const N: usize>(input: &[u8]) -> [u8; N] {
+    let mut padded = [0; N];
+    let len = input.len().min(N);
+    padded[..len].copy_from_slice(&input[..len]);
+    padded
+}
+
+fn read_fq(input: &[u8]) -> Result<Fq, Error> {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().rev().zip(input.chunks_exact(8)) {
+        *limb = u64::from_be_bytes(chunk.try_into().expect("chunk is 8 bytes long"));
+    }
+    Fq::from_bigint(BigInt(limbs)).ok_or(Error::FieldPointNotAMember)
+}
+
+fn write_fq(value: &Fq, output: &mut [u8]) {
+    let limbs = value.into_bigint().0;
+    for (limb, chunk) in limbs.iter().rev().zip(output.chunks_exact_mut(8)) {
+        chunk.copy_from_slice(&limb.to_be_bytes());
+    }
+}
+
+fn read_fq2(input: &[u8]) -> Result<Fq2, Error> {
+    let c1 = read_fq(&input[..FQ_LEN])?;
+    let c0 = read_fq(&input[FQ_LEN..])?;
+    Ok(Fq2::new(c0, c1))
+}
+
+pub(crate) fn read_g1<H: CurveHooks>(input: &[u8]) -> Result<G1Affine<H>, Error> {
+    let x = read_fq(&input[..FQ_LEN])?;
+    let y = read_fq(&input[FQ_LEN..])?;
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1Affine::<H>::zero());
+    }
+    let point = G1Affine::<H>::new_unchecked(x, y);
+    if !point.is_on_curve() {
+        return Err(Error::PointNotOnCurve);
+    }
+    Ok(point)
+}
+
+pub(crate) fn write_g1<H: CurveHooks>(point: &G1Affine<H>) -> [u8; G1_LEN] {
+    let mut output = [0; G1_LEN];
+    if let Some((x, y)) = point.xy() {
+        write_fq(&x, &mut output[..FQ_LEN]);
+        write_fq(&y, &mut output[FQ_LEN..]);
+    }
+    output
+}
+
+fn read_g2<H: CurveHooks>(input: &[u8]) -> Result<G2Affine<H>, Error> {
+    let x = read_fq2(&input[..2 * FQ_LEN])?;
+    let y = read_fq2(&input[2 * FQ_LEN..])?;
+    if x.is_zero() && y.is_zero() {
+        return Ok(G2Affine::<H>::zero());
+    }
+    let point = G2Affine::<H>::new_unchecked(x, y);
+    if !point.is_on_curve() {
+        return Err(Error::PointNotOnCurve);
+    }
+    if !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Error::PointNotInSubgroup);
+    }
+    Ok(point)
+}
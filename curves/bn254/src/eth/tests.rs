@@ -0,0 +1,188 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::native::NativeHooks;
+use ark_ff::{Field, UniformRand};
+use ark_models_ext::short_weierstrass::{Affine, SWCurveConfig};
+use ark_std::test_rng;
+use hex_literal::hex;
+
+type H = NativeHooks;
+
+const ONE: [u8; 32] = hex!("0000000000000000000000000000000000000000000000000000000000000001");
+const ZERO: [u8; 32] = [0; 32];
+
+// Base field modulus.
+const P: [u8; 32] = hex!("30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47");
+
+// Official vectors from the go-ethereum precompiles test suite.
+const ADD_CHFAST1: [u8; 128] = hex!(
+    "18b18acfb4c2c30276db5411368e7185b311dd124691610c5d3b74034e093dc9"
+    "063c909c4720840cb5134cb9f59fa749755796819658d32efc0d288198f37266"
+    "07c2b7f58a84bd6145f00c9c2bc0bb1a187f20ff2c92963a88019e7c6a014eed"
+    "06614e20c147e940f2d70da3f74c9a17df361706a4485c742bd6788478fa17d7"
+);
+const ADD_CHFAST1_EXPECTED: [u8; 64] = hex!(
+    "2243525c5efd4b9c3d3c45ac0ca3fe4dd85e830a4ce6b65fa1eeaee202839703"
+    "301d1d33be6da8e509df21cc35964723180eed7532537db9ae5e7d48f195c915"
+);
+
+const MUL_CHFAST1: [u8; 96] = hex!(
+    "2bd3e6d0f3b142924f5ca7b49ce5b9d54c4703d7ae5648e61d02268b1a0a9fb7"
+    "21611ce0a6af85915e2f1d70300909ce2e49dfad4a4619c8390cae66cefdb204"
+    "00000000000000000000000000000000000000000000000011138ce750fa15c2"
+);
+const MUL_CHFAST1_EXPECTED: [u8; 64] = hex!(
+    "070a8d6a982153cae4be29d434e8faef8a47b274a053f5a4ee2a6c9c13c31e5c"
+    "031b8ce914eba3a9ffb989f9cdd5b0f01943074bf4f0f315690ec3cec6981afc"
+);
+
+const PAIRING_JEFF1: [u8; 384] = hex!(
+    "1c76476f4def4bb94541d57ebba1193381ffa7aa76ada664dd31c16024c43f59"
+    "3034dd2920f673e204fee2811c678745fc819b55d3e9d294e45c9b03a76aef41"
+    "209dd15ebff5d46c4bd888e51a93cf99a7329636c63514396b4a452003a35bf7"
+    "04bf11ca01483bfa8b34b43561848d28905960114c8ac04049af4b6315a41678"
+    "2bb8324af6cfc93537a2ad1a445cfd0ca2a71acd7ac41fadbf933c2a51be344d"
+    "120a2a4cf30c1bf9845f20c6fe39e07ea2cce61f0c9bb048165fe5e4de877550"
+    "111e129f1cf1097710d41c4ac70fcdfa5ba2023c6ff1cbeac322de49d1b6df7c"
+    "2032c61a830e3c17286de9462bf242fca2883585b93870a73853face6a6bf411"
+    "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2"
+    "1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed"
+    "090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b"
+    "12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa"
+);
+
+fn write_g2(point: &G2Affine<H>) -> [u8; G2_LEN] {
+    let mut output = [0; G2_LEN];
+    if let Some((x, y)) = point.xy() {
+        write_fq(&x.c1, &mut output[..FQ_LEN]);
+        write_fq(&x.c0, &mut output[FQ_LEN..2 * FQ_LEN]);
+        write_fq(&y.c1, &mut output[2 * FQ_LEN..3 * FQ_LEN]);
+        write_fq(&y.c0, &mut output[3 * FQ_LEN..]);
+    }
+    output
+}
+
+#[test]
+fn ec_add_works() {
+    assert_eq!(ec_add::<H>(&ADD_CHFAST1), Ok(ADD_CHFAST1_EXPECTED));
+
+    // Empty and zero inputs sum up to the point at infinity.
+    assert_eq!(ec_add::<H>(&[]), Ok([0; 64]));
+    assert_eq!(ec_add::<H>(&[0; 128]), Ok([0; 64]));
+
+    // Short input is right padded, long input is truncated.
+    assert_eq!(
+        ec_add::<H>(&ADD_CHFAST1[..64]),
+        Ok(ADD_CHFAST1[..64].try_into().unwrap())
+    );
+    let mut long = ADD_CHFAST1.to_vec();
+    long.extend_from_slice(&[0xff; 32]);
+    assert_eq!(ec_add::<H>(&long), Ok(ADD_CHFAST1_EXPECTED));
+}
+
+#[test]
+fn ec_add_rejects_invalid_points() {
+    assert_eq!(ec_add::<H>(&[0x11; 128]), Err(Error::PointNotOnCurve));
+
+    let mut input = ADD_CHFAST1;
+    input[..32].copy_from_slice(&P);
+    assert_eq!(ec_add::<H>(&input), Err(Error::FieldPointNotAMember));
+}
+
+#[test]
+fn ec_mul_works() {
+    assert_eq!(ec_mul::<H>(&MUL_CHFAST1), Ok(MUL_CHFAST1_EXPECTED));
+
+    assert_eq!(ec_mul::<H>(&[]), Ok([0; 64]));
+    let mut input = [0; 96];
+    input[64] = 2;
+    assert_eq!(ec_mul::<H>(&input), Ok([0; 64]));
+
+    // Multiplying by the group order gives the point at infinity.
+    let mut input = MUL_CHFAST1;
+    input[64..].copy_from_slice(&hex!(
+        "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001"
+    ));
+    assert_eq!(ec_mul::<H>(&input), Ok([0; 64]));
+}
+
+#[test]
+fn ec_mul_rejects_invalid_points() {
+    let mut input = [0x11; 96];
+    input[64..].copy_from_slice(&ONE);
+    assert_eq!(ec_mul::<H>(&input), Err(Error::PointNotOnCurve));
+
+    let mut input = MUL_CHFAST1;
+    input[32..64].copy_from_slice(&P);
+    assert_eq!(ec_mul::<H>(&input), Err(Error::FieldPointNotAMember));
+}
+
+#[test]
+fn ec_pairing_works() {
+    assert_eq!(ec_pairing::<H>(&PAIRING_JEFF1), Ok(ONE));
+    assert_eq!(ec_pairing::<H>(&[]), Ok(ONE));
+
+    // Swapping the G1 points breaks the equation.
+    let mut input = PAIRING_JEFF1;
+    input[..64].copy_from_slice(&PAIRING_JEFF1[192..256]);
+    input[192..256].copy_from_slice(&PAIRING_JEFF1[..64]);
+    assert_eq!(ec_pairing::<H>(&input), Ok(ZERO));
+
+    // Pairs containing the point at infinity are skipped.
+    let mut input = PAIRING_JEFF1;
+    input[..64].fill(0);
+    input[256..].fill(0);
+    assert_eq!(ec_pairing::<H>(&input), Ok(ONE));
+
+    let mut rng = test_rng();
+    let p = G1Affine::<H>::rand(&mut rng);
+    let q = G2Affine::<H>::rand(&mut rng);
+    let mut input = [0; 2 * PAIR_ELEMENT_LEN];
+    input[..64].copy_from_slice(&write_g1(&p));
+    input[64..192].copy_from_slice(&write_g2(&q));
+    assert_eq!(ec_pairing::<H>(&input[..192]), Ok(ZERO));
+    input[192..256].copy_from_slice(&write_g1(&-p));
+    input[256..].copy_from_slice(&write_g2(&q));
+    assert_eq!(ec_pairing::<H>(&input), Ok(ONE));
+}
+
+#[test]
+fn ec_pairing_rejects_invalid_input() {
+    assert_eq!(
+        ec_pairing::<H>(&PAIRING_JEFF1[..191]),
+        Err(Error::PairLength)
+    );
+    assert_eq!(ec_pairing::<H>(&[0x11; 192]), Err(Error::PointNotOnCurve));
+
+    let mut input = PAIRING_JEFF1;
+    input[64..96].copy_from_slice(&P);
+    assert_eq!(ec_pairing::<H>(&input), Err(Error::FieldPointNotAMember));
+
+    // G2 point on the twist curve but outside the prime order subgroup.
+    let mut rng = test_rng();
+    let point = loop {
+        let x = Fq2::rand(&mut rng);
+        let y2 = x.square() * x + <crate::g2::Config<H> as SWCurveConfig>::COEFF_B;
+        if let Some(y) = y2.sqrt() {
+            break Affine::new_unchecked(x, y);
+        }
+    };
+    assert!(!point.is_in_correct_subgroup_assuming_on_curve());
+    let mut input = PAIRING_JEFF1;
+    input[64..192].copy_from_slice(&write_g2(&point));
+    assert_eq!(ec_pairing::<H>(&input), Err(Error::PointNotInSubgroup));
+}
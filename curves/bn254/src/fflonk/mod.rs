@@ -0,0 +1,356 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [fflonk](https://eprint.iacr.org/2021/1167.pdf) verifier, compatible with the
+//! proofs of snarkjs.
+//!
+//! Mirrors `fflonk_verify.js` of snarkjs. The PLONK polynomials are packed in three
+//! commitments:
+//! * `C0 = QL(X^8) + X QR(X^8) + ... + X^7 S3(X^8)`, opened at the 8th roots of `xi`;
+//! * `C1 = A(X^4) + X B(X^4) + X^2 C(X^4) + X^3 T0(X^4)`, opened at the 4th roots of
+//!   `xi`;
+//! * `C2 = Z(X^3) + X T1(X^3) + X^2 T2(X^3)`, opened at the cube roots of `xi` and
+//!   of `xi * w`.
+//!
+//! The three openings are batched and checked with one MSM and a single pairing
+//! check
+//!
+//! `e(F - E - J + y * W2, g2) = e(W2, X_2)`.
+//!
+//! Keys and proofs are read from JSON with
+//! [`crate::snarkjs::fflonk_vk_from_json`] and
+//! [`crate::snarkjs::fflonk_proof_from_json`].
+
+use ark_ff::{FftField, Field, One, Zero};
+use ark_models_ext::AffineRepr;
+use ark_std::vec::Vec;
+use educe::Educe;
+
+use crate::{
+    groth16::is_valid,
+    kzg::{self, interpolate, vanishing},
+    plonk::snarkjs::Transcript,
+    CurveHooks, Fr, G1Affine, G1Projective, G2Affine, TryCurveOps,
+};
+
+pub use crate::plonk::Error;
+
+#[cfg(test)]
+pub(crate) mod tests;
+
+/// snarkjs fflonk verifying key.
+#[derive(Educe)]
+#[educe(Clone, Debug, PartialEq, Eq)]
+pub struct VerifyingKey<H: CurveHooks> {
+    /// Number of public inputs.
+    pub n_public: usize,
+    /// Base two logarithm of the domain size.
+    pub power: u32,
+    /// Coset shift of the second wire.
+    pub k1: Fr,
+    /// Coset shift of the third wire.
+    pub k2: Fr,
+    /// Generator of the evaluation domain.
+    pub w: Fr,
+    /// Primitive cube root of unity.
+    pub w3: Fr,
+    /// Primitive 4th root of unity.
+    pub w4: Fr,
+    /// Primitive 8th root of unity.
+    pub w8: Fr,
+    /// Cube root of `w`.
+    pub wr: Fr,
+    /// `tau` in G2.
+    pub x_2: G2Affine<H>,
+    /// Commitment to the selectors and permutation polynomials.
+    pub c0: G1Affine<H>,
+}
+
+/// Evaluations of a snarkjs fflonk proof, at `xi` unless stated otherwise.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Evaluations {
+    /// Left selector.
+    pub ql: Fr,
+    /// Right selector.
+    pub qr: Fr,
+    /// Multiplication selector.
+    pub qm: Fr,
+    /// Output selector.
+    pub qo: Fr,
+    /// Constant selector.
+    pub qc: Fr,
+    /// First permutation.
+    pub s1: Fr,
+    /// Second permutation.
+    pub s2: Fr,
+    /// Third permutation.
+    pub s3: Fr,
+    /// First wire.
+    pub a: Fr,
+    /// Second wire.
+    pub b: Fr,
+    /// Third wire.
+    pub c: Fr,
+    /// Permutation polynomial.
+    pub z: Fr,
+    /// Permutation polynomial at `xi * w`.
+    pub zw: Fr,
+    /// First quotient polynomial at `xi * w`.
+    pub t1w: Fr,
+    /// Second quotient polynomial at `xi * w`.
+    pub t2w: Fr,
+    /// Inverses hint of the Solidity verifier, not needed here.
+    pub inv: Fr,
+}
+
+/// snarkjs fflonk proof.
+#[derive(Educe)]
+#[educe(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Proof<H: CurveHooks> {
+    /// Commitment to the wires and the gate quotient polynomial.
+    pub c1: G1Affine<H>,
+    /// Commitment to the permutation and its quotient polynomials.
+    pub c2: G1Affine<H>,
+    /// Batched quotient commitment.
+    pub w1: G1Affine<H>,
+    /// Opening proof at `y`.
+    pub w2: G1Affine<H>,
+    /// Polynomials evaluations.
+    pub evaluations: Evaluations,
+}
+
+impl Evaluations {
+    /// Evaluations in transcript order, `inv` excluded.
+    fn transcript(&self) -> [Fr; 15] {
+        [
+            self.ql, self.qr, self.qm, self.qo, self.qc, self.s1, self.s2, self.s3, self.a, self.b,
+            self.c, self.z, self.zw, self.t1w, self.t2w,
+        ]
+    }
+}
+
+/// Fiat-Shamir challenges of the protocol.
+pub(crate) struct Challenges {
+    pub(crate) beta: Fr,
+    pub(crate) gamma: Fr,
+    pub(crate) xi_seed: Fr,
+    pub(crate) alpha: Fr,
+    pub(crate) y: Fr,
+}
+
+impl Challenges {
+    pub(crate) fn derive<H: CurveHooks>(
+        vk: &VerifyingKey<H>,
+        proof: &Proof<H>,
+        public_inputs: &[Fr],
+    ) -> Self {
+        let mut transcript = Transcript::new();
+        transcript.add_point(&vk.c0);
+        public_inputs
+            .iter()
+            .for_each(|input| transcript.add_scalar(input));
+        transcript.add_point(&proof.c1);
+        let beta = transcript.challenge();
+
+        transcript.add_scalar(&beta);
+        let gamma = transcript.challenge();
+
+        transcript.add_scalar(&gamma);
+        transcript.add_point(&proof.c2);
+        let xi_seed = transcript.challenge();
+
+        transcript.add_scalar(&xi_seed);
+        for eval in proof.evaluations.transcript() {
+            transcript.add_scalar(&eval);
+        }
+        let alpha = transcript.challenge();
+
+        transcript.add_scalar(&alpha);
+        transcript.add_point(&proof.w1);
+        let y = transcript.challenge();
+
+        Challenges {
+            beta,
+            gamma,
+            xi_seed,
+            alpha,
+            y,
+        }
+    }
+}
+
+/// Opening points, as the cosets `h * <root>` of the roots of unity.
+pub(crate) struct Roots {
+    /// 8th roots of `xi`.
+    pub(crate) h0w8: [Fr; 8],
+    /// 4th roots of `xi`.
+    pub(crate) h1w4: [Fr; 4],
+    /// Cube roots of `xi` followed by the cube roots of `xi * w`.
+    pub(crate) h2w3_h3w3: [Fr; 6],
+}
+
+impl Roots {
+    /// Computes the roots of `xi = xi_seed^24`.
+    pub(crate) fn new<H: CurveHooks>(vk: &VerifyingKey<H>, xi_seed: Fr) -> Self {
+        let h0 = xi_seed.square() * xi_seed;
+        let h1 = h0.square();
+        let h2 = h1 * xi_seed.square();
+        let h2w3: [Fr; 3] = coset(h2, vk.w3);
+        let h3w3: [Fr; 3] = coset(h2 * vk.wr, vk.w3);
+        Roots {
+            h0w8: coset(h0, vk.w8),
+            h1w4: coset(h1, vk.w4),
+            h2w3_h3w3: [h2w3[0], h2w3[1], h2w3[2], h3w3[0], h3w3[1], h3w3[2]],
+        }
+    }
+}
+
+/// Returns `[h, h * root, h * root^2, ...]`.
+fn coset<const N: usize>(h: Fr, root: Fr) -> [Fr; N] {
+    let mut power = h;
+    core::array::from_fn(|_| {
+        let element = power;
+        power *= root;
+        element
+    })
+}
+
+/// Checks that `root` is a primitive root of unity of order `order`, a power of
+/// `prime`.
+fn has_order(root: &Fr, order: u64, prime: u64) -> bool {
+    root.pow([order]).is_one() && !root.pow([order / prime]).is_one()
+}
+
+/// Verifies the proof against the verifying key and the public inputs.
+pub fn verify<H: CurveHooks>(
+    vk: &VerifyingKey<H>,
+    proof: &Proof<H>,
+    public_inputs: &[Fr],
+) -> Result<bool, Error> {
+    if public_inputs.len() != vk.n_public {
+        return Err(Error::InvalidPublicInputsLength {
+            expected: vk.n_public,
+            found: public_inputs.len(),
+        });
+    }
+    let n = 1u64
+        .checked_shl(vk.power)
+        .filter(|_| vk.power <= Fr::TWO_ADICITY)
+        .ok_or(Error::MalformedVerifyingKey)?;
+    let valid_vk = has_order(&vk.w, n, 2)
+        && has_order(&vk.w3, 3, 3)
+        && has_order(&vk.w4, 4, 2)
+        && has_order(&vk.w8, 8, 2)
+        && vk.wr.pow([3]) == vk.w
+        && is_valid(&vk.c0);
+    if !valid_vk {
+        return Err(Error::MalformedVerifyingKey);
+    }
+    let pvk = kzg::prepare_verifier_key(kzg::VerifierKey {
+        g1: G1Affine::<H>::generator(),
+        g2: G2Affine::<H>::generator(),
+        tau_g2: vk.x_2,
+    })?;
+    if ![proof.c1, proof.c2, proof.w1, proof.w2]
+        .iter()
+        .all(is_valid)
+    {
+        return Err(Error::MalformedProof);
+    }
+
+    let Challenges {
+        beta,
+        gamma,
+        xi_seed,
+        alpha,
+        y,
+    } = Challenges::derive(vk, proof, public_inputs);
+    if xi_seed.is_zero() {
+        return Ok(false);
+    }
+    let roots = Roots::new(vk, xi_seed);
+    let xi = roots.h2w3_h3w3[0].pow([3]);
+
+    // Lagrange basis evaluations `L_i(xi) = w^i * (xi^n - 1) / (n * (xi - w^i))`.
+    let zh = xi.pow([n]) - Fr::one();
+    let mut lagrange = Vec::with_capacity(vk.n_public.max(1));
+    let mut w = Fr::one();
+    for _ in 0..vk.n_public.max(1) {
+        let Some(den) = (Fr::from(n) * (xi - w)).inverse() else {
+            return Ok(false);
+        };
+        lagrange.push(w * zh * den);
+        w *= vk.w;
+    }
+    let pi = -public_inputs
+        .iter()
+        .zip(&lagrange)
+        .map(|(input, l)| *input * l)
+        .sum::<Fr>();
+    let Some(zh_inv) = zh.inverse() else {
+        return Ok(false);
+    };
+
+    // Evaluations of `C0`, `C1` and `C2` at their opening points.
+    let e = &proof.evaluations;
+    let horner = |coeffs: &[Fr], x: &Fr| coeffs.iter().rev().fold(Fr::zero(), |acc, c| acc * x + c);
+    let c0 = [e.ql, e.qr, e.qo, e.qm, e.qc, e.s1, e.s2, e.s3];
+    let c0_values = roots.h0w8.map(|root| horner(&c0, &root));
+    let t0 = (e.ql * e.a + e.qr * e.b + e.qm * e.a * e.b + e.qo * e.c + e.qc + pi) * zh_inv;
+    let c1 = [e.a, e.b, e.c, t0];
+    let c1_values = roots.h1w4.map(|root| horner(&c1, &root));
+    let t1 = lagrange[0] * (e.z - Fr::one()) * zh_inv;
+    let betaxi = beta * xi;
+    let t2 = ((e.a + betaxi + gamma)
+        * (e.b + betaxi * vk.k1 + gamma)
+        * (e.c + betaxi * vk.k2 + gamma)
+        * e.z
+        - (e.a + beta * e.s1 + gamma)
+            * (e.b + beta * e.s2 + gamma)
+            * (e.c + beta * e.s3 + gamma)
+            * e.zw)
+        * zh_inv;
+    let c2_xi = [e.z, t1, t2];
+    let c2_xiw = [e.zw, e.t1w, e.t2w];
+    let c2_values: [Fr; 6] = core::array::from_fn(|i| match i < 3 {
+        true => horner(&c2_xi, &roots.h2w3_h3w3[i]),
+        false => horner(&c2_xiw, &roots.h2w3_h3w3[i]),
+    });
+    let r0 = interpolate(&roots.h0w8, &c0_values, y);
+    let r1 = interpolate(&roots.h1w4, &c1_values, y);
+    let r2 = interpolate(&roots.h2w3_h3w3, &c2_values, y);
+
+    // F = C0 + alpha * Z_T0(y) / Z_T1(y) * C1 + alpha^2 * Z_T0(y) / Z_T2(y) * C2,
+    // E = (r0 + ... ) * g1 and J = Z_T0(y) * W1.
+    let z_t0 = vanishing(roots.h0w8.iter(), y);
+    let z_t1 = vanishing(roots.h1w4.iter(), y);
+    let z_t2 = vanishing(roots.h2w3_h3w3.iter(), y);
+    let (Some(z_t1_inv), Some(z_t2_inv)) = (z_t1.inverse(), z_t2.inverse()) else {
+        return Ok(false);
+    };
+    let quotient1 = alpha * z_t0 * z_t1_inv;
+    let quotient2 = alpha.square() * z_t0 * z_t2_inv;
+    let bases = [vk.c0, proof.c1, proof.c2, proof.w1, proof.w2, pvk.vk.g1];
+    let scalars = [
+        Fr::one(),
+        quotient1,
+        quotient2,
+        -z_t0,
+        y,
+        -(r0 + quotient1 * r1 + quotient2 * r2),
+    ];
+    let lhs = G1Projective::<H>::try_msm(&bases, &scalars)?;
+    Ok(kzg::check(&pvk, lhs, proof.w2.into())?)
+}
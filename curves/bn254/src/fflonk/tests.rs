@@ -0,0 +1,273 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::{
+    metering::{GasSchedule, MeteredHooks, ThreadBudget, OUT_OF_GAS},
+    native::NativeHooks,
+    plonk::tests::{Circuit, Poly, Setup, N},
+    Fq,
+};
+use ark_ff::UniformRand;
+use ark_poly::{
+    univariate::DenseOrSparsePolynomial, DenseUVPolynomial, EvaluationDomain, Polynomial,
+};
+use ark_std::{
+    rand::{rngs::StdRng, SeedableRng},
+    vec,
+};
+
+/// Packs the polynomials `p_i` as `sum_i X^i * p_i(X^m)`, `m` being their number.
+fn pack(polys: &[&Poly]) -> Poly {
+    let m = polys.len();
+    let len = polys.iter().map(|poly| poly.coeffs.len()).max().unwrap();
+    let mut coeffs = vec![Fr::zero(); len * m];
+    for (i, poly) in polys.iter().enumerate() {
+        for (j, coeff) in poly.coeffs.iter().enumerate() {
+            coeffs[j * m + i] = *coeff;
+        }
+    }
+    Poly::from_coefficients_vec(coeffs)
+}
+
+/// Divides `poly` by the vanishing polynomial of the roots of `roots`, i.e. by
+/// `prod_i (X^m - roots_i)`, returning the quotient and the remainder.
+fn divide(poly: &Poly, m: usize, roots: &[Fr]) -> (Poly, Poly) {
+    let mut divisor = Poly::from_coefficients_slice(&[Fr::one()]);
+    for root in roots {
+        let mut coeffs = vec![Fr::zero(); m + 1];
+        (coeffs[0], coeffs[m]) = (-*root, Fr::one());
+        divisor = &divisor * &Poly::from_coefficients_vec(coeffs);
+    }
+    DenseOrSparsePolynomial::from(poly)
+        .divide_with_q_and_r(&(&divisor).into())
+        .unwrap()
+}
+
+/// Builds a key for the test circuit and proves it, the same way snarkjs does.
+pub(crate) fn prove<H: CurveHooks>(seed: u64) -> (VerifyingKey<H>, Proof<H>, Vec<Fr>) {
+    let rng = &mut StdRng::seed_from_u64(seed);
+    let setup = Setup::new(rng);
+    let (circuit, x) = Circuit::cubic(Fr::rand(rng), Fr::one());
+    let k = [Fr::one(), Fr::from(2u64), Fr::from(3u64)];
+    let polys = setup.polys(&circuit, &k);
+    let [ql, qr, qm, qo, qc] = &polys.q;
+    let [s1, s2, s3] = &polys.s;
+    let [a, b, c] = &polys.w;
+
+    let w = setup.domain.group_gen();
+    let c0 = pack(&[ql, qr, qo, qm, qc, s1, s2, s3]);
+    let vk = VerifyingKey {
+        n_public: 1,
+        power: N.trailing_zeros(),
+        k1: k[1],
+        k2: k[2],
+        w,
+        // Root of `X^2 + X + 1`.
+        w3: ((-Fr::from(3u64)).sqrt().unwrap() - Fr::one()) / Fr::from(2u64),
+        w4: Fr::get_root_of_unity(4).unwrap(),
+        w8: Fr::get_root_of_unity(8).unwrap(),
+        // `w` has order 8 and `3 * 3 = 1 mod 8`.
+        wr: w.pow([3]),
+        x_2: setup.tau_g2(),
+        c0: setup.commit(&c0),
+    };
+
+    let t0 = setup.divide(&setup.gate(&polys, &Poly::zero()));
+    let c1 = pack(&[a, b, c, &t0]);
+    let c1_cm = setup.commit::<H>(&c1);
+    let mut transcript = Transcript::new();
+    transcript.add_point(&vk.c0);
+    transcript.add_scalar(&x);
+    transcript.add_point(&c1_cm);
+    let beta = transcript.challenge();
+    transcript.add_scalar(&beta);
+    let gamma = transcript.challenge();
+
+    let z = setup.permutation(&circuit, &k, beta, gamma);
+    let [permutation, first_row] = setup.permutation_constraints(&polys, &z, &k, [beta, gamma]);
+    let t1 = setup.divide(&first_row);
+    let t2 = setup.divide(&permutation);
+    let c2 = pack(&[&z, &t1, &t2]);
+    let c2_cm = setup.commit::<H>(&c2);
+    transcript.add_scalar(&gamma);
+    transcript.add_point(&c2_cm);
+    let xi_seed = transcript.challenge();
+
+    let xi = xi_seed.pow([24]);
+    let xiw = xi * w;
+    let [ql, qr, qm, qo, qc, s1, s2, s3, a, b, c, z_xi] =
+        [ql, qr, qm, qo, qc, s1, s2, s3, a, b, c, &z].map(|poly| poly.evaluate(&xi));
+    let evaluations = Evaluations {
+        ql,
+        qr,
+        qm,
+        qo,
+        qc,
+        s1,
+        s2,
+        s3,
+        a,
+        b,
+        c,
+        z: z_xi,
+        zw: z.evaluate(&xiw),
+        t1w: t1.evaluate(&xiw),
+        t2w: t2.evaluate(&xiw),
+        inv: Fr::zero(),
+    };
+    transcript.add_scalar(&xi_seed);
+    for eval in evaluations.transcript() {
+        transcript.add_scalar(&eval);
+    }
+    let alpha = transcript.challenge();
+
+    let (q0, r0) = divide(&c0, 8, &[xi]);
+    let (q1, r1) = divide(&c1, 4, &[xi]);
+    let (q2, r2) = divide(&c2, 3, &[xi, xiw]);
+    let mut w1 = q0;
+    w1 += (alpha, &q1);
+    w1 += (alpha.square(), &q2);
+    let w1_cm = setup.commit::<H>(&w1);
+    transcript.add_scalar(&alpha);
+    transcript.add_point(&w1_cm);
+    let y = transcript.challenge();
+
+    let z_t0 = y.pow([8]) - xi;
+    let z_t1 = y.pow([4]) - xi;
+    let z_t2 = (y.pow([3]) - xi) * (y.pow([3]) - xiw);
+    let opened = |poly: &Poly, r: &Poly| poly - &Poly::from_coefficients_slice(&[r.evaluate(&y)]);
+    let mut l = opened(&c0, &r0);
+    l += (alpha * z_t0 / z_t1, &opened(&c1, &r1));
+    l += (alpha.square() * z_t0 / z_t2, &opened(&c2, &r2));
+    l += (-z_t0, &w1);
+    assert!(l.evaluate(&y).is_zero());
+
+    let proof = Proof {
+        c1: c1_cm,
+        c2: c2_cm,
+        w1: w1_cm,
+        w2: setup.open(&l, y),
+        evaluations,
+    };
+    (vk, proof, vec![x])
+}
+
+fn invalid_point<H: CurveHooks>() -> G1Affine<H> {
+    G1Affine::<H>::new_unchecked(Fq::one(), Fq::one())
+}
+
+#[test]
+fn verify_works() {
+    let (vk, proof, inputs) = prove::<NativeHooks>(0);
+    assert_eq!(verify(&vk, &proof, &inputs), Ok(true));
+
+    // The inverses hint is not used.
+    let mut hint = proof;
+    hint.evaluations.inv = Fr::one();
+    assert_eq!(verify(&vk, &hint, &inputs), Ok(true));
+}
+
+#[test]
+fn verify_rejects_wrong_proofs() {
+    let (vk, proof, inputs) = prove::<NativeHooks>(1);
+    assert_eq!(verify(&vk, &proof, &[inputs[0] + Fr::one()]), Ok(false));
+
+    let mut wrong_eval = proof;
+    wrong_eval.evaluations.t2w += Fr::one();
+    assert_eq!(verify(&vk, &wrong_eval, &inputs), Ok(false));
+    let mut wrong_eval = proof;
+    wrong_eval.evaluations.qm += Fr::one();
+    assert_eq!(verify(&vk, &wrong_eval, &inputs), Ok(false));
+    let swapped = Proof {
+        w1: proof.w2,
+        w2: proof.w1,
+        ..proof
+    };
+    assert_eq!(verify(&vk, &swapped, &inputs), Ok(false));
+    let wrong_commitment = Proof {
+        c1: proof.c2,
+        ..proof
+    };
+    assert_eq!(verify(&vk, &wrong_commitment, &inputs), Ok(false));
+}
+
+#[test]
+fn verify_rejects_malformed_inputs() {
+    let (vk, proof, inputs) = prove::<NativeHooks>(2);
+    assert_eq!(
+        verify(&vk, &proof, &[]),
+        Err(Error::InvalidPublicInputsLength {
+            expected: 1,
+            found: 0
+        })
+    );
+
+    let wrong_keys = [
+        VerifyingKey {
+            power: vk.power - 1,
+            ..vk.clone()
+        },
+        VerifyingKey {
+            w3: Fr::one(),
+            ..vk.clone()
+        },
+        VerifyingKey {
+            w8: vk.w4,
+            ..vk.clone()
+        },
+        VerifyingKey {
+            wr: vk.w,
+            ..vk.clone()
+        },
+        VerifyingKey {
+            c0: invalid_point(),
+            ..vk.clone()
+        },
+    ];
+    for wrong_key in wrong_keys {
+        assert_eq!(
+            verify(&wrong_key, &proof, &inputs),
+            Err(Error::MalformedVerifyingKey)
+        );
+    }
+
+    let invalid_point = Proof {
+        w2: invalid_point(),
+        ..proof
+    };
+    assert_eq!(
+        verify(&vk, &invalid_point, &inputs),
+        Err(Error::MalformedProof)
+    );
+}
+
+#[test]
+fn verify_uses_the_hooks() {
+    type Hooks = MeteredHooks<NativeHooks, ThreadBudget>;
+    let schedule = GasSchedule::EIP_1108;
+
+    // Proving goes through the metered hooks as well.
+    ThreadBudget::reset(u64::MAX);
+    let (vk, proof, inputs) = prove::<Hooks>(3);
+    ThreadBudget::reset(u64::MAX);
+    assert_eq!(verify(&vk, &proof, &inputs), Ok(true));
+    assert_eq!(
+        ThreadBudget::used(),
+        schedule.msm_g1_cost(6) + schedule.ec_pairing_cost(2)
+    );
+    ThreadBudget::reset(schedule.msm_g1_cost(6));
+    assert_eq!(verify(&vk, &proof, &inputs), Err(Error::Hook(OUT_OF_GAS)));
+}
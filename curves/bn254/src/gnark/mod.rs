@@ -0,0 +1,869 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [gnark](https://github.com/Consensys/gnark) binary format.
+//!
+//! Field elements are 32 bytes big-endian integers and `Fq2` elements are encoded
+//! as `c1 || c0`. Points come in two layouts:
+//! * raw: `x || y`, with the point at infinity encoded as all zeros;
+//! * compressed: `x` only, with the top two bits of the first byte flagging whether
+//!   `y` is the lexicographically smallest (`0b10`) or largest (`0b11`) square root,
+//!   or the point at infinity (`0b01`).
+//!
+//! Readers detect the layout of each point from its flags, as gnark does, and check
+//! that the points are on the curve and in the prime order subgroup.
+//!
+//! [`verify_groth16`] verifies gnark Groth16 proofs, including those of circuits
+//! using `Commit`, whose Pedersen commitments are hashed into extra public inputs.
+
+use ark_ff::{BigInt, Field, One, PrimeField, Zero};
+use ark_models_ext::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr, CurveGroup,
+};
+use ark_std::vec::Vec;
+use educe::Educe;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    groth16, Bn254, CurveHooks, Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine, TryCurveOps,
+    TryPairing,
+};
+#[cfg(feature = "plonk")]
+use crate::{kzg, plonk};
+
+#[cfg(test)]
+mod tests;
+
+/// Length of a compressed G1 point.
+pub const G1_COMPRESSED_LEN: usize = 32;
+/// Length of a raw G1 point.
+pub const G1_RAW_LEN: usize = 64;
+/// Length of a compressed G2 point.
+pub const G2_COMPRESSED_LEN: usize = 64;
+/// Length of a raw G2 point.
+pub const G2_RAW_LEN: usize = 128;
+
+const MASK: u8 = 0b11 << 6;
+const RAW: u8 = 0b00 << 6;
+const COMPRESSED_SMALLEST: u8 = 0b10 << 6;
+const COMPRESSED_LARGEST: u8 = 0b11 << 6;
+const COMPRESSED_INFINITY: u8 = 0b01 << 6;
+
+/// Domain separation tag used to hash Groth16 commitments into public inputs.
+pub const COMMITMENT_DST: &[u8] = b"bsb22-commitment";
+/// Domain separation tag used to derive the commitments folding challenge.
+pub const COMMITMENT_CHALLENGE_DST: &[u8] = b"G16-BSB22";
+
+/// Error returned by the decoders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// Input is shorter than expected or has trailing bytes.
+    InvalidLength,
+    /// Flag bits don't match the expected layout.
+    InvalidFlags,
+    /// Number is not a canonical field element.
+    InvalidFieldElement,
+    /// Point is not on the curve.
+    PointNotOnCurve,
+    /// Point is not in the prime order subgroup.
+    PointNotInSubgroup,
+    /// Index of a committed public input is out of range.
+    InvalidIndex,
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::InvalidLength => write!(f, "invalid length"),
+            Error::InvalidFlags => write!(f, "invalid flags"),
+            Error::InvalidFieldElement => write!(f, "invalid field element"),
+            Error::PointNotOnCurve => write!(f, "point not on curve"),
+            Error::PointNotInSubgroup => write!(f, "point not in subgroup"),
+            Error::InvalidIndex => write!(f, "invalid index"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Pedersen commitment verifying key.
+#[derive(Educe)]
+#[educe(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CommitmentKey<H: CurveHooks> {
+    /// `g` in G2.
+    pub g: G2Affine<H>,
+    /// `-g^(1/sigma)` in G2.
+    pub g_sigma_neg: G2Affine<H>,
+}
+
+/// gnark Groth16 verifying key.
+#[derive(Educe)]
+#[educe(Clone, Debug, PartialEq, Eq)]
+pub struct Groth16VerifyingKey<H: CurveHooks> {
+    /// Verifying key, whose `gamma_abc_g1` holds gnark's `K`.
+    pub vk: groth16::VerifyingKey<H>,
+    /// `beta` in G1, unused by the verifier.
+    pub beta_g1: G1Affine<H>,
+    /// `delta` in G1, unused by the verifier.
+    pub delta_g1: G1Affine<H>,
+    /// For each commitment, indexes of the committed public inputs, counting the
+    /// constant wire and the previous commitments hashes.
+    pub public_and_commitment_committed: Vec<Vec<usize>>,
+    /// For each commitment, the Pedersen verifying key.
+    pub commitment_keys: Vec<CommitmentKey<H>>,
+}
+
+/// gnark Groth16 proof.
+#[derive(Educe)]
+#[educe(Clone, Debug, PartialEq, Eq)]
+pub struct Groth16Proof<H: CurveHooks> {
+    /// The `A`, `B` and `C` proof points.
+    pub proof: groth16::Proof<H>,
+    /// Pedersen commitments.
+    pub commitments: Vec<G1Affine<H>>,
+    /// Batched proof of knowledge of the commitments openings.
+    pub commitment_pok: G1Affine<H>,
+}
+
+/// Encodes the G1 point in the compressed layout.
+pub fn g1_to_compressed<H: CurveHooks>(point: &G1Affine<H>) -> [u8; G1_COMPRESSED_LEN] {
+    let mut output = [0; G1_COMPRESSED_LEN];
+    match point.xy() {
+        Some((x, y)) => {
+            write_fq(&x, &mut output);
+            output[0] |= compressed_flag(fq_is_largest(&y));
+        }
+        None => output[0] = COMPRESSED_INFINITY,
+    }
+    output
+}
+
+/// Encodes the G1 point in the raw layout.
+pub fn g1_to_raw<H: CurveHooks>(point: &G1Affine<H>) -> [u8; G1_RAW_LEN] {
+    let mut output = [0; G1_RAW_LEN];
+    if let Some((x, y)) = point.xy() {
+        write_fq(&x, &mut output[..32]);
+        write_fq(&y, &mut output[32..]);
+    }
+    output
+}
+
+/// Encodes the G2 point in the compressed layout.
+pub fn g2_to_compressed<H: CurveHooks>(point: &G2Affine<H>) -> [u8; G2_COMPRESSED_LEN] {
+    let mut output = [0; G2_COMPRESSED_LEN];
+    match point.xy() {
+        Some((x, y)) => {
+            write_fq2(&x, &mut output);
+            output[0] |= compressed_flag(fq2_is_largest(&y));
+        }
+        None => output[0] = COMPRESSED_INFINITY,
+    }
+    output
+}
+
+/// Encodes the G2 point in the raw layout.
+pub fn g2_to_raw<H: CurveHooks>(point: &G2Affine<H>) -> [u8; G2_RAW_LEN] {
+    let mut output = [0; G2_RAW_LEN];
+    if let Some((x, y)) = point.xy() {
+        write_fq2(&x, &mut output[..64]);
+        write_fq2(&y, &mut output[64..]);
+    }
+    output
+}
+
+/// Decodes a G1 point in the compressed layout.
+pub fn g1_from_compressed<H: CurveHooks>(
+    bytes: &[u8; G1_COMPRESSED_LEN],
+) -> Result<G1Affine<H>, Error> {
+    decompress(bytes, read_fq, fq_is_largest)
+}
+
+/// Decodes a G1 point in the raw layout.
+pub fn g1_from_raw<H: CurveHooks>(bytes: &[u8; G1_RAW_LEN]) -> Result<G1Affine<H>, Error> {
+    if bytes[0] & MASK != RAW {
+        return Err(Error::InvalidFlags);
+    }
+    let (x, y) = bytes.split_at(32);
+    check_point(read_fq(x)?, read_fq(y)?)
+}
+
+/// Decodes a G2 point in the compressed layout.
+pub fn g2_from_compressed<H: CurveHooks>(
+    bytes: &[u8; G2_COMPRESSED_LEN],
+) -> Result<G2Affine<H>, Error> {
+    decompress(bytes, read_fq2, fq2_is_largest)
+}
+
+/// Decodes a G2 point in the raw layout.
+pub fn g2_from_raw<H: CurveHooks>(bytes: &[u8; G2_RAW_LEN]) -> Result<G2Affine<H>, Error> {
+    if bytes[0] & MASK != RAW {
+        return Err(Error::InvalidFlags);
+    }
+    let (x, y) = bytes.split_at(64);
+    check_point(read_fq2(x)?, read_fq2(y)?)
+}
+
+/// Reads a Groth16 verifying key as written by gnark's `WriteTo` (compressed) or
+/// `WriteRawTo` (raw).
+///
+/// Keys written by gnark versions predating commitments, which end right after
+/// `K`, are accepted as well.
+pub fn groth16_vk_from_bytes<H: CurveHooks>(bytes: &[u8]) -> Result<Groth16VerifyingKey<H>, Error> {
+    let mut reader = Reader(bytes);
+    let alpha_g1 = reader.g1()?;
+    let beta_g1 = reader.g1()?;
+    let beta_g2 = reader.g2()?;
+    let gamma_g2 = reader.g2()?;
+    let delta_g1 = reader.g1()?;
+    let delta_g2 = reader.g2()?;
+    let k = reader.vec(Reader::g1)?;
+    if k.is_empty() {
+        return Err(Error::InvalidLength);
+    }
+
+    let mut public_and_commitment_committed = Vec::new();
+    let mut commitment_keys = Vec::new();
+    if !reader.0.is_empty() {
+        // Indexes are written as `[][]uint64`.
+        public_and_commitment_committed = reader.vec(|reader| reader.vec(Reader::usize))?;
+        let valid = 1..k.len();
+        if !public_and_commitment_committed
+            .iter()
+            .flatten()
+            .all(|index| valid.contains(index))
+        {
+            return Err(Error::InvalidIndex);
+        }
+        commitment_keys = reader.vec(|reader| {
+            Ok(CommitmentKey {
+                g: reader.g2()?,
+                g_sigma_neg: reader.g2()?,
+            })
+        })?;
+    }
+    reader.finish()?;
+
+    Ok(Groth16VerifyingKey {
+        vk: groth16::VerifyingKey {
+            alpha_g1,
+            beta_g2,
+            gamma_g2,
+            delta_g2,
+            gamma_abc_g1: k,
+        },
+        beta_g1,
+        delta_g1,
+        public_and_commitment_committed,
+        commitment_keys,
+    })
+}
+
+/// Writes a Groth16 verifying key as gnark's `WriteTo` (compressed) or `WriteRawTo`
+/// (raw) do.
+pub fn groth16_vk_to_bytes<H: CurveHooks>(
+    vk: &Groth16VerifyingKey<H>,
+    compressed: bool,
+) -> Vec<u8> {
+    let mut writer = Writer {
+        output: Vec::new(),
+        compressed,
+    };
+    writer.g1(&vk.vk.alpha_g1);
+    writer.g1(&vk.beta_g1);
+    writer.g2(&vk.vk.beta_g2);
+    writer.g2(&vk.vk.gamma_g2);
+    writer.g1(&vk.delta_g1);
+    writer.g2(&vk.vk.delta_g2);
+    writer.u32(vk.vk.gamma_abc_g1.len());
+    vk.vk.gamma_abc_g1.iter().for_each(|point| writer.g1(point));
+    writer.u32(vk.public_and_commitment_committed.len());
+    for indexes in &vk.public_and_commitment_committed {
+        writer.u32(indexes.len());
+        indexes.iter().for_each(|index| writer.u64(*index as u64));
+    }
+    writer.u32(vk.commitment_keys.len());
+    for key in &vk.commitment_keys {
+        writer.g2(&key.g);
+        writer.g2(&key.g_sigma_neg);
+    }
+    writer.output
+}
+
+/// Reads a Groth16 proof as written by gnark's `WriteTo` (compressed) or
+/// `WriteRawTo` (raw).
+///
+/// Proofs made of the `A`, `B` and `C` points only, as exported for Solidity
+/// verifiers of circuits without commitments, are accepted as well.
+pub fn groth16_proof_from_bytes<H: CurveHooks>(bytes: &[u8]) -> Result<Groth16Proof<H>, Error> {
+    let mut reader = Reader(bytes);
+    let proof = groth16::Proof {
+        a: reader.g1()?,
+        b: reader.g2()?,
+        c: reader.g1()?,
+    };
+    let mut commitments = Vec::new();
+    let mut commitment_pok = G1Affine::<H>::zero();
+    if !reader.0.is_empty() {
+        commitments = reader.vec(Reader::g1)?;
+        commitment_pok = reader.g1()?;
+    }
+    reader.finish()?;
+    Ok(Groth16Proof {
+        proof,
+        commitments,
+        commitment_pok,
+    })
+}
+
+/// Writes a Groth16 proof as gnark's `WriteTo` (compressed) or `WriteRawTo` (raw) do.
+pub fn groth16_proof_to_bytes<H: CurveHooks>(proof: &Groth16Proof<H>, compressed: bool) -> Vec<u8> {
+    let mut writer = Writer {
+        output: Vec::new(),
+        compressed,
+    };
+    writer.g1(&proof.proof.a);
+    writer.g2(&proof.proof.b);
+    writer.g1(&proof.proof.c);
+    writer.u32(proof.commitments.len());
+    proof.commitments.iter().for_each(|point| writer.g1(point));
+    writer.g1(&proof.commitment_pok);
+    writer.output
+}
+
+/// Length of the precomputed KZG pairing lines serialized in gnark PLONK verifying
+/// keys, which are not needed here.
+#[cfg(feature = "plonk")]
+const KZG_LINES_LEN: usize = 33788;
+/// Length of the SP1 Solidity PLONK proof without BSB22 commitments.
+#[cfg(feature = "plonk")]
+const SOLIDITY_PLONK_PROOF_LEN: usize = 6 * G1_RAW_LEN + 5 * 32 + G1_RAW_LEN + 32 + 2 * G1_RAW_LEN;
+
+/// Reads a PLONK verifying key as written by gnark's `WriteTo` (compressed) or
+/// `WriteRawTo` (raw).
+///
+/// The precomputed KZG pairing lines of recent gnark versions are skipped.
+#[cfg(feature = "plonk")]
+pub fn plonk_vk_from_bytes<H: CurveHooks>(
+    bytes: &[u8],
+) -> Result<plonk::gnark::VerifyingKey<H>, Error> {
+    let mut reader = Reader(bytes);
+    let size = reader.u64()?;
+    let size_inv = reader.fr()?;
+    let generator = reader.fr()?;
+    let nb_public_variables = reader.usize()?;
+    let coset_shift = reader.fr()?;
+    let s = [reader.g1()?, reader.g1()?, reader.g1()?];
+    let ql = reader.g1()?;
+    let qr = reader.g1()?;
+    let qm = reader.g1()?;
+    let qo = reader.g1()?;
+    let qk = reader.g1()?;
+    let qcp = reader.vec(Reader::g1)?;
+    let kzg = kzg::VerifierKey {
+        g1: reader.g1()?,
+        g2: reader.g2()?,
+        tau_g2: reader.g2()?,
+    };
+    // Older gnark versions don't serialize the lines, which are followed by the
+    // commitment constraint indexes.
+    let has_lines = match reader.0.get(..8) {
+        Some(len) => {
+            let len = u64::from_be_bytes(len.try_into().expect("8 bytes"));
+            len.checked_mul(8) != Some(reader.0.len() as u64 - 8)
+        }
+        None => true,
+    };
+    if has_lines {
+        reader.take::<KZG_LINES_LEN>()?;
+    }
+    let len = reader.usize()?;
+    if reader.0.len() / 8 != len {
+        return Err(Error::InvalidLength);
+    }
+    let commitment_constraint_indexes = (0..len)
+        .map(|_| reader.usize())
+        .collect::<Result<Vec<_>, _>>()?;
+    reader.finish()?;
+
+    Ok(plonk::gnark::VerifyingKey {
+        size,
+        size_inv,
+        generator,
+        nb_public_variables,
+        coset_shift,
+        s,
+        ql,
+        qr,
+        qm,
+        qo,
+        qk,
+        qcp,
+        kzg,
+        commitment_constraint_indexes,
+    })
+}
+
+/// Reads a PLONK proof as written by gnark's `WriteTo` (compressed) or `WriteRawTo`
+/// (raw).
+#[cfg(feature = "plonk")]
+pub fn plonk_proof_from_bytes<H: CurveHooks>(
+    bytes: &[u8],
+) -> Result<plonk::gnark::Proof<H>, Error> {
+    let mut reader = Reader(bytes);
+    let lro = [reader.g1()?, reader.g1()?, reader.g1()?];
+    let z = reader.g1()?;
+    let h = [reader.g1()?, reader.g1()?, reader.g1()?];
+    let batched_proof = reader.g1()?;
+    let claimed_values = reader.vec(Reader::fr)?;
+    let z_shifted_proof = reader.g1()?;
+    let z_shifted_value = reader.fr()?;
+    let bsb22_commitments = reader.vec(Reader::g1)?;
+    reader.finish()?;
+    Ok(plonk::gnark::Proof {
+        lro,
+        z,
+        h,
+        bsb22_commitments,
+        claimed_values,
+        batched_proof,
+        z_shifted_value,
+        z_shifted_proof,
+    })
+}
+
+/// Writes a PLONK proof as gnark's `WriteTo` (compressed) or `WriteRawTo` (raw) do.
+#[cfg(feature = "plonk")]
+pub fn plonk_proof_to_bytes<H: CurveHooks>(
+    proof: &plonk::gnark::Proof<H>,
+    compressed: bool,
+) -> Vec<u8> {
+    let mut writer = Writer {
+        output: Vec::new(),
+        compressed,
+    };
+    proof.lro.iter().for_each(|point| writer.g1(point));
+    writer.g1(&proof.z);
+    proof.h.iter().for_each(|point| writer.g1(point));
+    writer.g1(&proof.batched_proof);
+    writer.u32(proof.claimed_values.len());
+    proof
+        .claimed_values
+        .iter()
+        .for_each(|value| writer.fr(value));
+    writer.g1(&proof.z_shifted_proof);
+    writer.fr(&proof.z_shifted_value);
+    writer.u32(proof.bsb22_commitments.len());
+    proof
+        .bsb22_commitments
+        .iter()
+        .for_each(|point| writer.g1(point));
+    writer.output
+}
+
+/// Reads a PLONK proof in the layout of gnark's Solidity verifier, as used by SP1:
+/// `L || R || O || H0 || H1 || H2 || l || r || o || s1 || s2 || Z || z(w zeta) ||
+/// batched proof || shifted proof`, followed by the BSB22 selectors evaluations and
+/// then the BSB22 commitments. Points are raw.
+#[cfg(feature = "plonk")]
+pub fn plonk_proof_from_solidity_bytes<H: CurveHooks>(
+    bytes: &[u8],
+) -> Result<plonk::gnark::Proof<H>, Error> {
+    let extra = bytes
+        .len()
+        .checked_sub(SOLIDITY_PLONK_PROOF_LEN)
+        .ok_or(Error::InvalidLength)?;
+    if extra % (32 + G1_RAW_LEN) != 0 {
+        return Err(Error::InvalidLength);
+    }
+    let nb_commitments = extra / (32 + G1_RAW_LEN);
+
+    let mut reader = Reader(bytes);
+    let lro = [reader.raw_g1()?, reader.raw_g1()?, reader.raw_g1()?];
+    let h = [reader.raw_g1()?, reader.raw_g1()?, reader.raw_g1()?];
+    let mut claimed_values = (0..5).map(|_| reader.fr()).collect::<Result<Vec<_>, _>>()?;
+    let z = reader.raw_g1()?;
+    let z_shifted_value = reader.fr()?;
+    let batched_proof = reader.raw_g1()?;
+    let z_shifted_proof = reader.raw_g1()?;
+    for _ in 0..nb_commitments {
+        claimed_values.push(reader.fr()?);
+    }
+    let bsb22_commitments = (0..nb_commitments)
+        .map(|_| reader.raw_g1())
+        .collect::<Result<Vec<_>, _>>()?;
+    reader.finish()?;
+    Ok(plonk::gnark::Proof {
+        lro,
+        z,
+        h,
+        bsb22_commitments,
+        claimed_values,
+        batched_proof,
+        z_shifted_value,
+        z_shifted_proof,
+    })
+}
+
+/// Reads a canonical big-endian scalar field element.
+pub fn fr_from_bytes(bytes: &[u8; 32]) -> Result<Fr, Error> {
+    read_prime(bytes)
+}
+
+/// Writes a scalar field element as big-endian bytes.
+pub fn fr_to_bytes(value: &Fr) -> [u8; 32] {
+    let mut output = [0; 32];
+    write_prime(value, &mut output);
+    output
+}
+
+/// Hashes `msg` to a scalar field element as gnark's `fr.Hash`, i.e. by reducing
+/// 48 bytes of `expand_message_xmd` with SHA-256 (RFC 9380).
+pub fn hash_to_fr(msg: &[u8], dst: &[u8]) -> Fr {
+    let mut bytes = [0; 48];
+    expand_message_xmd(msg, dst, &mut bytes);
+    Fr::from_be_bytes_mod_order(&bytes)
+}
+
+/// Verifies a gnark Groth16 proof against the verifying key and the public inputs.
+///
+/// For circuits using `Commit` each commitment is hashed, together with the public
+/// inputs it commits to, into an extra public input, and the proof of knowledge of
+/// the commitments openings is checked with an additional pairing check:
+///
+/// `e(pok, g) * prod_i e(r^i * D_i, g_sigma_neg_i) = 1`
+///
+/// where `r` is derived from the commitments hashes.
+pub fn verify_groth16<H: CurveHooks>(
+    vk: &Groth16VerifyingKey<H>,
+    proof: &Groth16Proof<H>,
+    public_inputs: &[Fr],
+) -> Result<bool, groth16::Error> {
+    let committed = &vk.public_and_commitment_committed;
+    if vk.commitment_keys.len() != committed.len() {
+        return Err(groth16::Error::MalformedVerifyingKey);
+    }
+    if proof.commitments.len() != committed.len() {
+        return Err(groth16::Error::MalformedProof);
+    }
+    let expected = vk
+        .vk
+        .num_public_inputs()
+        .checked_sub(committed.len())
+        .ok_or(groth16::Error::MalformedVerifyingKey)?;
+    if public_inputs.len() != expected {
+        return Err(groth16::Error::InvalidPublicInputsLength {
+            expected,
+            found: public_inputs.len(),
+        });
+    }
+    let pvk = groth16::prepare_verifying_key(vk.vk.clone())?;
+    let commitments_valid =
+        proof.commitments.iter().all(groth16::is_valid) && groth16::is_valid(&proof.commitment_pok);
+    if !commitments_valid {
+        return Err(groth16::Error::MalformedProof);
+    }
+
+    let mut inputs = public_inputs.to_vec();
+    let mut hashes = Vec::with_capacity(32 * committed.len());
+    for (commitment, indexes) in proof.commitments.iter().zip(committed) {
+        let mut prehash = g1_to_raw(commitment).to_vec();
+        for index in indexes {
+            // Indexes count the constant wire as well, and may refer to the hashes
+            // of previous commitments.
+            let input = index
+                .checked_sub(1)
+                .and_then(|index| inputs.get(index))
+                .ok_or(groth16::Error::MalformedVerifyingKey)?;
+            prehash.extend_from_slice(&fr_to_bytes(input));
+        }
+        let hash = hash_to_fr(&prehash, COMMITMENT_DST);
+        hashes.extend_from_slice(&fr_to_bytes(&hash));
+        inputs.push(hash);
+    }
+
+    if let Some((first, keys)) = vk.commitment_keys.split_first() {
+        if keys.iter().any(|key| key.g != first.g) {
+            return Err(groth16::Error::MalformedVerifyingKey);
+        }
+        let challenge = hash_to_fr(&hashes, COMMITMENT_CHALLENGE_DST);
+        let mut g1 = Vec::with_capacity(committed.len() + 1);
+        let mut power = Fr::one();
+        for commitment in &proof.commitments {
+            let folded = match power.is_one() {
+                true => *commitment,
+                false => commitment.into_group().try_mul(&power)?.into_affine(),
+            };
+            g1.push(folded);
+            power *= challenge;
+        }
+        g1.push(proof.commitment_pok);
+        let g2 = vk
+            .commitment_keys
+            .iter()
+            .map(|key| key.g_sigma_neg)
+            .chain([first.g]);
+        if !Bn254::<H>::pairing_check(g1, g2)? {
+            return Ok(false);
+        }
+    }
+
+    let prepared_inputs = groth16::prepare_inputs(&pvk, &inputs)?
+        + proof
+            .commitments
+            .iter()
+            .fold(G1Projective::<H>::zero(), |acc, commitment| {
+                acc + commitment
+            });
+    groth16::verify_proof_with_prepared_inputs(&pvk, &proof.proof, &prepared_inputs)
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<&[u8; N], Error> {
+        if self.0.len() < N {
+            return Err(Error::InvalidLength);
+        }
+        let (head, tail) = self.0.split_at(N);
+        self.0 = tail;
+        Ok(head.try_into().expect("head is N bytes long"))
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        self.take().map(|bytes| u32::from_be_bytes(*bytes))
+    }
+
+    fn u64(&mut self) -> Result<u64, Error> {
+        self.take().map(|bytes| u64::from_be_bytes(*bytes))
+    }
+
+    fn usize(&mut self) -> Result<usize, Error> {
+        usize::try_from(self.u64()?).map_err(|_| Error::InvalidLength)
+    }
+
+    #[cfg(feature = "plonk")]
+    fn fr(&mut self) -> Result<Fr, Error> {
+        fr_from_bytes(self.take()?)
+    }
+
+    #[cfg(feature = "plonk")]
+    fn raw_g1<H: CurveHooks>(&mut self) -> Result<G1Affine<H>, Error> {
+        g1_from_raw(self.take()?)
+    }
+
+    fn vec<T>(&mut self, read: impl Fn(&mut Self) -> Result<T, Error>) -> Result<Vec<T>, Error> {
+        let len = self.u32()? as usize;
+        // Every item takes at least four bytes, bound the allocation accordingly.
+        let mut items = Vec::with_capacity(len.min(self.0.len() / 4));
+        for _ in 0..len {
+            items.push(read(self)?);
+        }
+        Ok(items)
+    }
+
+    fn is_compressed(&self) -> Result<bool, Error> {
+        let first = self.0.first().ok_or(Error::InvalidLength)?;
+        Ok(first & MASK != RAW)
+    }
+
+    fn g1<H: CurveHooks>(&mut self) -> Result<G1Affine<H>, Error> {
+        match self.is_compressed()? {
+            true => g1_from_compressed(self.take()?),
+            false => g1_from_raw(self.take()?),
+        }
+    }
+
+    fn g2<H: CurveHooks>(&mut self) -> Result<G2Affine<H>, Error> {
+        match self.is_compressed()? {
+            true => g2_from_compressed(self.take()?),
+            false => g2_from_raw(self.take()?),
+        }
+    }
+
+    fn finish(self) -> Result<(), Error> {
+        match self.0.is_empty() {
+            true => Ok(()),
+            false => Err(Error::InvalidLength),
+        }
+    }
+}
+
+struct Writer {
+    output: Vec<u8>,
+    compressed: bool,
+}
+
+impl Writer {
+    fn u32(&mut self, value: usize) {
+        let value = u32::try_from(value).expect("gnark lengths fit in u32");
+        self.output.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.output.extend_from_slice(&value.to_be_bytes());
+    }
+
+    #[cfg(feature = "plonk")]
+    fn fr(&mut self, value: &Fr) {
+        self.output.extend(fr_to_bytes(value));
+    }
+
+    fn g1<H: CurveHooks>(&mut self, point: &G1Affine<H>) {
+        match self.compressed {
+            true => self.output.extend_from_slice(&g1_to_compressed(point)),
+            false => self.output.extend_from_slice(&g1_to_raw(point)),
+        }
+    }
+
+    fn g2<H: CurveHooks>(&mut self, point: &G2Affine<H>) {
+        match self.compressed {
+            true => self.output.extend_from_slice(&g2_to_compressed(point)),
+            false => self.output.extend_from_slice(&g2_to_raw(point)),
+        }
+    }
+}
+
+/// `expand_message_xmd` with SHA-256 (RFC 9380, section 5.3.1).
+fn expand_message_xmd(msg: &[u8], dst: &[u8], output: &mut [u8]) {
+    const BLOCK_LEN: usize = 64;
+    const HASH_LEN: usize = 32;
+    let len = u16::try_from(output.len()).expect("output fits in 255 hashes");
+    let ell = output.len().div_ceil(HASH_LEN);
+    assert!(ell <= 255 && dst.len() <= 255);
+    let dst_len = [dst.len() as u8];
+
+    let b0 = Sha256::new()
+        .chain_update([0; BLOCK_LEN])
+        .chain_update(msg)
+        .chain_update(len.to_be_bytes())
+        .chain_update([0])
+        .chain_update(dst)
+        .chain_update(dst_len)
+        .finalize();
+    let mut bi = Sha256::new()
+        .chain_update(b0)
+        .chain_update([1])
+        .chain_update(dst)
+        .chain_update(dst_len)
+        .finalize();
+    for (i, chunk) in output.chunks_mut(HASH_LEN).enumerate() {
+        if i > 0 {
+            let mut xored = b0;
+            xored.iter_mut().zip(bi).for_each(|(x, b)| *x ^= b);
+            bi = Sha256::new()
+                .chain_update(xored)
+                .chain_update([i as u8 + 1])
+                .chain_update(dst)
+                .chain_update(dst_len)
+                .finalize();
+        }
+        chunk.copy_from_slice(&bi[..chunk.len()]);
+    }
+}
+
+fn compressed_flag(largest: bool) -> u8 {
+    match largest {
+        true => COMPRESSED_LARGEST,
+        false => COMPRESSED_SMALLEST,
+    }
+}
+
+fn decompress<P: SWCurveConfig>(
+    bytes: &[u8],
+    read: impl Fn(&[u8]) -> Result<P::BaseField, Error>,
+    is_largest: impl Fn(&P::BaseField) -> bool,
+) -> Result<Affine<P>, Error> {
+    let flag = bytes[0] & MASK;
+    let mut bytes = bytes.to_vec();
+    bytes[0] &= !MASK;
+    match flag {
+        COMPRESSED_INFINITY if bytes.iter().all(|byte| *byte == 0) => Ok(Affine::identity()),
+        COMPRESSED_SMALLEST | COMPRESSED_LARGEST => {
+            let x = read(&bytes)?;
+            let y2 = x.square() * x + P::mul_by_a(x) + P::COEFF_B;
+            let mut y = y2.sqrt().ok_or(Error::PointNotOnCurve)?;
+            if is_largest(&y) != (flag == COMPRESSED_LARGEST) {
+                y = -y;
+            }
+            check_point(x, y)
+        }
+        _ => Err(Error::InvalidFlags),
+    }
+}
+
+fn check_point<P: SWCurveConfig>(x: P::BaseField, y: P::BaseField) -> Result<Affine<P>, Error> {
+    if x.is_zero() && y.is_zero() {
+        return Ok(Affine::identity());
+    }
+    let point = Affine::new_unchecked(x, y);
+    if !point.is_on_curve() {
+        return Err(Error::PointNotOnCurve);
+    }
+    if !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Error::PointNotInSubgroup);
+    }
+    Ok(point)
+}
+
+fn read_prime<F: PrimeField<BigInt = BigInt<4>>>(bytes: &[u8]) -> Result<F, Error> {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().rev().zip(bytes.chunks_exact(8)) {
+        *limb = u64::from_be_bytes(chunk.try_into().expect("chunk is 8 bytes long"));
+    }
+    F::from_bigint(BigInt(limbs)).ok_or(Error::InvalidFieldElement)
+}
+
+fn write_prime<F: PrimeField<BigInt = BigInt<4>>>(value: &F, output: &mut [u8]) {
+    let limbs = value.into_bigint().0;
+    for (limb, chunk) in limbs.iter().rev().zip(output.chunks_exact_mut(8)) {
+        chunk.copy_from_slice(&limb.to_be_bytes());
+    }
+}
+
+fn read_fq(bytes: &[u8]) -> Result<Fq, Error> {
+    read_prime(bytes)
+}
+
+fn write_fq(value: &Fq, output: &mut [u8]) {
+    write_prime(value, output)
+}
+
+fn read_fq2(bytes: &[u8]) -> Result<Fq2, Error> {
+    let c1 = read_fq(&bytes[..32])?;
+    let c0 = read_fq(&bytes[32..64])?;
+    Ok(Fq2::new(c0, c1))
+}
+
+fn write_fq2(value: &Fq2, output: &mut [u8]) {
+    write_fq(&value.c1, &mut output[..32]);
+    write_fq(&value.c0, &mut output[32..64]);
+}
+
+/// Whether `value` is greater than `-value`, i.e. than `(p - 1) / 2`.
+fn fq_is_largest(value: &Fq) -> bool {
+    value.into_bigint() > Fq::MODULUS_MINUS_ONE_DIV_TWO
+}
+
+/// Lexicographic comparison of `Fq2` as defined by gnark: `c0` first, `c1` when
+/// `c0` is zero.
+fn fq2_is_largest(value: &Fq2) -> bool {
+    match value.c0.is_zero() {
+        true => fq_is_largest(&value.c1),
+        false => fq_is_largest(&value.c0),
+    }
+}
@@ -0,0 +1,469 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::{
+    groth16::tests::setup_and_prove,
+    native::{G1Native, G2Native, NativeHooks},
+    FromArk, ToArk,
+};
+use ark_models_ext::{short_weierstrass::Affine, CurveGroup};
+use ark_std::{test_rng, vec, UniformRand};
+
+type G1 = G1Affine<NativeHooks>;
+type G2 = G2Affine<NativeHooks>;
+
+/// Groth16 verifying key of SP1 v5, as written by gnark `WriteTo`.
+const SP1_GROTH16_VK: &[u8] = include_bytes!("../zkvm/sp1_groth16_vk.bin");
+/// PLONK verifying key of SP1 v5, as written by gnark `WriteTo`.
+#[cfg(feature = "plonk")]
+const SP1_PLONK_VK: &[u8] = include_bytes!("test-data/plonk_vk.bin");
+
+fn random_points() -> (G1, G2) {
+    let mut rng = test_rng();
+    let g1 = Affine::from_ark(G1Native::rand(&mut rng).to_ark().into());
+    let g2 = Affine::from_ark(G2Native::rand(&mut rng).to_ark().into());
+    (g1, g2)
+}
+
+#[test]
+fn points_round_trip() {
+    let (g1, g2) = random_points();
+    for g1 in [g1, -g1, G1::zero()] {
+        assert_eq!(g1_from_compressed(&g1_to_compressed(&g1)), Ok(g1));
+        assert_eq!(g1_from_raw(&g1_to_raw(&g1)), Ok(g1));
+    }
+    for g2 in [g2, -g2, G2::zero()] {
+        assert_eq!(g2_from_compressed(&g2_to_compressed(&g2)), Ok(g2));
+        assert_eq!(g2_from_raw(&g2_to_raw(&g2)), Ok(g2));
+    }
+
+    assert_eq!(g1_to_raw(&G1::zero()), [0; G1_RAW_LEN]);
+    let mut infinity = [0; G1_COMPRESSED_LEN];
+    infinity[0] = 0b01 << 6;
+    assert_eq!(g1_to_compressed(&G1::zero()), infinity);
+}
+
+#[test]
+fn compressed_points_flag_the_largest_root() {
+    let g1 = G1::generator();
+    let compressed = g1_to_compressed(&g1);
+    // The generator is (1, 2), 2 being the smallest root.
+    assert_eq!(compressed[0], 0b10 << 6);
+    assert_eq!(compressed[31], 1);
+    assert_eq!(g1_to_compressed(&-g1)[0], 0b11 << 6);
+
+    let mut raw = [0; G1_RAW_LEN];
+    raw[31] = 1;
+    raw[63] = 2;
+    assert_eq!(g1_to_raw(&g1), raw);
+}
+
+#[test]
+fn malformed_points_are_rejected() {
+    let (g1, g2) = random_points();
+
+    let mut bytes = g1_to_compressed(&g1);
+    bytes[0] &= !(0b11 << 6);
+    assert_eq!(
+        g1_from_compressed::<NativeHooks>(&bytes),
+        Err(Error::InvalidFlags)
+    );
+    let mut bytes = g1_to_raw(&g1);
+    bytes[0] |= 0b10 << 6;
+    assert_eq!(g1_from_raw::<NativeHooks>(&bytes), Err(Error::InvalidFlags));
+
+    // Infinity must not carry any coordinate.
+    let mut bytes = g1_to_compressed(&G1::zero());
+    bytes[31] = 1;
+    assert_eq!(
+        g1_from_compressed::<NativeHooks>(&bytes),
+        Err(Error::InvalidFlags)
+    );
+
+    // Non canonical coordinate.
+    let mut bytes = [0xff; G1_RAW_LEN];
+    bytes[0] = 0x3f;
+    assert_eq!(
+        g1_from_raw::<NativeHooks>(&bytes),
+        Err(Error::InvalidFieldElement)
+    );
+
+    let mut bytes = g1_to_raw(&g1);
+    bytes[63] ^= 1;
+    assert_eq!(
+        g1_from_raw::<NativeHooks>(&bytes),
+        Err(Error::PointNotOnCurve)
+    );
+
+    // Points of the G2 curve outside of the prime order subgroup.
+    let mut x = Fq2::new(Fq::from(1u8), Fq::from(1u8));
+    let point = loop {
+        let y2 = x.square() * x + crate::g2::Config::<NativeHooks>::COEFF_B;
+        if let Some(y) = y2.sqrt() {
+            break G2::new_unchecked(x, y);
+        }
+        x += Fq2::ONE;
+    };
+    assert!(!point.is_in_correct_subgroup_assuming_on_curve());
+    assert_eq!(
+        g2_from_raw::<NativeHooks>(&g2_to_raw(&point)),
+        Err(Error::PointNotInSubgroup)
+    );
+    assert_eq!(
+        g2_from_compressed::<NativeHooks>(&g2_to_compressed(&point)),
+        Err(Error::PointNotInSubgroup)
+    );
+    assert!(g2_from_raw::<NativeHooks>(&g2_to_raw(&g2)).is_ok());
+}
+
+#[test]
+fn sp1_groth16_vk_is_parsed() {
+    let vk = groth16_vk_from_bytes::<NativeHooks>(SP1_GROTH16_VK).unwrap();
+    assert_eq!(vk.vk.num_public_inputs(), 2);
+    assert!(vk.public_and_commitment_committed.is_empty());
+    assert!(vk.commitment_keys.is_empty());
+    assert_eq!(groth16_vk_to_bytes(&vk, true), SP1_GROTH16_VK);
+
+    let raw = groth16_vk_to_bytes(&vk, false);
+    assert_eq!(groth16_vk_from_bytes(&raw), Ok(vk.clone()));
+
+    // Keys predating commitments end right after `K`.
+    let legacy = &SP1_GROTH16_VK[..SP1_GROTH16_VK.len() - 8];
+    assert_eq!(groth16_vk_from_bytes(legacy), Ok(vk));
+
+    assert_eq!(
+        groth16_vk_from_bytes::<NativeHooks>(&SP1_GROTH16_VK[..SP1_GROTH16_VK.len() - 1]),
+        Err(Error::InvalidLength)
+    );
+    let mut trailing = SP1_GROTH16_VK.to_vec();
+    trailing.push(0);
+    assert_eq!(
+        groth16_vk_from_bytes::<NativeHooks>(&trailing),
+        Err(Error::InvalidLength)
+    );
+}
+
+#[test]
+fn groth16_vk_committed_indexes_are_u64() {
+    let committed = |indexes: &[u64]| {
+        let mut bytes = SP1_GROTH16_VK[..SP1_GROTH16_VK.len() - 8].to_vec();
+        bytes.extend_from_slice(&1u32.to_be_bytes());
+        bytes.extend_from_slice(&(indexes.len() as u32).to_be_bytes());
+        indexes
+            .iter()
+            .for_each(|index| bytes.extend_from_slice(&index.to_be_bytes()));
+        bytes.extend_from_slice(&0u32.to_be_bytes());
+        bytes
+    };
+
+    let bytes = committed(&[1, 2]);
+    let vk = groth16_vk_from_bytes::<NativeHooks>(&bytes).unwrap();
+    assert_eq!(vk.public_and_commitment_committed, vec![vec![1, 2]]);
+    assert_eq!(groth16_vk_to_bytes(&vk, true), bytes);
+
+    // Indexes must point past the constant wire and within the public inputs.
+    for indexes in [[0, 1], [1, 3], [1, u64::MAX]] {
+        assert_eq!(
+            groth16_vk_from_bytes::<NativeHooks>(&committed(&indexes)),
+            Err(Error::InvalidIndex)
+        );
+    }
+}
+
+#[cfg(feature = "plonk")]
+#[test]
+fn sp1_plonk_vk_kzg_g2_is_the_generator() {
+    let qcp_len = u32::from_be_bytes(SP1_PLONK_VK[368..372].try_into().unwrap()) as usize;
+    let offset = 372 + 32 * qcp_len + 32;
+    let bytes = SP1_PLONK_VK[offset..offset + G2_COMPRESSED_LEN]
+        .try_into()
+        .unwrap();
+    assert_eq!(g2_from_compressed(bytes), Ok(G2::generator()));
+}
+
+#[cfg(feature = "plonk")]
+#[test]
+fn sp1_plonk_vk_is_parsed() {
+    let vk = plonk_vk_from_bytes::<NativeHooks>(SP1_PLONK_VK).unwrap();
+    assert_eq!(vk.size, 1 << 24);
+    assert_eq!(vk.size_inv * Fr::from(vk.size), Fr::one());
+    assert_eq!(vk.generator.pow([vk.size]), Fr::one());
+    assert_eq!(vk.nb_public_variables, 2);
+    assert_eq!(vk.qcp.len(), 1);
+    assert_eq!(vk.kzg.g2, G2::generator());
+    assert!(kzg::prepare_verifier_key(vk.kzg).is_ok());
+    assert_eq!(vk.commitment_constraint_indexes, vec![0xa65350]);
+
+    // Keys written before the KZG lines were added.
+    let lines_offset = 372 + 32 * vk.qcp.len() + 160;
+    let mut legacy = SP1_PLONK_VK[..lines_offset].to_vec();
+    legacy.extend(&SP1_PLONK_VK[lines_offset + 33788..]);
+    assert_eq!(plonk_vk_from_bytes(&legacy), Ok(vk));
+
+    assert_eq!(
+        plonk_vk_from_bytes::<NativeHooks>(&SP1_PLONK_VK[..SP1_PLONK_VK.len() - 1]),
+        Err(Error::InvalidLength)
+    );
+}
+
+#[test]
+fn groth16_proofs_round_trip() {
+    let (vk, proof, inputs) = setup_and_prove::<NativeHooks>(0);
+    let (g1, _) = random_points();
+    let vk = Groth16VerifyingKey {
+        vk,
+        beta_g1: g1,
+        delta_g1: -g1,
+        public_and_commitment_committed: vec![vec![1, 2]],
+        commitment_keys: vec![CommitmentKey {
+            g: G2::generator(),
+            g_sigma_neg: -G2::generator(),
+        }],
+    };
+    let proof = Groth16Proof {
+        proof,
+        commitments: vec![g1],
+        commitment_pok: G1::generator(),
+    };
+
+    for compressed in [true, false] {
+        let vk_bytes = groth16_vk_to_bytes(&vk, compressed);
+        let proof_bytes = groth16_proof_to_bytes(&proof, compressed);
+        let decoded_vk = groth16_vk_from_bytes(&vk_bytes).unwrap();
+        let decoded_proof = groth16_proof_from_bytes(&proof_bytes).unwrap();
+        assert_eq!(decoded_vk, vk);
+        assert_eq!(decoded_proof, proof);
+        assert_eq!(
+            groth16::verify(&decoded_vk.vk, &decoded_proof.proof, &inputs),
+            Ok(true)
+        );
+    }
+
+    // Bare `A || B || C` proofs, as used by SP1.
+    let mut bare = g1_to_raw(&proof.proof.a).to_vec();
+    bare.extend_from_slice(&g2_to_raw(&proof.proof.b));
+    bare.extend_from_slice(&g1_to_raw(&proof.proof.c));
+    assert_eq!(bare.len(), 256);
+    let decoded = groth16_proof_from_bytes::<NativeHooks>(&bare).unwrap();
+    assert_eq!(decoded.proof, proof.proof);
+    assert!(decoded.commitments.is_empty());
+}
+
+#[test]
+fn expand_message_xmd_matches_rfc_9380() {
+    const DST: &[u8] = b"QUUX-V01-CS02-with-expander-SHA256-128";
+    let mut output = [0; 0x20];
+    expand_message_xmd(b"", DST, &mut output);
+    assert_eq!(
+        output,
+        hex_literal::hex!("68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235")
+    );
+    expand_message_xmd(b"abc", DST, &mut output);
+    assert_eq!(
+        output,
+        hex_literal::hex!("d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615")
+    );
+    let mut output = [0; 0x80];
+    expand_message_xmd(b"", DST, &mut output);
+    assert_eq!(
+        output,
+        hex_literal::hex!(
+            "af84c27ccfd45d41914fdff5df25293e221afc53d8ad2ac06d5e3e29485dadbe"
+            "e0d121587713a3e0dd4d5e69e93eb7cd4f5df4cd103e188cf60cb02edc3edf18"
+            "eda8576c412b18ffb658e3dd6ec849469b979d444cf7b26911a08e63cf31f9dc"
+            "c541708d3491184472c2c29bb749d4286b004ceb5ee6b9a7fa5b646c993f0ced"
+        )
+    );
+}
+
+/// Builds a key and a proof with `committed.len()` commitments from known trapdoors.
+fn commitment_instance(
+    committed: Vec<Vec<usize>>,
+) -> (
+    Groth16VerifyingKey<NativeHooks>,
+    Groth16Proof<NativeHooks>,
+    Vec<Fr>,
+) {
+    let rng = &mut test_rng();
+    let g1 = G1::generator();
+    let g2 = G2::generator();
+    let [alpha, beta, gamma, delta, a, b] = [(); 6].map(|_| Fr::rand(rng));
+    let inputs = vec![Fr::rand(rng), Fr::rand(rng)];
+    let k: Vec<Fr> = (0..1 + inputs.len() + committed.len())
+        .map(|_| Fr::rand(rng))
+        .collect();
+
+    let mut commitments = Vec::new();
+    let mut commitment_keys = Vec::new();
+    let mut hashes = Vec::new();
+    let mut l = k[0] + inputs.iter().zip(&k[1..]).map(|(x, k)| *x * k).sum::<Fr>();
+    let mut sigmas = Vec::new();
+    // Commitments may commit to the hashes of previous ones.
+    let mut extended = inputs.clone();
+    for (i, indexes) in committed.iter().enumerate() {
+        let d = Fr::rand(rng);
+        let sigma = Fr::rand(rng);
+        let commitment = (g1 * d).into_affine();
+        let mut prehash = g1_to_raw(&commitment).to_vec();
+        for index in indexes {
+            prehash.extend_from_slice(&fr_to_bytes(&extended[*index - 1]));
+        }
+        let hash = hash_to_fr(&prehash, COMMITMENT_DST);
+        hashes.extend_from_slice(&fr_to_bytes(&hash));
+        extended.push(hash);
+        l += d + hash * k[1 + inputs.len() + i];
+        commitments.push(commitment);
+        commitment_keys.push(CommitmentKey {
+            g: g2,
+            g_sigma_neg: (g2 * -sigma).into_affine(),
+        });
+        sigmas.push(sigma * d);
+    }
+    // Proofs of knowledge are folded with the powers of the challenge.
+    let challenge = hash_to_fr(&hashes, COMMITMENT_CHALLENGE_DST);
+    let pok = sigmas
+        .iter()
+        .rev()
+        .fold(Fr::zero(), |acc, sigma| acc * challenge + sigma);
+    let c = (a * b - alpha * beta - l * gamma) / delta;
+
+    let vk = Groth16VerifyingKey {
+        vk: groth16::VerifyingKey {
+            alpha_g1: (g1 * alpha).into_affine(),
+            beta_g2: (g2 * beta).into_affine(),
+            gamma_g2: (g2 * gamma).into_affine(),
+            delta_g2: (g2 * delta).into_affine(),
+            gamma_abc_g1: k.iter().map(|k| (g1 * k).into_affine()).collect(),
+        },
+        beta_g1: (g1 * beta).into_affine(),
+        delta_g1: (g1 * delta).into_affine(),
+        public_and_commitment_committed: committed,
+        commitment_keys,
+    };
+    let proof = Groth16Proof {
+        proof: groth16::Proof {
+            a: (g1 * a).into_affine(),
+            b: (g2 * b).into_affine(),
+            c: (g1 * c).into_affine(),
+        },
+        commitments,
+        commitment_pok: (g1 * pok).into_affine(),
+    };
+    (vk, proof, inputs)
+}
+
+#[test]
+fn verify_groth16_works() {
+    let (vk, proof, inputs) = setup_and_prove::<NativeHooks>(0);
+    let vk = groth16_vk_from_bytes(&groth16_vk_to_bytes(
+        &Groth16VerifyingKey {
+            vk,
+            beta_g1: G1::generator(),
+            delta_g1: G1::generator(),
+            public_and_commitment_committed: vec![],
+            commitment_keys: vec![],
+        },
+        true,
+    ))
+    .unwrap();
+    let proof = Groth16Proof {
+        proof,
+        commitments: vec![],
+        commitment_pok: G1::zero(),
+    };
+    assert_eq!(verify_groth16(&vk, &proof, &inputs), Ok(true));
+    assert_eq!(
+        verify_groth16(&vk, &proof, &[inputs[1], inputs[0]]),
+        Ok(false)
+    );
+
+    for committed in [vec![vec![]], vec![vec![1]], vec![vec![2], vec![1, 2]]] {
+        let (vk, proof, inputs) = commitment_instance(committed);
+        assert_eq!(verify_groth16(&vk, &proof, &inputs), Ok(true));
+        let bytes = groth16_proof_to_bytes(&proof, true);
+        assert_eq!(
+            verify_groth16(&vk, &groth16_proof_from_bytes(&bytes).unwrap(), &inputs),
+            Ok(true)
+        );
+    }
+}
+
+#[test]
+fn verify_groth16_checks_commitments() {
+    let (vk, proof, inputs) = commitment_instance(vec![vec![1], vec![2]]);
+    assert_eq!(verify_groth16(&vk, &proof, &inputs), Ok(true));
+
+    // Committed public input changes the commitment hash.
+    let mut wrong_inputs = inputs.clone();
+    wrong_inputs[0] += Fr::one();
+    assert_eq!(verify_groth16(&vk, &proof, &wrong_inputs), Ok(false));
+
+    let wrong_pok = Groth16Proof {
+        commitment_pok: (proof.commitment_pok + G1::generator()).into_affine(),
+        ..proof.clone()
+    };
+    assert_eq!(verify_groth16(&vk, &wrong_pok, &inputs), Ok(false));
+
+    let mut swapped = proof.clone();
+    swapped.commitments.swap(0, 1);
+    assert_eq!(verify_groth16(&vk, &swapped, &inputs), Ok(false));
+
+    let mut missing = proof.clone();
+    missing.commitments.pop();
+    assert_eq!(
+        verify_groth16(&vk, &missing, &inputs),
+        Err(groth16::Error::MalformedProof)
+    );
+    assert_eq!(
+        verify_groth16(&vk, &proof, &inputs[..1]),
+        Err(groth16::Error::InvalidPublicInputsLength {
+            expected: 2,
+            found: 1
+        })
+    );
+
+    let mut bad_vk = vk.clone();
+    bad_vk.public_and_commitment_committed[0] = vec![0];
+    assert_eq!(
+        verify_groth16(&bad_vk, &proof, &inputs),
+        Err(groth16::Error::MalformedVerifyingKey)
+    );
+    let mut bad_vk = vk;
+    bad_vk.commitment_keys[1].g = -bad_vk.commitment_keys[1].g;
+    assert_eq!(
+        verify_groth16(&bad_vk, &proof, &inputs),
+        Err(groth16::Error::MalformedVerifyingKey)
+    );
+}
+
+#[test]
+fn verify_groth16_handles_nested_commitments() {
+    // The second commitment commits to the first one's hash, the third input.
+    let (vk, proof, inputs) = commitment_instance(vec![vec![1], vec![2, 3]]);
+    assert_eq!(verify_groth16(&vk, &proof, &inputs), Ok(true));
+
+    let mut wrong_inputs = inputs.clone();
+    wrong_inputs[0] += Fr::one();
+    assert_eq!(verify_groth16(&vk, &proof, &wrong_inputs), Ok(false));
+
+    // A commitment cannot refer to its own hash.
+    let mut bad_vk = vk;
+    bad_vk.public_and_commitment_committed[1] = vec![4];
+    assert_eq!(
+        verify_groth16(&bad_vk, &proof, &inputs),
+        Err(groth16::Error::MalformedVerifyingKey)
+    );
+}
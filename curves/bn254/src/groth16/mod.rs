@@ -0,0 +1,316 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [Groth16](https://eprint.iacr.org/2016/260.pdf) verifier.
+//!
+//! The public inputs linear combination is computed through the `msm_g1` hook
+//! while the pairing equation
+//!
+//! `e(A, B) = e(alpha, beta) * e(L, gamma) * e(C, delta)`
+//!
+//! is checked with a single four pairs multi Miller loop followed by a final
+//! exponentiation.
+//!
+//! Proofs for the same key can be batched with a random linear combination, so
+//! that `n` proofs are checked with a single `n + 3` pairs multi Miller loop (see
+//! [`verify_proofs_batch`]).
+
+use ark_ff::{One, UniformRand, Zero};
+use ark_models_ext::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr, CurveGroup,
+};
+use ark_std::{rand::Rng, vec, vec::Vec};
+use educe::Educe;
+
+use crate::{
+    Bn254, CurveHooks, Fr, G1Affine, G1Projective, G2Affine, HookError, TryCurveOps, TryPairing,
+};
+
+#[cfg(test)]
+pub(crate) mod tests;
+
+/// Error returned by the verifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// Verifying key points are not valid or there are no public inputs bases.
+    MalformedVerifyingKey,
+    /// Proof points are not valid.
+    MalformedProof,
+    /// Number of public inputs doesn't match the verifying key.
+    InvalidPublicInputsLength {
+        /// Number of public inputs expected by the verifying key.
+        expected: usize,
+        /// Number of public inputs provided.
+        found: usize,
+    },
+    /// Failure reported by the hooks.
+    Hook(HookError),
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::MalformedVerifyingKey => write!(f, "malformed verifying key"),
+            Error::MalformedProof => write!(f, "malformed proof"),
+            Error::InvalidPublicInputsLength { expected, found } => write!(
+                f,
+                "invalid public inputs length (expected {expected}, found {found})"
+            ),
+            Error::Hook(err) => write!(f, "hook failure: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl From<HookError> for Error {
+    fn from(err: HookError) -> Self {
+        Error::Hook(err)
+    }
+}
+
+/// Groth16 verifying key.
+#[derive(Educe)]
+#[educe(Clone, Debug, PartialEq, Eq)]
+pub struct VerifyingKey<H: CurveHooks> {
+    /// `alpha` in G1.
+    pub alpha_g1: G1Affine<H>,
+    /// `beta` in G2.
+    pub beta_g2: G2Affine<H>,
+    /// `gamma` in G2.
+    pub gamma_g2: G2Affine<H>,
+    /// `delta` in G2.
+    pub delta_g2: G2Affine<H>,
+    /// Public inputs bases, the first one being the constant term.
+    pub gamma_abc_g1: Vec<G1Affine<H>>,
+}
+
+impl<H: CurveHooks> VerifyingKey<H> {
+    /// Number of public inputs expected by the key.
+    pub fn num_public_inputs(&self) -> usize {
+        self.gamma_abc_g1.len().saturating_sub(1)
+    }
+}
+
+/// Groth16 verifying key prepared for verification.
+///
+/// Points are validated once and negated as required by the pairing equation.
+#[derive(Educe)]
+#[educe(Clone, Debug, PartialEq, Eq)]
+pub struct PreparedVerifyingKey<H: CurveHooks> {
+    /// The original verifying key.
+    pub vk: VerifyingKey<H>,
+    alpha_g1_neg: G1Affine<H>,
+    gamma_g2_neg: G2Affine<H>,
+    delta_g2_neg: G2Affine<H>,
+}
+
+impl<H: CurveHooks> TryFrom<VerifyingKey<H>> for PreparedVerifyingKey<H> {
+    type Error = Error;
+
+    fn try_from(vk: VerifyingKey<H>) -> Result<Self, Self::Error> {
+        prepare_verifying_key(vk)
+    }
+}
+
+/// Groth16 proof.
+#[derive(Educe)]
+#[educe(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Proof<H: CurveHooks> {
+    /// `A` in G1.
+    pub a: G1Affine<H>,
+    /// `B` in G2.
+    pub b: G2Affine<H>,
+    /// `C` in G1.
+    pub c: G1Affine<H>,
+}
+
+pub(crate) fn is_valid<P: SWCurveConfig>(point: &Affine<P>) -> bool {
+    point.is_zero() || point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve()
+}
+
+/// Validates and prepares the verifying key.
+pub fn prepare_verifying_key<H: CurveHooks>(
+    vk: VerifyingKey<H>,
+) -> Result<PreparedVerifyingKey<H>, Error> {
+    let valid = is_valid(&vk.alpha_g1)
+        && is_valid(&vk.beta_g2)
+        && is_valid(&vk.gamma_g2)
+        && is_valid(&vk.delta_g2)
+        && !vk.gamma_abc_g1.is_empty()
+        && vk.gamma_abc_g1.iter().all(is_valid);
+    if !valid {
+        return Err(Error::MalformedVerifyingKey);
+    }
+    Ok(PreparedVerifyingKey {
+        alpha_g1_neg: -vk.alpha_g1,
+        gamma_g2_neg: -vk.gamma_g2,
+        delta_g2_neg: -vk.delta_g2,
+        vk,
+    })
+}
+
+/// Computes the public inputs linear combination `L` through the `msm_g1` hook.
+pub fn prepare_inputs<H: CurveHooks>(
+    pvk: &PreparedVerifyingKey<H>,
+    public_inputs: &[Fr],
+) -> Result<G1Projective<H>, Error> {
+    let expected = pvk.vk.num_public_inputs();
+    if public_inputs.len() != expected {
+        return Err(Error::InvalidPublicInputsLength {
+            expected,
+            found: public_inputs.len(),
+        });
+    }
+    let (constant, bases) = pvk
+        .vk
+        .gamma_abc_g1
+        .split_first()
+        .ok_or(Error::MalformedVerifyingKey)?;
+    let acc = G1Projective::<H>::try_msm(bases, public_inputs)?;
+    Ok(acc + constant)
+}
+
+/// Verifies the proof against the prepared verifying key and the already
+/// prepared public inputs (see [`prepare_inputs`]).
+pub fn verify_proof_with_prepared_inputs<H: CurveHooks>(
+    pvk: &PreparedVerifyingKey<H>,
+    proof: &Proof<H>,
+    prepared_inputs: &G1Projective<H>,
+) -> Result<bool, Error> {
+    if !is_valid(&proof.a) || !is_valid(&proof.b) || !is_valid(&proof.c) {
+        return Err(Error::MalformedProof);
+    }
+    let g1 = [
+        proof.a,
+        pvk.alpha_g1_neg,
+        prepared_inputs.into_affine(),
+        proof.c,
+    ];
+    let g2 = [proof.b, pvk.vk.beta_g2, pvk.gamma_g2_neg, pvk.delta_g2_neg];
+    let target = Bn254::<H>::try_multi_miller_loop(g1, g2)?;
+    let res = Bn254::<H>::try_final_exponentiation(target)?;
+    Ok(res.0.is_one())
+}
+
+/// Verifies the proof against the prepared verifying key and the public inputs.
+pub fn verify_proof<H: CurveHooks>(
+    pvk: &PreparedVerifyingKey<H>,
+    proof: &Proof<H>,
+    public_inputs: &[Fr],
+) -> Result<bool, Error> {
+    let prepared_inputs = prepare_inputs(pvk, public_inputs)?;
+    verify_proof_with_prepared_inputs(pvk, proof, &prepared_inputs)
+}
+
+/// Verifies the proof against the verifying key and the public inputs.
+///
+/// When verifying many proofs against the same key prefer [`verify_proof`] with a
+/// key prepared once by [`prepare_verifying_key`].
+pub fn verify<H: CurveHooks>(
+    vk: &VerifyingKey<H>,
+    proof: &Proof<H>,
+    public_inputs: &[Fr],
+) -> Result<bool, Error> {
+    let pvk = prepare_verifying_key(vk.clone())?;
+    verify_proof(&pvk, proof, public_inputs)
+}
+
+/// Verifies a batch of proofs against the same prepared verifying key.
+///
+/// Each proof is weighted by a random scalar drawn from `rng` and all the pairing
+/// equations are folded into
+///
+/// `prod_i e(r_i * A_i, B_i) = e(sum_i r_i * alpha, beta) * e(sum_i r_i * L_i, gamma) * e(sum_i r_i * C_i, delta)`
+///
+/// where the public inputs combinations `L_i` and the `C_i` are folded through the
+/// `msm_g1` hook. Returns `true` only if all the proofs are valid, use
+/// [`find_invalid_proofs`] to identify the failing ones.
+pub fn verify_proofs_batch<H: CurveHooks, I: AsRef<[Fr]>, R: Rng>(
+    pvk: &PreparedVerifyingKey<H>,
+    batch: &[(Proof<H>, I)],
+    rng: &mut R,
+) -> Result<bool, Error> {
+    if batch.is_empty() {
+        return Ok(true);
+    }
+    let expected = pvk.vk.num_public_inputs();
+    let mut g1 = Vec::with_capacity(batch.len() + 3);
+    let mut g2 = Vec::with_capacity(batch.len() + 3);
+    // The first scalar weights the constant term of the public inputs combination.
+    let mut inputs = vec![Fr::zero(); expected + 1];
+    let mut c_bases = Vec::with_capacity(batch.len());
+    let mut c_scalars = Vec::with_capacity(batch.len());
+    for (proof, public_inputs) in batch {
+        let public_inputs = public_inputs.as_ref();
+        if public_inputs.len() != expected {
+            return Err(Error::InvalidPublicInputsLength {
+                expected,
+                found: public_inputs.len(),
+            });
+        }
+        if !is_valid(&proof.a) || !is_valid(&proof.b) || !is_valid(&proof.c) {
+            return Err(Error::MalformedProof);
+        }
+        let r = Fr::rand(rng);
+        inputs[0] += r;
+        for (acc, input) in inputs[1..].iter_mut().zip(public_inputs) {
+            *acc += r * input;
+        }
+        g1.push(proof.a.into_group().try_mul(&r)?.into_affine());
+        g2.push(proof.b);
+        c_bases.push(proof.c);
+        c_scalars.push(r);
+    }
+
+    let alpha = pvk.alpha_g1_neg.into_group().try_mul(&inputs[0])?;
+    let prepared_inputs = G1Projective::<H>::try_msm(&pvk.vk.gamma_abc_g1, &inputs)?;
+    let c = G1Projective::<H>::try_msm(&c_bases, &c_scalars)?;
+    g1.extend([alpha, prepared_inputs, c].map(|point| point.into_affine()));
+    g2.extend([pvk.vk.beta_g2, pvk.gamma_g2_neg, pvk.delta_g2_neg]);
+    let target = Bn254::<H>::try_multi_miller_loop(g1, g2)?;
+    let res = Bn254::<H>::try_final_exponentiation(target)?;
+    Ok(res.0.is_one())
+}
+
+/// Returns the indexes of the invalid proofs of the batch.
+///
+/// The batch is first checked with [`verify_proofs_batch`] and, only if that
+/// fails, every proof is verified on its own. Malformed proofs and proofs with the
+/// wrong number of public inputs are reported as invalid, while hook failures
+/// abort the verification.
+pub fn find_invalid_proofs<H: CurveHooks, I: AsRef<[Fr]>, R: Rng>(
+    pvk: &PreparedVerifyingKey<H>,
+    batch: &[(Proof<H>, I)],
+    rng: &mut R,
+) -> Result<Vec<usize>, Error> {
+    match verify_proofs_batch(pvk, batch, rng) {
+        Ok(true) => return Ok(Vec::new()),
+        Err(Error::Hook(err)) => return Err(Error::Hook(err)),
+        Ok(false) | Err(_) => (),
+    }
+    let mut invalid = Vec::new();
+    for (i, (proof, public_inputs)) in batch.iter().enumerate() {
+        match verify_proof(pvk, proof, public_inputs.as_ref()) {
+            Ok(true) => (),
+            Err(Error::Hook(err)) => return Err(Error::Hook(err)),
+            Ok(false) | Err(_) => invalid.push(i),
+        }
+    }
+    Ok(invalid)
+}
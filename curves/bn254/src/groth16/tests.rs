@@ -0,0 +1,230 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::{
+    metering::{GasSchedule, MeteredHooks, ThreadBudget, OUT_OF_GAS},
+    native::NativeHooks,
+    FromArk,
+};
+use ark_bn254::Bn254 as ArkBn254;
+use ark_ff::{Field, UniformRand};
+use ark_models_ext::short_weierstrass::Affine;
+use ark_relations::{
+    lc,
+    r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable},
+};
+use ark_snark::SNARK;
+use ark_std::{
+    rand::{rngs::StdRng, SeedableRng},
+    vec,
+};
+
+type Groth16 = ark_groth16::Groth16<ArkBn254>;
+pub(crate) type Batch<H> = Vec<(Proof<H>, Vec<Fr>)>;
+
+/// Proves knowledge of `x` such that `x^3 + x + 5 = y`, with `y` and `x^2` public.
+#[derive(Clone, Copy)]
+struct CubicCircuit {
+    x: Option<Fr>,
+}
+
+impl ConstraintSynthesizer<Fr> for CubicCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let x_val = self.x;
+        let x_sq_val = x_val.map(|x| x.square());
+        let x_cu_val = x_val.map(|x| x.square() * x);
+        let y_val = x_val.map(|x| x.square() * x + x + Fr::from(5u8));
+
+        let x = cs.new_witness_variable(|| x_val.ok_or(SynthesisError::AssignmentMissing))?;
+        let x_sq = cs.new_input_variable(|| x_sq_val.ok_or(SynthesisError::AssignmentMissing))?;
+        let x_cu = cs.new_witness_variable(|| x_cu_val.ok_or(SynthesisError::AssignmentMissing))?;
+        let y = cs.new_input_variable(|| y_val.ok_or(SynthesisError::AssignmentMissing))?;
+
+        cs.enforce_constraint(lc!() + x, lc!() + x, lc!() + x_sq)?;
+        cs.enforce_constraint(lc!() + x_sq, lc!() + x, lc!() + x_cu)?;
+        cs.enforce_constraint(
+            lc!() + x_cu + x + (Fr::from(5u8), Variable::One),
+            lc!() + Variable::One,
+            lc!() + y,
+        )?;
+        Ok(())
+    }
+}
+
+/// Generates a key and a proof with the upstream `ark-groth16` implementation.
+pub(crate) fn setup_and_prove<H: CurveHooks>(seed: u64) -> (VerifyingKey<H>, Proof<H>, Vec<Fr>) {
+    let (vk, mut proofs) = setup_and_prove_many(seed, 1);
+    let (proof, inputs) = proofs.pop().unwrap();
+    (vk, proof, inputs)
+}
+
+/// Generates a key and `n` proofs for random witnesses.
+pub(crate) fn setup_and_prove_many<H: CurveHooks>(
+    seed: u64,
+    n: usize,
+) -> (VerifyingKey<H>, Batch<H>) {
+    let rng = &mut StdRng::seed_from_u64(seed);
+    let (pk, vk) = Groth16::circuit_specific_setup(CubicCircuit { x: None }, rng).unwrap();
+    let proofs = (0..n)
+        .map(|_| {
+            let x = Fr::rand(rng);
+            let proof = Groth16::prove(&pk, CubicCircuit { x: Some(x) }, rng).unwrap();
+            let inputs = vec![x.square(), x.square() * x + x + Fr::from(5u8)];
+            assert!(Groth16::verify(&vk, &inputs, &proof).unwrap());
+            let proof = Proof {
+                a: Affine::from_ark(proof.a),
+                b: Affine::from_ark(proof.b),
+                c: Affine::from_ark(proof.c),
+            };
+            (proof, inputs)
+        })
+        .collect();
+
+    let vk = VerifyingKey {
+        alpha_g1: Affine::from_ark(vk.alpha_g1),
+        beta_g2: Affine::from_ark(vk.beta_g2),
+        gamma_g2: Affine::from_ark(vk.gamma_g2),
+        delta_g2: Affine::from_ark(vk.delta_g2),
+        gamma_abc_g1: vk.gamma_abc_g1.into_iter().map(Affine::from_ark).collect(),
+    };
+    (vk, proofs)
+}
+
+#[test]
+fn verify_works() {
+    let (vk, proof, inputs) = setup_and_prove::<NativeHooks>(0);
+    assert_eq!(vk.num_public_inputs(), 2);
+    assert_eq!(verify(&vk, &proof, &inputs), Ok(true));
+
+    let pvk = PreparedVerifyingKey::try_from(vk).unwrap();
+    assert_eq!(verify_proof(&pvk, &proof, &inputs), Ok(true));
+
+    let mut wrong_inputs = inputs.clone();
+    wrong_inputs[1] += Fr::from(1u8);
+    assert_eq!(verify_proof(&pvk, &proof, &wrong_inputs), Ok(false));
+
+    let wrong_proof = Proof {
+        a: proof.c,
+        c: proof.a,
+        ..proof
+    };
+    assert_eq!(verify_proof(&pvk, &wrong_proof, &inputs), Ok(false));
+}
+
+#[test]
+fn verify_uses_one_msm_and_one_pairing_check() {
+    type Hooks = MeteredHooks<NativeHooks, ThreadBudget>;
+
+    let (vk, proof, inputs) = setup_and_prove::<Hooks>(0);
+    let pvk = prepare_verifying_key(vk).unwrap();
+
+    ThreadBudget::reset(u64::MAX);
+    assert_eq!(verify_proof(&pvk, &proof, &inputs), Ok(true));
+    let schedule = GasSchedule::EIP_1108;
+    assert_eq!(
+        ThreadBudget::used(),
+        schedule.msm_g1_cost(2) + schedule.ec_pairing_cost(4)
+    );
+
+    ThreadBudget::reset(schedule.msm_g1_cost(2));
+    assert_eq!(
+        verify_proof(&pvk, &proof, &inputs),
+        Err(Error::Hook(OUT_OF_GAS))
+    );
+}
+
+#[test]
+fn malformed_inputs_are_rejected() {
+    let (vk, proof, inputs) = setup_and_prove::<NativeHooks>(0);
+
+    assert_eq!(
+        verify(&vk, &proof, &inputs[..1]),
+        Err(Error::InvalidPublicInputsLength {
+            expected: 2,
+            found: 1
+        })
+    );
+
+    let off_curve = G1Affine::<NativeHooks>::new_unchecked(proof.a.x, proof.a.y + proof.a.y);
+    let bad_proof = Proof {
+        a: off_curve,
+        ..proof
+    };
+    assert_eq!(verify(&vk, &bad_proof, &inputs), Err(Error::MalformedProof));
+
+    let bad_vk = VerifyingKey {
+        alpha_g1: off_curve,
+        ..vk.clone()
+    };
+    assert_eq!(
+        prepare_verifying_key(bad_vk),
+        Err(Error::MalformedVerifyingKey)
+    );
+
+    let empty_vk = VerifyingKey {
+        gamma_abc_g1: vec![],
+        ..vk
+    };
+    assert_eq!(
+        verify(&empty_vk, &proof, &[]),
+        Err(Error::MalformedVerifyingKey)
+    );
+}
+
+#[test]
+fn verify_proofs_batch_works() {
+    let rng = &mut StdRng::seed_from_u64(1);
+    let (vk, mut batch) = setup_and_prove_many::<NativeHooks>(0, 4);
+    let pvk = prepare_verifying_key(vk).unwrap();
+
+    assert_eq!(verify_proofs_batch(&pvk, &batch, rng), Ok(true));
+    assert_eq!(find_invalid_proofs(&pvk, &batch, rng), Ok(vec![]));
+    assert_eq!(verify_proofs_batch(&pvk, &Batch::new(), rng), Ok(true));
+
+    // Swapping the inputs of two proofs breaks both of them.
+    let inputs = batch[1].1.clone();
+    batch[1].1 = batch[3].1.clone();
+    batch[3].1 = inputs;
+    assert_eq!(verify_proofs_batch(&pvk, &batch, rng), Ok(false));
+    assert_eq!(find_invalid_proofs(&pvk, &batch, rng), Ok(vec![1, 3]));
+
+    batch[3].1.pop();
+    assert_eq!(
+        verify_proofs_batch(&pvk, &batch, rng),
+        Err(Error::InvalidPublicInputsLength {
+            expected: 2,
+            found: 1
+        })
+    );
+    assert_eq!(find_invalid_proofs(&pvk, &batch, rng), Ok(vec![1, 3]));
+}
+
+#[test]
+fn verify_proofs_batch_uses_one_pairing_check() {
+    type Hooks = MeteredHooks<NativeHooks, ThreadBudget>;
+
+    let rng = &mut StdRng::seed_from_u64(1);
+    let (vk, batch) = setup_and_prove_many::<Hooks>(0, 3);
+    let pvk = prepare_verifying_key(vk).unwrap();
+
+    ThreadBudget::reset(u64::MAX);
+    assert_eq!(verify_proofs_batch(&pvk, &batch, rng), Ok(true));
+    let schedule = GasSchedule::EIP_1108;
+    assert_eq!(
+        ThreadBudget::used(),
+        4 * schedule.ec_mul_cost() + 2 * schedule.msm_g1_cost(3) + schedule.ec_pairing_cost(6)
+    );
+}
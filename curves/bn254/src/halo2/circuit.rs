@@ -0,0 +1,294 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Constraint system of a halo2 circuit, as seen by the verifier.
+//!
+//! Mirrors the `ConstraintSystem` of a halo2 verifying key once selectors have
+//! been turned into fixed columns: gates, lookups and the permutation refer to the
+//! queried cells through their query index.
+
+use ark_std::{boxed::Box, vec::Vec};
+use core::ops::{Add, Mul, Neg, Sub};
+
+use crate::Fr;
+
+/// Type of a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    /// Fixed column, committed in the verifying key.
+    Fixed,
+    /// Advice column, committed in the proof.
+    Advice,
+    /// Instance column, holding the public inputs.
+    Instance,
+}
+
+/// Column of the circuit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Column {
+    /// Type of the column.
+    pub column_type: ColumnType,
+    /// Index of the column among the ones of its type.
+    pub index: usize,
+}
+
+impl Column {
+    /// Creates a column.
+    pub fn new(column_type: ColumnType, index: usize) -> Self {
+        Column { column_type, index }
+    }
+}
+
+/// Polynomial expression over the queried cells and the challenges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expression {
+    /// Constant.
+    Constant(Fr),
+    /// Fixed cell, given by its index in the fixed queries.
+    Fixed(usize),
+    /// Advice cell, given by its index in the advice queries.
+    Advice(usize),
+    /// Instance cell, given by its index in the instance queries.
+    Instance(usize),
+    /// Challenge, given by its index.
+    Challenge(usize),
+    /// Negated expression.
+    Negated(Box<Expression>),
+    /// Sum of two expressions.
+    Sum(Box<Expression>, Box<Expression>),
+    /// Product of two expressions.
+    Product(Box<Expression>, Box<Expression>),
+    /// Expression scaled by a constant.
+    Scaled(Box<Expression>, Fr),
+}
+
+impl Expression {
+    /// Degree of the expression in the cells.
+    pub fn degree(&self) -> usize {
+        match self {
+            Expression::Constant(_) | Expression::Challenge(_) => 0,
+            Expression::Fixed(_) | Expression::Advice(_) | Expression::Instance(_) => 1,
+            Expression::Negated(a) | Expression::Scaled(a, _) => a.degree(),
+            Expression::Sum(a, b) => a.degree().max(b.degree()),
+            Expression::Product(a, b) => a.degree() + b.degree(),
+        }
+    }
+
+    /// Evaluates the expression, the indexes having been checked against the
+    /// constraint system.
+    pub(crate) fn evaluate(
+        &self,
+        fixed: &[Fr],
+        advice: &[Fr],
+        instance: &[Fr],
+        challenges: &[Fr],
+    ) -> Fr {
+        let eval = |expr: &Expression| expr.evaluate(fixed, advice, instance, challenges);
+        match self {
+            Expression::Constant(value) => *value,
+            Expression::Fixed(query) => fixed[*query],
+            Expression::Advice(query) => advice[*query],
+            Expression::Instance(query) => instance[*query],
+            Expression::Challenge(index) => challenges[*index],
+            Expression::Negated(a) => -eval(a),
+            Expression::Sum(a, b) => eval(a) + eval(b),
+            Expression::Product(a, b) => eval(a) * eval(b),
+            Expression::Scaled(a, scalar) => eval(a) * scalar,
+        }
+    }
+
+    fn is_valid(&self, cs: &ConstraintSystem) -> bool {
+        match self {
+            Expression::Constant(_) => true,
+            Expression::Fixed(query) => *query < cs.fixed_queries.len(),
+            Expression::Advice(query) => *query < cs.advice_queries.len(),
+            Expression::Instance(query) => *query < cs.instance_queries.len(),
+            Expression::Challenge(index) => *index < cs.challenge_phase.len(),
+            Expression::Negated(a) | Expression::Scaled(a, _) => a.is_valid(cs),
+            Expression::Sum(a, b) | Expression::Product(a, b) => a.is_valid(cs) && b.is_valid(cs),
+        }
+    }
+}
+
+impl Neg for Expression {
+    type Output = Expression;
+
+    fn neg(self) -> Expression {
+        Expression::Negated(Box::new(self))
+    }
+}
+
+impl Add for Expression {
+    type Output = Expression;
+
+    fn add(self, rhs: Expression) -> Expression {
+        Expression::Sum(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl Sub for Expression {
+    type Output = Expression;
+
+    fn sub(self, rhs: Expression) -> Expression {
+        Expression::Sum(Box::new(self), Box::new(-rhs))
+    }
+}
+
+impl Mul for Expression {
+    type Output = Expression;
+
+    fn mul(self, rhs: Expression) -> Expression {
+        Expression::Product(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl Mul<Fr> for Expression {
+    type Output = Expression;
+
+    fn mul(self, rhs: Fr) -> Expression {
+        Expression::Scaled(Box::new(self), rhs)
+    }
+}
+
+/// Lookup argument: the tuples of input expressions must appear among the tuples
+/// of table expressions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lookup {
+    /// Input expressions.
+    pub input_expressions: Vec<Expression>,
+    /// Table expressions.
+    pub table_expressions: Vec<Expression>,
+}
+
+impl Lookup {
+    fn required_degree(&self) -> usize {
+        let degree =
+            |exprs: &[Expression]| exprs.iter().map(Expression::degree).fold(1, usize::max);
+        usize::max(
+            4,
+            2 + degree(&self.input_expressions) + degree(&self.table_expressions),
+        )
+    }
+}
+
+/// Constraint system of a circuit, as found in its halo2 verifying key.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ConstraintSystem {
+    /// Number of fixed columns, selectors included.
+    pub num_fixed_columns: usize,
+    /// Number of instance columns.
+    pub num_instance_columns: usize,
+    /// Phase of each advice column.
+    pub advice_column_phase: Vec<u8>,
+    /// Phase after which each challenge is squeezed.
+    pub challenge_phase: Vec<u8>,
+    /// Fixed queries, as column index and rotation.
+    pub fixed_queries: Vec<(usize, i32)>,
+    /// Advice queries, as column index and rotation.
+    pub advice_queries: Vec<(usize, i32)>,
+    /// Instance queries, as column index and rotation.
+    pub instance_queries: Vec<(usize, i32)>,
+    /// Polynomials of all the gates, in gate order.
+    pub gates: Vec<Expression>,
+    /// Columns of the permutation argument.
+    pub permutation: Vec<Column>,
+    /// Lookup arguments.
+    pub lookups: Vec<Lookup>,
+    /// Lower bound of the degree, if set by the circuit.
+    pub minimum_degree: Option<usize>,
+}
+
+impl ConstraintSystem {
+    /// Number of advice columns.
+    pub fn num_advice_columns(&self) -> usize {
+        self.advice_column_phase.len()
+    }
+
+    /// Degree of the constraint system, which sets the number of quotient pieces
+    /// and the size of the permutation chunks.
+    pub fn degree(&self) -> usize {
+        // The permutation argument requires degree 3.
+        let lookups = self.lookups.iter().map(Lookup::required_degree).max();
+        let gates = self.gates.iter().map(Expression::degree).max();
+        [Some(3), lookups, gates, self.minimum_degree]
+            .into_iter()
+            .flatten()
+            .fold(1, usize::max)
+    }
+
+    /// Number of blinding rows at the end of the columns.
+    pub fn blinding_factors(&self) -> usize {
+        let max_advice_queries = (0..self.num_advice_columns())
+            .map(|column| {
+                self.advice_queries
+                    .iter()
+                    .filter(|(queried, _)| *queried == column)
+                    .count()
+            })
+            .fold(1, usize::max);
+        // The permutation argument opens its polynomials up to three times, one
+        // more opening comes from the multiopen argument and one is for safety.
+        usize::max(3, max_advice_queries) + 2
+    }
+
+    /// Phases of the advice columns, in increasing order.
+    pub(crate) fn phases(&self) -> core::ops::RangeInclusive<u8> {
+        0..=self.advice_column_phase.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Index of the query of `column` at the current row.
+    pub(crate) fn query_index(&self, column: &Column) -> Option<usize> {
+        let queries = match column.column_type {
+            ColumnType::Fixed => &self.fixed_queries,
+            ColumnType::Advice => &self.advice_queries,
+            ColumnType::Instance => &self.instance_queries,
+        };
+        queries.iter().position(|query| *query == (column.index, 0))
+    }
+
+    /// Checks that columns, queries and challenges are consistent.
+    pub(crate) fn is_valid(&self) -> bool {
+        let queries_valid = |queries: &[(usize, i32)], columns: usize| {
+            queries.iter().all(|(column, _)| *column < columns)
+        };
+        let challenges_valid = self
+            .challenge_phase
+            .iter()
+            .all(|phase| self.phases().contains(phase));
+        let expressions = self
+            .gates
+            .iter()
+            .chain(self.lookups.iter().flat_map(|lookup| {
+                lookup
+                    .input_expressions
+                    .iter()
+                    .chain(&lookup.table_expressions)
+            }));
+        queries_valid(&self.fixed_queries, self.num_fixed_columns)
+            && queries_valid(&self.advice_queries, self.num_advice_columns())
+            && queries_valid(&self.instance_queries, self.num_instance_columns)
+            && challenges_valid
+            && self.degree() > 2
+            && self
+                .lookups
+                .iter()
+                .all(|lookup| lookup.input_expressions.len() == lookup.table_expressions.len())
+            && self
+                .permutation
+                .iter()
+                .all(|column| self.query_index(column).is_some())
+            && expressions.into_iter().all(|expr| expr.is_valid(self))
+    }
+}
@@ -0,0 +1,733 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [halo2](https://github.com/privacy-scaling-explorations/halo2) verifier, for
+//! proofs over BN254 with the KZG commitment scheme.
+//!
+//! Verifies the proofs of PSE halo2 and of the forks sharing its proof format (e.g.
+//! `halo2-axiom`), with:
+//! * a Blake2b or Keccak-256 transcript, see [`TranscriptHash`];
+//! * the SHPLONK or GWC multiopen argument, see [`MultiOpen`].
+//!
+//! halo2 verifying keys only hold commitments: the [`ConstraintSystem`] of the
+//! circuit is provided along with them, as well as the transcript representation
+//! of the key, which halo2 derives by hashing the whole key. Points are encoded
+//! in the compressed format of `halo2curves` (`SerdeFormat::Processed`).
+//!
+//! The verifier checks the gates, permutation and lookup constraints at the
+//! evaluation challenge and reduces all the openings to one MSM through the
+//! `msm_g1` hook and a single KZG pairing check (see [`crate::kzg`]).
+
+use ark_ff::{batch_inversion, BigInt, BigInteger, Field, MontFp, One, PrimeField, Zero};
+use ark_models_ext::AffineRepr;
+use ark_std::{vec, vec::Vec};
+use educe::Educe;
+use sha3::{Digest, Keccak256};
+
+use crate::{groth16::is_valid, kzg, CurveHooks, Fq, Fr, G1Affine};
+
+pub use crate::plonk::Error;
+pub use circuit::{Column, ColumnType, ConstraintSystem, Expression, Lookup};
+
+mod circuit;
+mod multiopen;
+#[cfg(test)]
+mod tests;
+
+use multiopen::{Claim, Msm, Query};
+
+/// Length of an encoded point.
+pub const POINT_LEN: usize = 32;
+/// Length of an encoded scalar.
+pub const SCALAR_LEN: usize = 32;
+/// Maximum base two logarithm of the number of rows.
+pub const MAX_K: u32 = 28;
+
+const VERSION: u8 = 0x02;
+const HEADER_LEN: usize = 10;
+const IDENTITY_FLAG: u8 = 1 << 7;
+const SIGN_FLAG: u8 = 1 << 6;
+
+const TRANSCRIPT_PERSONALIZATION: &[u8; 16] = b"Halo2-Transcript";
+const PREFIX_CHALLENGE: u8 = 0;
+const PREFIX_POINT: u8 = 1;
+const PREFIX_SCALAR: u8 = 2;
+const PREFIX_CHALLENGE_LO: u8 = 10;
+const PREFIX_CHALLENGE_HI: u8 = 11;
+
+/// `7^t`, where `r - 1 = 2^28 t`, the `2^28`-th root of unity of halo2.
+const ROOT_OF_UNITY: Fr =
+    MontFp!("1748695177688661943023146337482803886740723238769601073607632802312037301404");
+/// `7^(2^28)`, the generator of the cosets of the permutation argument.
+const DELTA: Fr =
+    MontFp!("4131629893567559867359510883348571134090853742863529169391034518566172092834");
+
+/// Hash function of the transcript.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptHash {
+    /// Blake2b, halo2's default.
+    Blake2b,
+    /// Keccak-256, for proofs verified on the EVM.
+    Keccak256,
+}
+
+/// Multiopen argument of the proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MultiOpen {
+    /// SHPLONK, halo2's `ProverSHPLONK`.
+    Shplonk,
+    /// GWC, halo2's `ProverGWC`.
+    Gwc,
+}
+
+/// halo2 verifying key.
+#[derive(Educe)]
+#[educe(Clone, Debug, PartialEq, Eq)]
+pub struct VerifyingKey<H: CurveHooks> {
+    /// Base two logarithm of the number of rows.
+    pub k: u32,
+    /// Whether the selectors were combined into fewer fixed columns.
+    pub compress_selectors: bool,
+    /// Commitments to the fixed columns.
+    pub fixed_commitments: Vec<G1Affine<H>>,
+    /// Commitments to the permutation of each column of the permutation argument.
+    pub permutation_commitments: Vec<G1Affine<H>>,
+    /// Bit packed selectors, only present when they are compressed.
+    pub selectors: Vec<u8>,
+    /// Constraint system of the circuit.
+    pub cs: ConstraintSystem,
+    /// Transcript representation of the key, as returned by halo2's
+    /// `VerifyingKey::transcript_repr`.
+    pub transcript_repr: Fr,
+}
+
+impl<H: CurveHooks> VerifyingKey<H> {
+    /// Number of rows of the circuit.
+    pub fn n(&self) -> u64 {
+        1 << self.k
+    }
+}
+
+/// Fiat-Shamir transcript of halo2 over the proof bytes.
+pub(crate) struct Transcript<'a> {
+    hasher: Hasher,
+    proof: &'a [u8],
+}
+
+enum Hasher {
+    Blake2b(blake2b_simd::State),
+    Keccak256(Keccak256),
+}
+
+impl<'a> Transcript<'a> {
+    pub(crate) fn new(hash: TranscriptHash, proof: &'a [u8]) -> Self {
+        let hasher = match hash {
+            TranscriptHash::Blake2b => Hasher::Blake2b(
+                blake2b_simd::Params::new()
+                    .hash_length(64)
+                    .personal(TRANSCRIPT_PERSONALIZATION)
+                    .to_state(),
+            ),
+            TranscriptHash::Keccak256 => {
+                Hasher::Keccak256(Keccak256::new_with_prefix(TRANSCRIPT_PERSONALIZATION))
+            }
+        };
+        Transcript { hasher, proof }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match &mut self.hasher {
+            Hasher::Blake2b(state) => {
+                state.update(data);
+            }
+            Hasher::Keccak256(state) => state.update(data),
+        }
+    }
+
+    pub(crate) fn common_scalar(&mut self, scalar: &Fr) {
+        self.update(&[PREFIX_SCALAR]);
+        self.update(&write_prime(scalar));
+    }
+
+    pub(crate) fn common_point<H: CurveHooks>(&mut self, point: &G1Affine<H>) -> Result<(), Error> {
+        let (x, y) = point.xy().ok_or(Error::MalformedProof)?;
+        self.update(&[PREFIX_POINT]);
+        self.update(&write_prime(&x));
+        self.update(&write_prime(&y));
+        Ok(())
+    }
+
+    /// Squeezes a challenge, from 64 bytes of output of the hasher. The state
+    /// keeps absorbing, i.e. the output is not fed back.
+    pub(crate) fn squeeze(&mut self) -> Fr {
+        self.update(&[PREFIX_CHALLENGE]);
+        let mut output = [0; 64];
+        match &self.hasher {
+            Hasher::Blake2b(state) => output.copy_from_slice(state.finalize().as_bytes()),
+            Hasher::Keccak256(state) => {
+                let lo = state.clone().chain_update([PREFIX_CHALLENGE_LO]).finalize();
+                let hi = state.clone().chain_update([PREFIX_CHALLENGE_HI]).finalize();
+                output[..32].copy_from_slice(&lo);
+                output[32..].copy_from_slice(&hi);
+            }
+        }
+        Fr::from_le_bytes_mod_order(&output)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.proof.len() < len {
+            return Err(Error::MalformedProof);
+        }
+        let (head, tail) = self.proof.split_at(len);
+        self.proof = tail;
+        Ok(head)
+    }
+
+    pub(crate) fn read_point<H: CurveHooks>(&mut self) -> Result<G1Affine<H>, Error> {
+        let point = read_g1(self.take(POINT_LEN)?).ok_or(Error::MalformedProof)?;
+        self.common_point(&point)?;
+        Ok(point)
+    }
+
+    pub(crate) fn read_points<H: CurveHooks>(
+        &mut self,
+        count: usize,
+    ) -> Result<Vec<G1Affine<H>>, Error> {
+        (0..count).map(|_| self.read_point()).collect()
+    }
+
+    pub(crate) fn read_scalar(&mut self) -> Result<Fr, Error> {
+        let scalar = read_prime(self.take(SCALAR_LEN)?).ok_or(Error::MalformedProof)?;
+        self.common_scalar(&scalar);
+        Ok(scalar)
+    }
+
+    pub(crate) fn read_scalars(&mut self, count: usize) -> Result<Vec<Fr>, Error> {
+        (0..count).map(|_| self.read_scalar()).collect()
+    }
+
+    /// Checks that the whole proof was read.
+    pub(crate) fn finish(self) -> Result<(), Error> {
+        match self.proof.is_empty() {
+            true => Ok(()),
+            false => Err(Error::MalformedProof),
+        }
+    }
+}
+
+/// Evaluation domain of `n` rows.
+struct Domain {
+    n: u64,
+    omega: Fr,
+    omega_inv: Fr,
+}
+
+impl Domain {
+    fn new(k: u32) -> Self {
+        let mut omega = ROOT_OF_UNITY;
+        for _ in k..MAX_K {
+            omega.square_in_place();
+        }
+        Domain {
+            n: 1 << k,
+            omega,
+            omega_inv: omega.inverse().expect("root of unity is not zero"),
+        }
+    }
+
+    fn rotate(&self, point: Fr, rotation: i64) -> Fr {
+        match rotation >= 0 {
+            true => point * self.omega.pow([rotation as u64]),
+            false => point * self.omega_inv.pow([rotation.unsigned_abs()]),
+        }
+    }
+
+    /// Evaluates at `x` the Lagrange polynomials of the rows `omega^i`, or returns
+    /// `None` if `x` is one of them.
+    fn lagrange_evals(
+        &self,
+        x: Fr,
+        xn: Fr,
+        rotations: impl Iterator<Item = i64> + Clone,
+    ) -> Option<Vec<Fr>> {
+        let mut evals: Vec<Fr> = rotations
+            .clone()
+            .map(|i| x - self.rotate(Fr::one(), i))
+            .collect();
+        if evals.iter().any(Zero::is_zero) {
+            return None;
+        }
+        batch_inversion(&mut evals);
+        let common = (xn - Fr::one()) * Fr::from(self.n).inverse()?;
+        Some(
+            evals
+                .into_iter()
+                .zip(rotations)
+                .map(|(eval, i)| self.rotate(eval * common, i))
+                .collect(),
+        )
+    }
+}
+
+/// Evaluations of a permutation product polynomial.
+struct PermutationSet {
+    eval: Fr,
+    next_eval: Fr,
+    last_eval: Option<Fr>,
+}
+
+/// Evaluations of the polynomials of a lookup argument.
+struct LookupEvals {
+    product: Fr,
+    product_next: Fr,
+    permuted_input: Fr,
+    permuted_input_inv: Fr,
+    permuted_table: Fr,
+}
+
+/// Verifies the proof of a single circuit against the verifying key and the
+/// values of its instance columns.
+///
+/// The KZG verifier key is made of `g[0]`, `g2` and `s_g2` of the halo2 params.
+pub fn verify<H: CurveHooks>(
+    pvk: &kzg::PreparedVerifierKey<H>,
+    vk: &VerifyingKey<H>,
+    instances: &[&[Fr]],
+    proof: &[u8],
+    hash: TranscriptHash,
+    multiopen: MultiOpen,
+) -> Result<bool, Error> {
+    let cs = &vk.cs;
+    if instances.len() != cs.num_instance_columns {
+        return Err(Error::InvalidPublicInputsLength {
+            expected: cs.num_instance_columns,
+            found: instances.len(),
+        });
+    }
+    let blinding_factors = cs.blinding_factors();
+    let usable_rows = (1..=MAX_K)
+        .contains(&vk.k)
+        .then(|| (vk.n() as usize).checked_sub(blinding_factors + 1))
+        .flatten();
+    let valid_vk = cs.is_valid()
+        && vk.fixed_commitments.len() == cs.num_fixed_columns
+        && vk.permutation_commitments.len() == cs.permutation.len()
+        && vk.fixed_commitments.iter().all(is_valid)
+        && vk.permutation_commitments.iter().all(is_valid);
+    let Some(usable_rows) = usable_rows.filter(|_| valid_vk) else {
+        return Err(Error::MalformedVerifyingKey);
+    };
+    if let Some(instance) = instances
+        .iter()
+        .find(|instance| instance.len() > usable_rows)
+    {
+        return Err(Error::InvalidPublicInputsLength {
+            expected: usable_rows,
+            found: instance.len(),
+        });
+    }
+    let degree = cs.degree();
+    let chunk_len = degree - 2;
+    let num_sets = cs.permutation.len().div_ceil(chunk_len);
+    let domain = Domain::new(vk.k);
+
+    // Commitments and challenges.
+    let mut transcript = Transcript::new(hash, proof);
+    transcript.common_scalar(&vk.transcript_repr);
+    instances
+        .iter()
+        .flat_map(|instance| instance.iter())
+        .for_each(|value| transcript.common_scalar(value));
+    let mut advice_commitments = vec![G1Affine::<H>::zero(); cs.num_advice_columns()];
+    let mut challenges = vec![Fr::zero(); cs.challenge_phase.len()];
+    for phase in cs.phases() {
+        for (column_phase, commitment) in cs.advice_column_phase.iter().zip(&mut advice_commitments)
+        {
+            if *column_phase == phase {
+                *commitment = transcript.read_point()?;
+            }
+        }
+        for (challenge_phase, challenge) in cs.challenge_phase.iter().zip(&mut challenges) {
+            if *challenge_phase == phase {
+                *challenge = transcript.squeeze();
+            }
+        }
+    }
+    let theta = transcript.squeeze();
+    let lookup_permuted = transcript.read_points::<H>(2 * cs.lookups.len())?;
+    let beta = transcript.squeeze();
+    let gamma = transcript.squeeze();
+    let permutation_products = transcript.read_points::<H>(num_sets)?;
+    let lookup_products = transcript.read_points::<H>(cs.lookups.len())?;
+    let random_poly = transcript.read_point::<H>()?;
+    let y = transcript.squeeze();
+    let h_commitments = transcript.read_points::<H>(degree - 1)?;
+    let x = transcript.squeeze();
+
+    // Evaluations.
+    let advice_evals = transcript.read_scalars(cs.advice_queries.len())?;
+    let fixed_evals = transcript.read_scalars(cs.fixed_queries.len())?;
+    let random_eval = transcript.read_scalar()?;
+    let permutation_evals = transcript.read_scalars(cs.permutation.len())?;
+    let permutation_sets = (0..num_sets)
+        .map(|i| {
+            Ok(PermutationSet {
+                eval: transcript.read_scalar()?,
+                next_eval: transcript.read_scalar()?,
+                last_eval: match i + 1 < num_sets {
+                    true => Some(transcript.read_scalar()?),
+                    false => None,
+                },
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    let lookup_evals = cs
+        .lookups
+        .iter()
+        .map(|_| {
+            Ok(LookupEvals {
+                product: transcript.read_scalar()?,
+                product_next: transcript.read_scalar()?,
+                permuted_input: transcript.read_scalar()?,
+                permuted_input_inv: transcript.read_scalar()?,
+                permuted_table: transcript.read_scalar()?,
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    // Instance columns are not committed: their evaluations are computed from the
+    // values, `omega^rotation x` being a root of unity in the `i - rotation`-th row.
+    let xn = x.pow([domain.n]);
+    let mut instance_evals = Vec::with_capacity(cs.instance_queries.len());
+    for (column, rotation) in &cs.instance_queries {
+        let values = instances[*column];
+        let rotations = (0..values.len() as i64).map(|i| i - *rotation as i64);
+        let Some(lagrange) = domain.lagrange_evals(x, xn, rotations) else {
+            return Ok(false);
+        };
+        instance_evals.push(values.iter().zip(lagrange).map(|(v, l)| *v * l).sum());
+    }
+
+    // Vanishing argument: the constraints folded with the powers of `y` must equal
+    // `h(x) (x^n - 1)`.
+    let Some(lagrange) = domain.lagrange_evals(x, xn, -(blinding_factors as i64 + 1)..=0) else {
+        return Ok(false);
+    };
+    let l_last = lagrange[0];
+    let l_blind: Fr = lagrange[1..=blinding_factors].iter().sum();
+    let l_0 = lagrange[blinding_factors + 1];
+    let active_rows = Fr::one() - (l_last + l_blind);
+    let evaluate = |expr: &Expression| {
+        expr.evaluate(&fixed_evals, &advice_evals, &instance_evals, &challenges)
+    };
+    let column_eval = |column: &Column| {
+        let query = cs
+            .query_index(column)
+            .expect("permutation columns are queried");
+        match column.column_type {
+            ColumnType::Fixed => fixed_evals[query],
+            ColumnType::Advice => advice_evals[query],
+            ColumnType::Instance => instance_evals[query],
+        }
+    };
+
+    let mut constraints: Vec<Fr> = cs.gates.iter().map(evaluate).collect();
+    if let (Some(first), Some(last)) = (permutation_sets.first(), permutation_sets.last()) {
+        constraints.push(l_0 * (Fr::one() - first.eval));
+        constraints.push(l_last * (last.eval.square() - last.eval));
+    }
+    for (set, previous) in permutation_sets.iter().skip(1).zip(&permutation_sets) {
+        let previous_last = previous
+            .last_eval
+            .expect("only the last set has no last eval");
+        constraints.push(l_0 * (set.eval - previous_last));
+    }
+    let chunks = permutation_sets
+        .iter()
+        .zip(cs.permutation.chunks(chunk_len))
+        .zip(permutation_evals.chunks(chunk_len));
+    let mut delta_power = beta * x;
+    for ((set, columns), sigma_evals) in chunks {
+        let mut left = set.next_eval;
+        let mut right = set.eval;
+        for (column, sigma_eval) in columns.iter().zip(sigma_evals) {
+            let eval = column_eval(column);
+            left *= eval + beta * sigma_eval + gamma;
+            right *= eval + delta_power + gamma;
+            delta_power *= DELTA;
+        }
+        constraints.push((left - right) * active_rows);
+    }
+    for (lookup, evals) in cs.lookups.iter().zip(&lookup_evals) {
+        let compress = |exprs: &[Expression]| {
+            exprs
+                .iter()
+                .fold(Fr::zero(), |acc, expr| acc * theta + evaluate(expr))
+        };
+        let left =
+            evals.product_next * (evals.permuted_input + beta) * (evals.permuted_table + gamma);
+        let right = evals.product
+            * (compress(&lookup.input_expressions) + beta)
+            * (compress(&lookup.table_expressions) + gamma);
+        let input_minus_table = evals.permuted_input - evals.permuted_table;
+        constraints.extend([
+            l_0 * (Fr::one() - evals.product),
+            l_last * (evals.product.square() - evals.product),
+            (left - right) * active_rows,
+            l_0 * input_minus_table,
+            input_minus_table * (evals.permuted_input - evals.permuted_input_inv) * active_rows,
+        ]);
+    }
+    let Some(vanishing_inv) = (xn - Fr::one()).inverse() else {
+        return Ok(false);
+    };
+    let expected_h_eval = constraints
+        .into_iter()
+        .fold(Fr::zero(), |acc, constraint| acc * y + constraint)
+        * vanishing_inv;
+
+    // Openings, in halo2's order, commitments being referred to by index.
+    let mut commitments = Vec::new();
+    let mut commit = |terms: &[(G1Affine<H>, Fr)]| {
+        let mut msm = Msm::new();
+        terms
+            .iter()
+            .for_each(|(base, scalar)| msm.push(*base, *scalar));
+        commitments.push(msm);
+        commitments.len() - 1
+    };
+    let advice_ids: Vec<usize> = advice_commitments
+        .iter()
+        .map(|c| commit(&[(*c, Fr::one())]))
+        .collect();
+    let fixed_ids: Vec<usize> = vk
+        .fixed_commitments
+        .iter()
+        .map(|c| commit(&[(*c, Fr::one())]))
+        .collect();
+    let permutation_ids: Vec<usize> = vk
+        .permutation_commitments
+        .iter()
+        .map(|c| commit(&[(*c, Fr::one())]))
+        .collect();
+    let set_ids: Vec<usize> = permutation_products
+        .iter()
+        .map(|c| commit(&[(*c, Fr::one())]))
+        .collect();
+    let lookup_ids: Vec<[usize; 3]> = lookup_permuted
+        .chunks(2)
+        .zip(&lookup_products)
+        .map(|(permuted, product)| {
+            [
+                commit(&[(permuted[0], Fr::one())]),
+                commit(&[(permuted[1], Fr::one())]),
+                commit(&[(*product, Fr::one())]),
+            ]
+        })
+        .collect();
+    // `h(X)` is committed in pieces of degree `n - 1`.
+    let mut xn_power = Fr::one();
+    let h_terms: Vec<_> = h_commitments
+        .iter()
+        .map(|c| {
+            let term = (*c, xn_power);
+            xn_power *= xn;
+            term
+        })
+        .collect();
+    let h_id = commit(&h_terms);
+    let random_id = commit(&[(random_poly, Fr::one())]);
+
+    let x_next = domain.rotate(x, 1);
+    let x_prev = domain.rotate(x, -1);
+    let x_last = domain.rotate(x, -(blinding_factors as i64 + 1));
+    let query = |commitment, point, eval| Query {
+        commitment,
+        point,
+        eval,
+    };
+    let mut queries = Vec::new();
+    for ((column, rotation), eval) in cs.advice_queries.iter().zip(&advice_evals) {
+        let point = domain.rotate(x, *rotation as i64);
+        queries.push(query(advice_ids[*column], point, *eval));
+    }
+    for (id, set) in set_ids.iter().zip(&permutation_sets) {
+        queries.push(query(*id, x, set.eval));
+        queries.push(query(*id, x_next, set.next_eval));
+    }
+    for (id, set) in set_ids.iter().zip(&permutation_sets).rev().skip(1) {
+        let last_eval = set.last_eval.expect("only the last set has no last eval");
+        queries.push(query(*id, x_last, last_eval));
+    }
+    for ([input, table, product], evals) in lookup_ids.iter().zip(&lookup_evals) {
+        queries.extend([
+            query(*product, x, evals.product),
+            query(*input, x, evals.permuted_input),
+            query(*table, x, evals.permuted_table),
+            query(*input, x_prev, evals.permuted_input_inv),
+            query(*product, x_next, evals.product_next),
+        ]);
+    }
+    for ((column, rotation), eval) in cs.fixed_queries.iter().zip(&fixed_evals) {
+        let point = domain.rotate(x, *rotation as i64);
+        queries.push(query(fixed_ids[*column], point, *eval));
+    }
+    for (id, eval) in permutation_ids.iter().zip(&permutation_evals) {
+        queries.push(query(*id, x, *eval));
+    }
+    queries.push(query(h_id, x, expected_h_eval));
+    queries.push(query(random_id, x, random_eval));
+
+    let g1 = pvk.vk.g1;
+    let claim = match multiopen {
+        MultiOpen::Shplonk => multiopen::shplonk(&mut transcript, g1, &commitments, &queries)?,
+        MultiOpen::Gwc => multiopen::gwc(&mut transcript, g1, &commitments, &queries)?,
+    };
+    transcript.finish()?;
+    let Some(Claim { lhs, proof }) = claim else {
+        return Ok(false);
+    };
+    Ok(kzg::check(pvk, lhs.eval()?, proof.eval()?)?)
+}
+
+/// Decodes a verifying key in halo2's `SerdeFormat::Processed` format.
+///
+/// The constraint system and the transcript representation are not part of the
+/// encoding and must match the ones of the key.
+pub fn vk_from_bytes<H: CurveHooks>(
+    bytes: &[u8],
+    cs: ConstraintSystem,
+    transcript_repr: Fr,
+) -> Result<VerifyingKey<H>, Error> {
+    let mut reader = Reader(bytes);
+    let header = reader.take(HEADER_LEN)?;
+    let k = u32::from_le_bytes(header[1..5].try_into().expect("slice is 4 bytes long"));
+    let compress_selectors = match header[5] {
+        0 => false,
+        1 => true,
+        _ => return Err(Error::MalformedVerifyingKey),
+    };
+    let num_fixed = u32::from_le_bytes(header[6..].try_into().expect("slice is 4 bytes long"));
+    if header[0] != VERSION
+        || !(1..=MAX_K).contains(&k)
+        || num_fixed as usize != cs.num_fixed_columns
+    {
+        return Err(Error::MalformedVerifyingKey);
+    }
+    let fixed_commitments = reader.points(cs.num_fixed_columns)?;
+    let permutation_commitments = reader.points(cs.permutation.len())?;
+    // Selectors are packed by eight rows.
+    let selectors = reader.0.to_vec();
+    let selector_len = (1usize << k).div_ceil(8);
+    if selectors.len() % selector_len != 0 || !compress_selectors && !selectors.is_empty() {
+        return Err(Error::MalformedVerifyingKey);
+    }
+    Ok(VerifyingKey {
+        k,
+        compress_selectors,
+        fixed_commitments,
+        permutation_commitments,
+        selectors,
+        cs,
+        transcript_repr,
+    })
+}
+
+/// Encodes a verifying key in halo2's `SerdeFormat::Processed` format.
+pub fn vk_to_bytes<H: CurveHooks>(vk: &VerifyingKey<H>) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(
+        HEADER_LEN
+            + (vk.fixed_commitments.len() + vk.permutation_commitments.len()) * POINT_LEN
+            + vk.selectors.len(),
+    );
+    bytes.push(VERSION);
+    bytes.extend(vk.k.to_le_bytes());
+    bytes.push(vk.compress_selectors as u8);
+    bytes.extend((vk.fixed_commitments.len() as u32).to_le_bytes());
+    vk.fixed_commitments
+        .iter()
+        .chain(&vk.permutation_commitments)
+        .for_each(|point| bytes.extend(write_g1(point)));
+    bytes.extend(&vk.selectors);
+    bytes
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.0.len() < len {
+            return Err(Error::MalformedVerifyingKey);
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn points<H: CurveHooks>(&mut self, count: usize) -> Result<Vec<G1Affine<H>>, Error> {
+        (0..count)
+            .map(|_| read_g1(self.take(POINT_LEN)?).ok_or(Error::MalformedVerifyingKey))
+            .collect()
+    }
+}
+
+/// Reads a compressed point: `x` as little-endian bytes, with the top bit
+/// flagging the point at infinity and the next one the parity of `y`.
+fn read_g1<H: CurveHooks>(bytes: &[u8]) -> Option<G1Affine<H>> {
+    let mut bytes: [u8; POINT_LEN] = bytes.try_into().ok()?;
+    let flags = bytes[POINT_LEN - 1] & (IDENTITY_FLAG | SIGN_FLAG);
+    bytes[POINT_LEN - 1] &= !flags;
+    let x: Fq = read_prime(&bytes)?;
+    if flags & IDENTITY_FLAG != 0 {
+        return (flags == IDENTITY_FLAG && x.is_zero()).then(G1Affine::<H>::zero);
+    }
+    let mut y = (x.square() * x + Fq::from(3)).sqrt()?;
+    if y.into_bigint().is_odd() != (flags == SIGN_FLAG) {
+        y = -y;
+    }
+    Some(G1Affine::<H>::new_unchecked(x, y))
+}
+
+fn write_g1<H: CurveHooks>(point: &G1Affine<H>) -> [u8; POINT_LEN] {
+    match point.xy() {
+        Some((x, y)) => {
+            let mut bytes = write_prime(&x);
+            if y.into_bigint().is_odd() {
+                bytes[POINT_LEN - 1] |= SIGN_FLAG;
+            }
+            bytes
+        }
+        None => {
+            let mut bytes = [0; POINT_LEN];
+            bytes[POINT_LEN - 1] = IDENTITY_FLAG;
+            bytes
+        }
+    }
+}
+
+/// Reads a canonical little-endian field element.
+fn read_prime<F: PrimeField<BigInt = BigInt<4>>>(bytes: &[u8]) -> Option<F> {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+        *limb = u64::from_le_bytes(chunk.try_into().expect("chunk is 8 bytes long"));
+    }
+    F::from_bigint(BigInt(limbs))
+}
+
+fn write_prime<F: PrimeField<BigInt = BigInt<4>>>(value: &F) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    bytes.copy_from_slice(&value.into_bigint().to_bytes_le());
+    bytes
+}
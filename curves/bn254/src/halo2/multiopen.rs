@@ -0,0 +1,226 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Multiopen arguments of halo2, reducing the opening claims of the verifier to
+//! a single KZG pairing check.
+//!
+//! Commitments are referred to by their index, as halo2 tells them apart by
+//! reference and not by value.
+
+use ark_ff::{Field, One, Zero};
+use ark_std::{vec, vec::Vec};
+
+use super::{Error, Transcript};
+use crate::{
+    kzg::{interpolate, vanishing},
+    CurveHooks, Fr, G1Affine, G1Projective, TryCurveOps,
+};
+
+/// Claimed evaluation of a commitment at a point.
+pub(crate) struct Query {
+    pub(crate) commitment: usize,
+    pub(crate) point: Fr,
+    pub(crate) eval: Fr,
+}
+
+/// Linear combination of points.
+pub(crate) struct Msm<H: CurveHooks> {
+    bases: Vec<G1Affine<H>>,
+    scalars: Vec<Fr>,
+}
+
+impl<H: CurveHooks> Msm<H> {
+    pub(crate) fn new() -> Self {
+        Msm {
+            bases: Vec::new(),
+            scalars: Vec::new(),
+        }
+    }
+
+    pub(crate) fn push(&mut self, base: G1Affine<H>, scalar: Fr) {
+        self.bases.push(base);
+        self.scalars.push(scalar);
+    }
+
+    fn extend_scaled(&mut self, other: &Msm<H>, scale: Fr) {
+        self.bases.extend_from_slice(&other.bases);
+        self.scalars
+            .extend(other.scalars.iter().map(|scalar| *scalar * scale));
+    }
+
+    pub(crate) fn eval(&self) -> Result<G1Projective<H>, Error> {
+        // A single unscaled point needs no MSM.
+        if let ([base], [scalar]) = (&self.bases[..], &self.scalars[..]) {
+            if scalar.is_one() {
+                return Ok((*base).into());
+            }
+        }
+        Ok(G1Projective::<H>::try_msm(&self.bases, &self.scalars)?)
+    }
+}
+
+/// Pairing check `e(lhs, g2) = e(proof, s_g2)` the openings reduce to.
+pub(crate) struct Claim<H: CurveHooks> {
+    pub(crate) lhs: Msm<H>,
+    pub(crate) proof: Msm<H>,
+}
+
+/// Returns `None` if a commitment is queried twice at the same point.
+fn check_collisions(queries: &[Query]) -> Option<()> {
+    let collision = queries.iter().enumerate().any(|(i, query)| {
+        queries[..i]
+            .iter()
+            .any(|other| other.commitment == query.commitment && other.point == query.point)
+    });
+    (!collision).then_some(())
+}
+
+fn find_eval(queries: &[Query], commitment: usize, point: Fr) -> Fr {
+    queries
+        .iter()
+        .find(|query| query.commitment == commitment && query.point == point)
+        .map(|query| query.eval)
+        .expect("commitment is queried at point")
+}
+
+/// SHPLONK multiopen: commitments are grouped by the set of points they are
+/// queried at, and the proof holds the quotient `h1` and its opening `h2` at `u`.
+pub(crate) fn shplonk<H: CurveHooks>(
+    transcript: &mut Transcript<'_>,
+    g1: G1Affine<H>,
+    commitments: &[Msm<H>],
+    queries: &[Query],
+) -> Result<Option<Claim<H>>, Error> {
+    if check_collisions(queries).is_none() {
+        return Ok(None);
+    }
+    let mut super_point_set = Vec::new();
+    let mut commitment_points: Vec<(usize, Vec<Fr>)> = Vec::new();
+    for query in queries {
+        if !super_point_set.contains(&query.point) {
+            super_point_set.push(query.point);
+        }
+        match commitment_points
+            .iter_mut()
+            .find(|(commitment, _)| *commitment == query.commitment)
+        {
+            Some((_, points)) => points.push(query.point),
+            None => commitment_points.push((query.commitment, vec![query.point])),
+        }
+    }
+    let mut rotation_sets: Vec<(Vec<Fr>, Vec<usize>)> = Vec::new();
+    for (commitment, points) in commitment_points {
+        let same_set =
+            |set: &[Fr]| set.len() == points.len() && set.iter().all(|p| points.contains(p));
+        match rotation_sets.iter_mut().find(|(set, _)| same_set(set)) {
+            Some((_, set_commitments)) => set_commitments.push(commitment),
+            None => rotation_sets.push((points, vec![commitment])),
+        }
+    }
+
+    let y = transcript.squeeze();
+    let v = transcript.squeeze();
+    let h1 = transcript.read_point()?;
+    let u = transcript.squeeze();
+    let h2 = transcript.read_point()?;
+
+    // Each set is scaled by the vanishing polynomial of the points outside of
+    // it, normalized by the one of the first set.
+    let mut lhs = Msm::new();
+    let (mut z_0, mut z_0_diff_inverse) = (Fr::zero(), Fr::zero());
+    let mut r_outer = Fr::zero();
+    let mut v_power = Fr::one();
+    for (i, (points, set_commitments)) in rotation_sets.iter().enumerate() {
+        let diffs = super_point_set
+            .iter()
+            .filter(|point| !points.contains(point));
+        let mut z_diff = vanishing(diffs, u);
+        if i == 0 {
+            z_0 = vanishing(points.iter(), u);
+            let Some(inverse) = z_diff.inverse() else {
+                return Ok(None);
+            };
+            z_0_diff_inverse = inverse;
+            z_diff = Fr::one();
+        } else {
+            z_diff *= z_0_diff_inverse;
+        }
+        let scale = v_power * z_diff;
+        let mut r_inner = Fr::zero();
+        let mut y_power = Fr::one();
+        for commitment in set_commitments {
+            let evals: Vec<Fr> = points
+                .iter()
+                .map(|point| find_eval(queries, *commitment, *point))
+                .collect();
+            r_inner += y_power * interpolate(points, &evals, u);
+            lhs.extend_scaled(&commitments[*commitment], y_power * scale);
+            y_power *= y;
+        }
+        r_outer += scale * r_inner;
+        v_power *= v;
+    }
+    lhs.push(g1, -r_outer);
+    lhs.push(h1, -z_0);
+    lhs.push(h2, u);
+    let mut proof = Msm::new();
+    proof.push(h2, Fr::one());
+    Ok(Some(Claim { lhs, proof }))
+}
+
+/// GWC multiopen: commitments are grouped by point, and the proof holds one
+/// opening per point.
+pub(crate) fn gwc<H: CurveHooks>(
+    transcript: &mut Transcript<'_>,
+    g1: G1Affine<H>,
+    commitments: &[Msm<H>],
+    queries: &[Query],
+) -> Result<Option<Claim<H>>, Error> {
+    if check_collisions(queries).is_none() {
+        return Ok(None);
+    }
+    let mut point_sets: Vec<(Fr, Vec<&Query>)> = Vec::new();
+    for query in queries {
+        match point_sets
+            .iter_mut()
+            .find(|(point, _)| *point == query.point)
+        {
+            Some((_, set)) => set.push(query),
+            None => point_sets.push((query.point, vec![query])),
+        }
+    }
+
+    let v = transcript.squeeze();
+    let witnesses = transcript.read_points(point_sets.len())?;
+    let u = transcript.squeeze();
+
+    let mut lhs = Msm::new();
+    let mut proof = Msm::new();
+    let mut eval = Fr::zero();
+    let mut u_power = Fr::one();
+    for ((point, set), witness) in point_sets.iter().zip(witnesses) {
+        let mut v_power = Fr::one();
+        for query in set {
+            lhs.extend_scaled(&commitments[query.commitment], u_power * v_power);
+            eval += u_power * v_power * query.eval;
+            v_power *= v;
+        }
+        lhs.push(witness, u_power * point);
+        proof.push(witness, u_power);
+        u_power *= u;
+    }
+    lhs.push(g1, -eval);
+    Ok(Some(Claim { lhs, proof }))
+}
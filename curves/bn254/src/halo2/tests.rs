@@ -0,0 +1,323 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::{
+    gnark::g2_from_raw,
+    metering::{GasSchedule, MeteredHooks, ThreadBudget, OUT_OF_GAS},
+    native::NativeHooks,
+};
+
+/// Verifying key of the test circuit, written by `halo2-axiom` 0.5.
+const VK: &[u8] = include_bytes!("test-data/vk.bin");
+/// `s_g2` of the params the key was generated with.
+const S_G2: &[u8] = include_bytes!("test-data/s_g2.bin");
+/// Proofs of the test circuit for each transcript and multiopen argument.
+const PROOFS: [(TranscriptHash, MultiOpen, &[u8]); 4] = [
+    (
+        TranscriptHash::Blake2b,
+        MultiOpen::Shplonk,
+        include_bytes!("test-data/blake2b_shplonk.bin"),
+    ),
+    (
+        TranscriptHash::Blake2b,
+        MultiOpen::Gwc,
+        include_bytes!("test-data/blake2b_gwc.bin"),
+    ),
+    (
+        TranscriptHash::Keccak256,
+        MultiOpen::Shplonk,
+        include_bytes!("test-data/keccak_shplonk.bin"),
+    ),
+    (
+        TranscriptHash::Keccak256,
+        MultiOpen::Gwc,
+        include_bytes!("test-data/keccak_gwc.bin"),
+    ),
+];
+/// `VerifyingKey::transcript_repr` of the key.
+const TRANSCRIPT_REPR: [u8; 32] =
+    hex_literal::hex!("8ea70f2b1c3dfcb4956f3366632feed71b52d72824c443693c111c3f05865421");
+/// Value of the instance cell of the test circuit.
+const INSTANCE: u64 = 0x1234;
+
+/// Constraint system of the test circuit: a standard PLONK gate with an instance
+/// column, a gate with rotations, a gate using a second phase column and a
+/// challenge, and a lookup into a fixed table. Selectors are fixed columns 6 to 8.
+fn test_cs() -> ConstraintSystem {
+    let f = Expression::Fixed;
+    let a = Expression::Advice;
+    ConstraintSystem {
+        num_fixed_columns: 9,
+        num_instance_columns: 1,
+        advice_column_phase: vec![0, 0, 0, 1],
+        challenge_phase: vec![0],
+        fixed_queries: (0..9).map(|column| (column, 0)).collect(),
+        advice_queries: vec![(0, 0), (1, 0), (2, 0), (0, -1), (1, 1), (3, 0)],
+        instance_queries: vec![(0, 0)],
+        gates: vec![
+            f(0) * a(0)
+                + f(1) * a(1)
+                + f(2) * a(2)
+                + f(3) * a(0) * a(1)
+                + f(4)
+                + Expression::Instance(0),
+            f(6) * (a(2) - a(3) * a(4)),
+            f(7) * (a(5) - Expression::Challenge(0) * a(0)),
+        ],
+        permutation: vec![
+            Column::new(ColumnType::Advice, 0),
+            Column::new(ColumnType::Advice, 1),
+            Column::new(ColumnType::Advice, 2),
+            Column::new(ColumnType::Instance, 0),
+        ],
+        lookups: vec![Lookup {
+            input_expressions: vec![f(8) * a(0)],
+            table_expressions: vec![f(5)],
+        }],
+        minimum_degree: None,
+    }
+}
+
+fn load<H: CurveHooks>() -> (kzg::PreparedVerifierKey<H>, VerifyingKey<H>) {
+    let transcript_repr = read_prime(&TRANSCRIPT_REPR).unwrap();
+    let vk = vk_from_bytes(VK, test_cs(), transcript_repr).unwrap();
+    let pvk = kzg::prepare_verifier_key(kzg::VerifierKey {
+        g1: G1Affine::<H>::generator(),
+        g2: crate::G2Affine::<H>::generator(),
+        tau_g2: g2_from_raw(S_G2.try_into().unwrap()).unwrap(),
+    })
+    .unwrap();
+    (pvk, vk)
+}
+
+#[test]
+fn constants_match_halo2() {
+    assert_eq!(ROOT_OF_UNITY.pow([1 << 28]), Fr::one());
+    assert_ne!(ROOT_OF_UNITY.pow([1 << 27]), Fr::one());
+    assert_eq!(DELTA, Fr::from(7).pow([1 << 28]));
+}
+
+#[test]
+fn vk_round_trip() {
+    let (_, vk) = load::<NativeHooks>();
+    assert_eq!(vk.k, 5);
+    assert!(!vk.compress_selectors);
+    assert_eq!(vk.cs.degree(), 5);
+    assert_eq!(vk.cs.blinding_factors(), 5);
+    assert_eq!(vk_to_bytes(&vk), VK);
+}
+
+#[test]
+fn malformed_vks_are_rejected() {
+    let repr = Fr::one();
+    let mut bytes = VK.to_vec();
+    bytes[0] = 0x01;
+    assert_eq!(
+        vk_from_bytes::<NativeHooks>(&bytes, test_cs(), repr),
+        Err(Error::MalformedVerifyingKey)
+    );
+    let mut cs = test_cs();
+    cs.num_fixed_columns = 8;
+    assert_eq!(
+        vk_from_bytes::<NativeHooks>(VK, cs, repr),
+        Err(Error::MalformedVerifyingKey)
+    );
+    // Selectors are only present when compressed.
+    let mut bytes = VK.to_vec();
+    bytes.extend([0; 4]);
+    assert_eq!(
+        vk_from_bytes::<NativeHooks>(&bytes, test_cs(), repr),
+        Err(Error::MalformedVerifyingKey)
+    );
+    assert_eq!(
+        vk_from_bytes::<NativeHooks>(&VK[..VK.len() - 1], test_cs(), repr),
+        Err(Error::MalformedVerifyingKey)
+    );
+
+    // Queries must refer to existing columns.
+    let (pvk, mut vk) = load::<NativeHooks>();
+    vk.cs.gates.push(Expression::Advice(6));
+    let (_, _, proof) = PROOFS[0];
+    assert_eq!(
+        verify(
+            &pvk,
+            &vk,
+            &[&[Fr::from(INSTANCE)]],
+            proof,
+            TranscriptHash::Blake2b,
+            MultiOpen::Shplonk
+        ),
+        Err(Error::MalformedVerifyingKey)
+    );
+}
+
+#[test]
+fn points_round_trip() {
+    let g = G1Affine::<NativeHooks>::generator();
+    let points = [g, -g, (g * Fr::from(5)).into(), G1Affine::zero()];
+    for point in points {
+        assert_eq!(read_g1(&write_g1(&point)), Some(point));
+    }
+    // The generator is `(1, 2)`, with an even `y`.
+    let mut bytes = [0; POINT_LEN];
+    bytes[0] = 1;
+    assert_eq!(write_g1(&g), bytes);
+    bytes[POINT_LEN - 1] = IDENTITY_FLAG;
+    assert_eq!(read_g1::<NativeHooks>(&bytes), None);
+}
+
+#[test]
+fn halo2_proofs_are_verified() {
+    let (pvk, vk) = load::<NativeHooks>();
+    let instance = [Fr::from(INSTANCE)];
+    for (hash, multiopen, proof) in PROOFS {
+        assert_eq!(
+            verify(&pvk, &vk, &[&instance], proof, hash, multiopen),
+            Ok(true)
+        );
+    }
+}
+
+#[test]
+fn invalid_proofs_are_rejected() {
+    let (pvk, vk) = load::<NativeHooks>();
+    let instance = [Fr::from(INSTANCE)];
+    let (hash, multiopen, proof) = PROOFS[0];
+
+    let wrong_instance = [Fr::from(INSTANCE + 1)];
+    assert_eq!(
+        verify(&pvk, &vk, &[&wrong_instance], proof, hash, multiopen),
+        Ok(false)
+    );
+    assert_eq!(
+        verify(
+            &pvk,
+            &vk,
+            &[&instance],
+            proof,
+            TranscriptHash::Keccak256,
+            multiopen
+        ),
+        Ok(false)
+    );
+
+    // Tamper with the first advice evaluation, which follows the 14 commitments.
+    let mut tampered = proof.to_vec();
+    tampered[14 * POINT_LEN] ^= 1;
+    assert_eq!(
+        verify(&pvk, &vk, &[&instance], &tampered, hash, multiopen),
+        Ok(false)
+    );
+
+    let mut transcript_repr = vk.clone();
+    transcript_repr.transcript_repr += Fr::one();
+    assert_eq!(
+        verify(&pvk, &transcript_repr, &[&instance], proof, hash, multiopen),
+        Ok(false)
+    );
+}
+
+#[test]
+fn malformed_proofs_are_rejected() {
+    let (pvk, vk) = load::<NativeHooks>();
+    let instance = [Fr::from(INSTANCE)];
+    let (hash, multiopen, proof) = PROOFS[0];
+
+    assert_eq!(
+        verify(
+            &pvk,
+            &vk,
+            &[&instance],
+            &proof[..proof.len() - 1],
+            hash,
+            multiopen
+        ),
+        Err(Error::MalformedProof)
+    );
+    let mut trailing = proof.to_vec();
+    trailing.push(0);
+    assert_eq!(
+        verify(&pvk, &vk, &[&instance], &trailing, hash, multiopen),
+        Err(Error::MalformedProof)
+    );
+    // SHPLONK proofs are shorter than GWC ones.
+    assert_eq!(
+        verify(&pvk, &vk, &[&instance], proof, hash, MultiOpen::Gwc),
+        Err(Error::MalformedProof)
+    );
+    // The point at infinity can't be hashed into the transcript.
+    let mut infinity = proof.to_vec();
+    infinity[..POINT_LEN].copy_from_slice(&write_g1(&G1Affine::<NativeHooks>::zero()));
+    assert_eq!(
+        verify(&pvk, &vk, &[&instance], &infinity, hash, multiopen),
+        Err(Error::MalformedProof)
+    );
+    let mut non_canonical = proof.to_vec();
+    non_canonical[14 * POINT_LEN..15 * POINT_LEN].fill(0xff);
+    assert_eq!(
+        verify(&pvk, &vk, &[&instance], &non_canonical, hash, multiopen),
+        Err(Error::MalformedProof)
+    );
+}
+
+#[test]
+fn instances_are_checked() {
+    let (pvk, vk) = load::<NativeHooks>();
+    let (hash, multiopen, proof) = PROOFS[0];
+    assert_eq!(
+        verify(&pvk, &vk, &[], proof, hash, multiopen),
+        Err(Error::InvalidPublicInputsLength {
+            expected: 1,
+            found: 0
+        })
+    );
+    // The last `blinding_factors + 1` rows are not usable.
+    let instance = vec![Fr::one(); 27];
+    assert_eq!(
+        verify(&pvk, &vk, &[&instance], proof, hash, multiopen),
+        Err(Error::InvalidPublicInputsLength {
+            expected: 26,
+            found: 27
+        })
+    );
+}
+
+#[test]
+fn verify_uses_the_hooks() {
+    type Hooks = MeteredHooks<NativeHooks, ThreadBudget>;
+    let schedule = GasSchedule::EIP_1108;
+    let (pvk, vk) = load::<Hooks>();
+    let instance = [Fr::from(INSTANCE)];
+    let (hash, multiopen, proof) = PROOFS[0];
+
+    // 4 advice, 9 fixed, 4 permutation, 2 permutation products, 3 lookup, 4
+    // quotient and 1 random commitments, plus `g1`, `h1` and `h2`.
+    let bases = 27 + 3;
+    ThreadBudget::reset(u64::MAX);
+    assert_eq!(
+        verify(&pvk, &vk, &[&instance], proof, hash, multiopen),
+        Ok(true)
+    );
+    assert_eq!(
+        ThreadBudget::used(),
+        schedule.msm_g1_cost(bases) + schedule.ec_pairing_cost(2)
+    );
+    ThreadBudget::reset(schedule.msm_g1_cost(bases));
+    assert_eq!(
+        verify(&pvk, &vk, &[&instance], proof, hash, multiopen),
+        Err(Error::Hook(OUT_OF_GAS))
+    );
+}
@@ -0,0 +1,640 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [UltraHonk](https://github.com/AztecProtocol/aztec-packages/tree/master/barretenberg)
+//! verifier, for the proofs of Noir circuits compiled with Barretenberg.
+//!
+//! Follows the Keccak-256 flavour of Barretenberg, the one targeting its Solidity
+//! verifier. The Ultra arithmetization has four wires and arithmetic, permutation,
+//! log-derivative lookup, delta range, elliptic, auxiliary (non native field and
+//! memory) and Poseidon2 relations. The verifier:
+//! * replays the transcript, each challenge hashing the previous one and the proof
+//!   elements sent since;
+//! * checks the sumcheck rounds and the batched relations at the sumcheck
+//!   challenge `u`;
+//! * reduces the claimed evaluations at `u` to a single KZG opening with Gemini and
+//!   Shplonk (Shplemini), checked with one MSM and one pairing check.
+//!
+//! Proofs have `log_n` sumcheck rounds and Gemini folds, i.e. they are not padded
+//! to a constant size.
+//!
+//! Barretenberg UltraPlonk proofs are not supported yet: their verifying key
+//! layout, transcript and linearization differ from UltraHonk and need a verifier
+//! of their own.
+
+use ark_ff::{AdditiveGroup, Field, MontFp, One, PrimeField, Zero};
+use ark_models_ext::AffineRepr;
+use ark_std::{vec, vec::Vec};
+use educe::Educe;
+use sha3::{Digest, Keccak256};
+
+use crate::{
+    eth::{read_g1, write_g1, G1_LEN},
+    gnark::{fr_from_bytes, fr_to_bytes},
+    groth16::is_valid,
+    kzg::{self, interpolate},
+    CurveHooks, Fq2, Fr, G1Affine, G1Projective, G2Affine, TryCurveOps,
+};
+
+pub use crate::plonk::Error;
+pub use relations::NUMBER_OF_ENTITIES;
+
+mod relations;
+#[cfg(test)]
+mod tests;
+
+use relations::{Parameters, NUMBER_OF_ALPHAS, NUMBER_UNSHIFTED};
+
+/// Length of the sumcheck round univariates.
+pub const BATCHED_RELATION_PARTIAL_LENGTH: usize = 8;
+/// Maximum base two logarithm of the circuit size.
+pub const MAX_LOG_CIRCUIT_SIZE: u32 = 28;
+/// Length of an encoded verifying key.
+pub const VK_LEN: usize = 4 * WORD_LEN + NUMBER_OF_PRECOMPUTED * G1_LEN;
+
+/// Number of commitments of the verifying key.
+pub const NUMBER_OF_PRECOMPUTED: usize = 27;
+
+const WORD_LEN: usize = 32;
+
+/// Barretenberg UltraHonk verifying key.
+#[derive(Educe)]
+#[educe(Clone, Debug, PartialEq, Eq)]
+pub struct VerifyingKey<H: CurveHooks> {
+    /// Base two logarithm of the circuit size.
+    pub log_circuit_size: u32,
+    /// Number of public inputs.
+    pub num_public_inputs: usize,
+    /// Row of the first public input.
+    pub pub_inputs_offset: u64,
+    /// Commitments to the selectors, permutations, tables and Lagrange
+    /// polynomials, in the order of [`VerifyingKey::commitments`].
+    pub commitments: [G1Affine<H>; NUMBER_OF_PRECOMPUTED],
+}
+
+impl<H: CurveHooks> VerifyingKey<H> {
+    /// Number of rows of the circuit.
+    pub fn circuit_size(&self) -> u64 {
+        1 << self.log_circuit_size
+    }
+
+    /// Commitments to `q_m`, `q_c`, `q_l`, `q_r`, `q_o`, `q_4`, `q_lookup`,
+    /// `q_arith`, `q_range`, `q_elliptic`, `q_aux`, `q_poseidon2_external`,
+    /// `q_poseidon2_internal`, `sigma_1..4`, `id_1..4`, `table_1..4`,
+    /// `lagrange_first` and `lagrange_last`.
+    pub fn commitments(&self) -> &[G1Affine<H>; NUMBER_OF_PRECOMPUTED] {
+        &self.commitments
+    }
+}
+
+/// Barretenberg UltraHonk proof.
+#[derive(Educe)]
+#[educe(Clone, Debug, PartialEq, Eq)]
+pub struct Proof<H: CurveHooks> {
+    /// Commitments to the wires `w_1..4`.
+    pub w: [G1Affine<H>; 4],
+    /// Commitment to the lookup read counts.
+    pub lookup_read_counts: G1Affine<H>,
+    /// Commitment to the lookup read tags.
+    pub lookup_read_tags: G1Affine<H>,
+    /// Commitment to the lookup inverses.
+    pub lookup_inverses: G1Affine<H>,
+    /// Commitment to the permutation grand product.
+    pub z_perm: G1Affine<H>,
+    /// Univariates of the sumcheck rounds, evaluated on `0..8`.
+    pub sumcheck_univariates: Vec<[Fr; BATCHED_RELATION_PARTIAL_LENGTH]>,
+    /// Evaluations of the entities at the sumcheck challenge.
+    pub sumcheck_evaluations: [Fr; NUMBER_OF_ENTITIES],
+    /// Commitments to the Gemini folds `A_1..A_{log_n - 1}`.
+    pub gemini_fold_comms: Vec<G1Affine<H>>,
+    /// Evaluations `A_i(-r^{2^i})` of the Gemini folds.
+    pub gemini_a_evaluations: Vec<Fr>,
+    /// Shplonk batched quotient commitment.
+    pub shplonk_q: G1Affine<H>,
+    /// KZG opening proof at the Shplonk challenge.
+    pub kzg_quotient: G1Affine<H>,
+}
+
+impl<H: CurveHooks> Proof<H> {
+    /// Witness commitments in entity order.
+    fn witness_commitments(&self) -> [G1Affine<H>; 8] {
+        let [w_1, w_2, w_3, w_4] = self.w;
+        [
+            w_1,
+            w_2,
+            w_3,
+            w_4,
+            self.z_perm,
+            self.lookup_inverses,
+            self.lookup_read_counts,
+            self.lookup_read_tags,
+        ]
+    }
+}
+
+/// Keccak-256 transcript of Barretenberg.
+///
+/// Every challenge hashes the previous challenge and the data added since.
+pub(crate) struct Transcript(Keccak256);
+
+impl Transcript {
+    pub(crate) fn new() -> Self {
+        Transcript(Keccak256::new())
+    }
+
+    pub(crate) fn add_u64(&mut self, value: u64) {
+        let mut word = [0; WORD_LEN];
+        word[WORD_LEN - 8..].copy_from_slice(&value.to_be_bytes());
+        self.0.update(word);
+    }
+
+    pub(crate) fn add_point<H: CurveHooks>(&mut self, point: &G1Affine<H>) {
+        self.0.update(write_g1(point));
+    }
+
+    pub(crate) fn add_scalar(&mut self, scalar: &Fr) {
+        self.0.update(fr_to_bytes(scalar));
+    }
+
+    /// Returns the challenge split in its lower 128 bits and its upper bits.
+    pub(crate) fn challenge(&mut self) -> (Fr, Fr) {
+        let challenge = Fr::from_be_bytes_mod_order(&self.0.finalize_reset());
+        self.add_scalar(&challenge);
+        let bytes = fr_to_bytes(&challenge);
+        (
+            Fr::from_be_bytes_mod_order(&bytes[16..]),
+            Fr::from_be_bytes_mod_order(&bytes[..16]),
+        )
+    }
+}
+
+/// Fiat-Shamir challenges of the protocol.
+pub(crate) struct Challenges {
+    pub(crate) params: Parameters,
+    pub(crate) alphas: [Fr; NUMBER_OF_ALPHAS],
+    pub(crate) gate: Vec<Fr>,
+    pub(crate) sumcheck: Vec<Fr>,
+    pub(crate) rho: Fr,
+    pub(crate) gemini_r: Fr,
+    pub(crate) shplonk_nu: Fr,
+    pub(crate) shplonk_z: Fr,
+}
+
+impl Challenges {
+    /// Derives the challenges, `public_inputs_delta` being left to zero.
+    pub(crate) fn derive<H: CurveHooks>(
+        vk: &VerifyingKey<H>,
+        proof: &Proof<H>,
+        public_inputs: &[Fr],
+    ) -> Self {
+        let mut transcript = Transcript::new();
+        transcript.add_u64(vk.circuit_size());
+        transcript.add_u64(public_inputs.len() as u64);
+        transcript.add_u64(vk.pub_inputs_offset);
+        public_inputs
+            .iter()
+            .for_each(|input| transcript.add_scalar(input));
+        proof.w[..3]
+            .iter()
+            .for_each(|point| transcript.add_point(point));
+        let (eta, eta_two) = transcript.challenge();
+        let (eta_three, _) = transcript.challenge();
+
+        transcript.add_point(&proof.lookup_read_counts);
+        transcript.add_point(&proof.lookup_read_tags);
+        transcript.add_point(&proof.w[3]);
+        let (beta, gamma) = transcript.challenge();
+
+        transcript.add_point(&proof.lookup_inverses);
+        transcript.add_point(&proof.z_perm);
+        let mut alphas = [Fr::zero(); NUMBER_OF_ALPHAS];
+        for pair in alphas.chunks_mut(2) {
+            let (first, second) = transcript.challenge();
+            pair[0] = first;
+            if let Some(alpha) = pair.get_mut(1) {
+                *alpha = second;
+            }
+        }
+
+        let log_n = vk.log_circuit_size as usize;
+        let gate = (0..log_n).map(|_| transcript.challenge().0).collect();
+
+        let sumcheck = proof
+            .sumcheck_univariates
+            .iter()
+            .map(|univariate| {
+                univariate
+                    .iter()
+                    .for_each(|eval| transcript.add_scalar(eval));
+                transcript.challenge().0
+            })
+            .collect();
+
+        proof
+            .sumcheck_evaluations
+            .iter()
+            .for_each(|eval| transcript.add_scalar(eval));
+        let (rho, _) = transcript.challenge();
+
+        proof
+            .gemini_fold_comms
+            .iter()
+            .for_each(|point| transcript.add_point(point));
+        let (gemini_r, _) = transcript.challenge();
+
+        proof
+            .gemini_a_evaluations
+            .iter()
+            .for_each(|eval| transcript.add_scalar(eval));
+        let (shplonk_nu, _) = transcript.challenge();
+
+        transcript.add_point(&proof.shplonk_q);
+        let (shplonk_z, _) = transcript.challenge();
+
+        Challenges {
+            params: Parameters {
+                eta,
+                eta_two,
+                eta_three,
+                beta,
+                gamma,
+                public_inputs_delta: Fr::zero(),
+            },
+            alphas,
+            gate,
+            sumcheck,
+            rho,
+            gemini_r,
+            shplonk_nu,
+            shplonk_z,
+        }
+    }
+}
+
+/// Computes the contribution of the public inputs to the permutation grand
+/// product, the public inputs being copied from the second wire.
+pub(crate) fn public_inputs_delta(
+    public_inputs: &[Fr],
+    beta: Fr,
+    gamma: Fr,
+    circuit_size: u64,
+    offset: u64,
+) -> Option<Fr> {
+    let mut numerator = Fr::one();
+    let mut denominator = Fr::one();
+    let mut numerator_acc = gamma + beta * Fr::from(circuit_size + offset);
+    let mut denominator_acc = gamma - beta * Fr::from(offset + 1);
+    for input in public_inputs {
+        numerator *= numerator_acc + input;
+        denominator *= denominator_acc + input;
+        numerator_acc += beta;
+        denominator_acc -= beta;
+    }
+    denominator.inverse().map(|inv| numerator * inv)
+}
+
+/// Evaluates at `x` the round univariate given by its evaluations on `0..8`.
+pub(crate) fn evaluate_univariate(evals: &[Fr; BATCHED_RELATION_PARTIAL_LENGTH], x: Fr) -> Fr {
+    let points: [Fr; BATCHED_RELATION_PARTIAL_LENGTH] =
+        core::array::from_fn(|i| Fr::from(i as u64));
+    interpolate(&points, evals, x)
+}
+
+/// KZG verifier key of the Aztec Ignition SRS, used by Barretenberg.
+pub fn kzg_verifier_key<H: CurveHooks>() -> kzg::VerifierKey<H> {
+    let x = Fq2::new(
+        MontFp!("496075682290949347282619629729389528669750910289829251317610107342504362928"),
+        MontFp!("17212635814319756364507010169094758005397460366678210664966334781961899574209"),
+    );
+    let y = Fq2::new(
+        MontFp!("15828724851114720558251891430452666121603726704878231219287131634746610441813"),
+        MontFp!("2255182984359105691812395885056400739448730162863181907784180250290003009508"),
+    );
+    kzg::VerifierKey {
+        g1: G1Affine::<H>::generator(),
+        g2: G2Affine::<H>::generator(),
+        tau_g2: G2Affine::<H>::new_unchecked(x, y),
+    }
+}
+
+/// Verifies the proof against the verifying key and the public inputs, with the
+/// Aztec Ignition SRS.
+pub fn verify<H: CurveHooks>(
+    vk: &VerifyingKey<H>,
+    proof: &Proof<H>,
+    public_inputs: &[Fr],
+) -> Result<bool, Error> {
+    let pvk = kzg::prepare_verifier_key(kzg_verifier_key())?;
+    verify_with_kzg_key(&pvk, vk, proof, public_inputs)
+}
+
+/// Verifies the proof against the verifying key and the public inputs, with the
+/// given SRS.
+pub fn verify_with_kzg_key<H: CurveHooks>(
+    pvk: &kzg::PreparedVerifierKey<H>,
+    vk: &VerifyingKey<H>,
+    proof: &Proof<H>,
+    public_inputs: &[Fr],
+) -> Result<bool, Error> {
+    if public_inputs.len() != vk.num_public_inputs {
+        return Err(Error::InvalidPublicInputsLength {
+            expected: vk.num_public_inputs,
+            found: public_inputs.len(),
+        });
+    }
+    let log_n = vk.log_circuit_size as usize;
+    let valid_vk = (1..=MAX_LOG_CIRCUIT_SIZE).contains(&vk.log_circuit_size)
+        && vk.pub_inputs_offset + vk.num_public_inputs as u64 <= vk.circuit_size()
+        && vk.commitments.iter().all(is_valid);
+    if !valid_vk {
+        return Err(Error::MalformedVerifyingKey);
+    }
+    let valid_proof = proof.sumcheck_univariates.len() == log_n
+        && proof.gemini_fold_comms.len() == log_n - 1
+        && proof.gemini_a_evaluations.len() == log_n
+        && proof.witness_commitments().iter().all(is_valid)
+        && proof.gemini_fold_comms.iter().all(is_valid)
+        && is_valid(&proof.shplonk_q)
+        && is_valid(&proof.kzg_quotient);
+    if !valid_proof {
+        return Err(Error::MalformedProof);
+    }
+
+    let mut challenges = Challenges::derive(vk, proof, public_inputs);
+    let params = &mut challenges.params;
+    let Some(delta) = public_inputs_delta(
+        public_inputs,
+        params.beta,
+        params.gamma,
+        vk.circuit_size(),
+        vk.pub_inputs_offset,
+    ) else {
+        return Ok(false);
+    };
+    params.public_inputs_delta = delta;
+
+    // Sumcheck.
+    let mut target = Fr::zero();
+    let mut pow = Fr::one();
+    let rounds = proof
+        .sumcheck_univariates
+        .iter()
+        .zip(&challenges.sumcheck)
+        .zip(&challenges.gate);
+    for ((univariate, u), beta) in rounds {
+        if univariate[0] + univariate[1] != target {
+            return Ok(false);
+        }
+        target = evaluate_univariate(univariate, *u);
+        pow *= Fr::one() + *u * (*beta - Fr::one());
+    }
+    let evals = &proof.sumcheck_evaluations;
+    if relations::accumulate(evals, &challenges.params, &challenges.alphas, pow) != target {
+        return Ok(false);
+    }
+
+    // Shplemini: the entities are batched with the powers of `rho` into
+    // `A_0(X) = F(X) + G(X) / X`, `G` batching the shifted ones. `A_0` is opened
+    // at `r` and `-r`, the folds `A_i` at `-r^{2^i}`, and the openings are batched
+    // with the powers of `nu`, the `j`-th one being scaled by `1 / (z - x_j)`.
+    let Challenges {
+        rho,
+        gemini_r: r,
+        shplonk_nu: nu,
+        shplonk_z: z,
+        ..
+    } = challenges;
+    let mut r_powers = Vec::with_capacity(log_n);
+    let mut r_power = r;
+    for _ in 0..log_n {
+        r_powers.push(r_power);
+        r_power.square_in_place();
+    }
+    let (Some(pos_inv), Some(neg_inv), Some(r_inv)) =
+        ((z - r).inverse(), (z + r).inverse(), r.inverse())
+    else {
+        return Ok(false);
+    };
+    let unshifted_scalar = pos_inv + nu * neg_inv;
+    let shifted_scalar = r_inv * (pos_inv - nu * neg_inv);
+
+    let mut bases = Vec::with_capacity(NUMBER_UNSHIFTED + log_n + 3);
+    let mut scalars = Vec::with_capacity(NUMBER_UNSHIFTED + log_n + 3);
+    bases.push(proof.shplonk_q);
+    scalars.push(Fr::one());
+    bases.extend(vk.commitments);
+    bases.extend(proof.witness_commitments());
+    let mut batched_evaluation = Fr::zero();
+    let mut rho_power = Fr::one();
+    for (i, eval) in evals.iter().enumerate() {
+        batched_evaluation += *eval * rho_power;
+        if i < NUMBER_UNSHIFTED {
+            scalars.push(-unshifted_scalar * rho_power);
+        } else {
+            // Shifted wires and permutation come right after the unshifted ones.
+            scalars[1 + i - NUMBER_UNSHIFTED + relations::W_L] -= shifted_scalar * rho_power;
+        }
+        rho_power *= rho;
+    }
+
+    // `A_i(r^{2^i})` from `A_{i+1}(r^{2^{i+1}})` and `A_i(-r^{2^i})`, starting from
+    // the constant `A_{log_n}`.
+    let mut a_pos = batched_evaluation;
+    for i in (0..log_n).rev() {
+        let (r_i, u) = (r_powers[i], challenges.sumcheck[i]);
+        let a_neg = proof.gemini_a_evaluations[i];
+        let Some(den) = (r_i * (Fr::one() - u) + u).inverse() else {
+            return Ok(false);
+        };
+        a_pos = (r_i * a_pos.double() - a_neg * (r_i * (Fr::one() - u) - u)) * den;
+    }
+    let mut constant = a_pos * pos_inv + proof.gemini_a_evaluations[0] * nu * neg_inv;
+    let mut nu_power = nu.square();
+    for (i, fold) in proof.gemini_fold_comms.iter().enumerate() {
+        let Some(den) = (z + r_powers[i + 1]).inverse() else {
+            return Ok(false);
+        };
+        let scaling = nu_power * den;
+        bases.push(*fold);
+        scalars.push(-scaling);
+        constant += scaling * proof.gemini_a_evaluations[i + 1];
+        nu_power *= nu;
+    }
+
+    // `e(P, g2) = e(W, tau_g2)` with `P = Q - sum_j c_j C_j + (sum_j c_j v_j) g1 + z W`.
+    bases.extend([pvk.vk.g1, proof.kzg_quotient]);
+    scalars.extend([constant, z]);
+    let lhs = G1Projective::<H>::try_msm(&bases, &scalars)?;
+    Ok(kzg::check(pvk, lhs, proof.kzg_quotient.into())?)
+}
+
+/// Returns the length of an encoded proof for a circuit of `2^log_n` rows.
+pub fn proof_len(log_n: u32) -> usize {
+    let log_n = log_n as usize;
+    (8 + log_n - 1 + 2) * G1_LEN
+        + (log_n * BATCHED_RELATION_PARTIAL_LENGTH + NUMBER_OF_ENTITIES + log_n) * WORD_LEN
+}
+
+/// Reads a verifying key: the circuit size, its logarithm, the number of public
+/// inputs and their offset as 32 bytes big-endian integers, followed by the
+/// commitments, each encoded as `x || y`.
+pub fn vk_from_bytes<H: CurveHooks>(bytes: &[u8]) -> Result<VerifyingKey<H>, Error> {
+    let err = Error::MalformedVerifyingKey;
+    if bytes.len() != VK_LEN {
+        return Err(err);
+    }
+    let mut reader = Reader(bytes);
+    let circuit_size = reader.u64().ok_or(err)?;
+    let log_circuit_size = u32::try_from(reader.u64().ok_or(err)?).map_err(|_| err)?;
+    let num_public_inputs = usize::try_from(reader.u64().ok_or(err)?).map_err(|_| err)?;
+    let pub_inputs_offset = reader.u64().ok_or(err)?;
+    if log_circuit_size > MAX_LOG_CIRCUIT_SIZE || circuit_size != 1 << log_circuit_size {
+        return Err(err);
+    }
+    let mut commitments = [G1Affine::<H>::zero(); NUMBER_OF_PRECOMPUTED];
+    for commitment in commitments.iter_mut() {
+        *commitment = reader.g1().ok_or(err)?;
+    }
+    Ok(VerifyingKey {
+        log_circuit_size,
+        num_public_inputs,
+        pub_inputs_offset,
+        commitments,
+    })
+}
+
+/// Writes a verifying key in the layout of [`vk_from_bytes`].
+pub fn vk_to_bytes<H: CurveHooks>(vk: &VerifyingKey<H>) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(VK_LEN);
+    for value in [
+        vk.circuit_size(),
+        vk.log_circuit_size.into(),
+        vk.num_public_inputs as u64,
+        vk.pub_inputs_offset,
+    ] {
+        bytes.extend([0; WORD_LEN - 8]);
+        bytes.extend(value.to_be_bytes());
+    }
+    vk.commitments
+        .iter()
+        .for_each(|point| bytes.extend(write_g1(point)));
+    bytes
+}
+
+/// Reads a proof, in transcript order: `w_1..3`, the lookup read counts and tags,
+/// `w_4`, the lookup inverses, `z_perm`, the sumcheck univariates and evaluations,
+/// the Gemini folds and evaluations, the Shplonk quotient and the KZG quotient.
+///
+/// Points are encoded as `x || y` and scalars as 32 bytes big-endian integers, the
+/// circuit size being deduced from the length (see [`proof_len`]).
+pub fn proof_from_bytes<H: CurveHooks>(bytes: &[u8]) -> Result<Proof<H>, Error> {
+    let err = Error::MalformedProof;
+    let log_n = (1..=MAX_LOG_CIRCUIT_SIZE)
+        .find(|log_n| proof_len(*log_n) == bytes.len())
+        .ok_or(err)? as usize;
+    let mut reader = Reader(bytes);
+    let mut g1 = || reader.g1().ok_or(err);
+    let [w_1, w_2, w_3, lookup_read_counts, lookup_read_tags, w_4, lookup_inverses, z_perm] =
+        [g1()?, g1()?, g1()?, g1()?, g1()?, g1()?, g1()?, g1()?];
+    let mut sumcheck_univariates = vec![[Fr::zero(); BATCHED_RELATION_PARTIAL_LENGTH]; log_n];
+    for eval in sumcheck_univariates.iter_mut().flatten() {
+        *eval = reader.fr().ok_or(err)?;
+    }
+    let mut sumcheck_evaluations = [Fr::zero(); NUMBER_OF_ENTITIES];
+    for eval in sumcheck_evaluations.iter_mut() {
+        *eval = reader.fr().ok_or(err)?;
+    }
+    let gemini_fold_comms = (1..log_n)
+        .map(|_| reader.g1().ok_or(err))
+        .collect::<Result<_, _>>()?;
+    let gemini_a_evaluations = (0..log_n)
+        .map(|_| reader.fr().ok_or(err))
+        .collect::<Result<_, _>>()?;
+    Ok(Proof {
+        w: [w_1, w_2, w_3, w_4],
+        lookup_read_counts,
+        lookup_read_tags,
+        lookup_inverses,
+        z_perm,
+        sumcheck_univariates,
+        sumcheck_evaluations,
+        gemini_fold_comms,
+        gemini_a_evaluations,
+        shplonk_q: reader.g1().ok_or(err)?,
+        kzg_quotient: reader.g1().ok_or(err)?,
+    })
+}
+
+/// Writes a proof in the layout of [`proof_from_bytes`].
+pub fn proof_to_bytes<H: CurveHooks>(proof: &Proof<H>) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(proof_len(proof.sumcheck_univariates.len() as u32));
+    let [w_1, w_2, w_3, w_4] = &proof.w;
+    for point in [
+        w_1,
+        w_2,
+        w_3,
+        &proof.lookup_read_counts,
+        &proof.lookup_read_tags,
+        w_4,
+        &proof.lookup_inverses,
+        &proof.z_perm,
+    ] {
+        bytes.extend(write_g1(point));
+    }
+    proof
+        .sumcheck_univariates
+        .iter()
+        .flatten()
+        .chain(&proof.sumcheck_evaluations)
+        .for_each(|eval| bytes.extend(fr_to_bytes(eval)));
+    proof
+        .gemini_fold_comms
+        .iter()
+        .for_each(|point| bytes.extend(write_g1(point)));
+    proof
+        .gemini_a_evaluations
+        .iter()
+        .for_each(|eval| bytes.extend(fr_to_bytes(eval)));
+    bytes.extend(write_g1(&proof.shplonk_q));
+    bytes.extend(write_g1(&proof.kzg_quotient));
+    bytes
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Option<&[u8; N]> {
+        let (head, tail) = self.0.split_first_chunk()?;
+        self.0 = tail;
+        Some(head)
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        let word = self.take::<WORD_LEN>()?;
+        let (high, low) = word.split_at(WORD_LEN - 8);
+        match high.iter().all(|byte| *byte == 0) {
+            true => Some(u64::from_be_bytes(low.try_into().ok()?)),
+            false => None,
+        }
+    }
+
+    fn fr(&mut self) -> Option<Fr> {
+        fr_from_bytes(self.take()?).ok()
+    }
+
+    fn g1<H: CurveHooks>(&mut self) -> Option<G1Affine<H>> {
+        read_g1(self.take::<G1_LEN>()?).ok()
+    }
+}
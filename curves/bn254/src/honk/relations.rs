@@ -0,0 +1,299 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Relations of the Ultra arithmetization, as evaluated by the verifier at the
+//! sumcheck challenge.
+//!
+//! Each relation contributes one or more subrelations, which are scaled by the
+//! `alpha` challenges and summed up. Subrelations are multiplied by the partial
+//! evaluation of the `pow` polynomial, except the ones which only hold when
+//! summed over the whole hypercube.
+
+use ark_ff::{AdditiveGroup, Field, MontFp, One};
+
+use crate::Fr;
+
+/// Number of polynomials evaluated by the sumcheck.
+pub const NUMBER_OF_ENTITIES: usize = 40;
+/// Number of polynomials opened at the sumcheck challenge.
+pub(crate) const NUMBER_UNSHIFTED: usize = 35;
+/// Number of subrelations.
+pub(crate) const NUMBER_OF_SUBRELATIONS: usize = 26;
+/// Number of challenges scaling the subrelations, the first one being unscaled.
+pub(crate) const NUMBER_OF_ALPHAS: usize = NUMBER_OF_SUBRELATIONS - 1;
+
+// Entities, in proof order: the verifying key polynomials, the witness polynomials
+// and the shifts of the first four wires and of the permutation polynomial.
+pub(crate) const Q_M: usize = 0;
+pub(crate) const Q_C: usize = 1;
+pub(crate) const Q_L: usize = 2;
+pub(crate) const Q_R: usize = 3;
+pub(crate) const Q_O: usize = 4;
+pub(crate) const Q_4: usize = 5;
+pub(crate) const Q_LOOKUP: usize = 6;
+pub(crate) const Q_ARITH: usize = 7;
+pub(crate) const Q_RANGE: usize = 8;
+pub(crate) const Q_ELLIPTIC: usize = 9;
+pub(crate) const Q_AUX: usize = 10;
+pub(crate) const Q_POSEIDON2_EXTERNAL: usize = 11;
+pub(crate) const Q_POSEIDON2_INTERNAL: usize = 12;
+pub(crate) const SIGMA_1: usize = 13;
+pub(crate) const ID_1: usize = 17;
+pub(crate) const TABLE_1: usize = 21;
+pub(crate) const LAGRANGE_FIRST: usize = 25;
+pub(crate) const LAGRANGE_LAST: usize = 26;
+pub(crate) const W_L: usize = 27;
+pub(crate) const W_R: usize = 28;
+pub(crate) const W_O: usize = 29;
+pub(crate) const W_4: usize = 30;
+pub(crate) const Z_PERM: usize = 31;
+pub(crate) const LOOKUP_INVERSES: usize = 32;
+pub(crate) const LOOKUP_READ_COUNTS: usize = 33;
+pub(crate) const LOOKUP_READ_TAGS: usize = 34;
+pub(crate) const W_L_SHIFT: usize = 35;
+pub(crate) const W_R_SHIFT: usize = 36;
+pub(crate) const W_O_SHIFT: usize = 37;
+pub(crate) const W_4_SHIFT: usize = 38;
+pub(crate) const Z_PERM_SHIFT: usize = 39;
+
+/// `-b` for the Grumpkin curve `y^2 = x^3 - 17`, whose points are added by the
+/// elliptic relation.
+const GRUMPKIN_B_NEG: Fr = MontFp!("17");
+/// Limbs size of the non native field arithmetic.
+const LIMB_SIZE: Fr = MontFp!("295147905179352825856");
+/// Sublimbs size of the non native field arithmetic.
+const SUBLIMB_SHIFT: Fr = MontFp!("16384");
+/// Diagonal of the internal matrix of Poseidon2, minus one.
+pub(crate) const POSEIDON2_INTERNAL_DIAGONAL: [Fr; 4] = [
+    MontFp!("7626475329478847982857743246276194948757851985510858890691733676098590062311"),
+    MontFp!("5498568565063849786384470689962419967523752476452646391422913716315471115275"),
+    MontFp!("148936322117705719734052984176402258788283488576388928671173547788498414613"),
+    MontFp!("15456385653678559339152734484033356164266089951521103188900320352052358038155"),
+];
+
+/// Challenges the relations depend on.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Parameters {
+    pub(crate) eta: Fr,
+    pub(crate) eta_two: Fr,
+    pub(crate) eta_three: Fr,
+    pub(crate) beta: Fr,
+    pub(crate) gamma: Fr,
+    pub(crate) public_inputs_delta: Fr,
+}
+
+/// Evaluates the batched relations on the entities `e`, `pow` being the partial
+/// evaluation of the `pow` polynomial.
+pub(crate) fn accumulate(
+    e: &[Fr; NUMBER_OF_ENTITIES],
+    params: &Parameters,
+    alphas: &[Fr; NUMBER_OF_ALPHAS],
+    pow: Fr,
+) -> Fr {
+    let mut evals = [Fr::from(0u64); NUMBER_OF_SUBRELATIONS];
+    arithmetic(e, &mut evals, pow);
+    permutation(e, params, &mut evals, pow);
+    lookup(e, params, &mut evals, pow);
+    delta_range(e, &mut evals, pow);
+    elliptic(e, &mut evals, pow);
+    auxiliary(e, params, &mut evals, pow);
+    poseidon2_external(e, &mut evals, pow);
+    poseidon2_internal(e, &mut evals, pow);
+    evals[0]
+        + evals[1..]
+            .iter()
+            .zip(alphas)
+            .map(|(eval, alpha)| *eval * alpha)
+            .sum::<Fr>()
+}
+
+fn arithmetic(e: &[Fr; NUMBER_OF_ENTITIES], evals: &mut [Fr], pow: Fr) {
+    let q_arith = e[Q_ARITH];
+    let neg_half = -Fr::from(2u64).inverse().expect("two is not zero");
+    let mut acc = (q_arith - Fr::from(3u64)) * e[Q_M] * e[W_R] * e[W_L] * neg_half;
+    acc += e[Q_L] * e[W_L] + e[Q_R] * e[W_R] + e[Q_O] * e[W_O] + e[Q_4] * e[W_4] + e[Q_C];
+    acc += (q_arith - Fr::one()) * e[W_4_SHIFT];
+    evals[0] = acc * q_arith * pow;
+
+    let acc = e[W_L] + e[W_4] - e[W_L_SHIFT] + e[Q_M];
+    evals[1] = acc * (q_arith - Fr::from(2u64)) * (q_arith - Fr::one()) * q_arith * pow;
+}
+
+fn permutation(e: &[Fr; NUMBER_OF_ENTITIES], params: &Parameters, evals: &mut [Fr], pow: Fr) {
+    let (beta, gamma) = (params.beta, params.gamma);
+    let mut num = Fr::one();
+    let mut den = Fr::one();
+    for i in 0..4 {
+        let wire = e[W_L + i] + gamma;
+        num *= wire + e[ID_1 + i] * beta;
+        den *= wire + e[SIGMA_1 + i] * beta;
+    }
+    let acc = (e[Z_PERM] + e[LAGRANGE_FIRST]) * num
+        - (e[Z_PERM_SHIFT] + e[LAGRANGE_LAST] * params.public_inputs_delta) * den;
+    evals[2] = acc * pow;
+    evals[3] = e[LAGRANGE_LAST] * e[Z_PERM_SHIFT] * pow;
+}
+
+fn lookup(e: &[Fr; NUMBER_OF_ENTITIES], params: &Parameters, evals: &mut [Fr], pow: Fr) {
+    let (eta, eta_two, eta_three) = (params.eta, params.eta_two, params.eta_three);
+    let write_term = e[TABLE_1]
+        + params.gamma
+        + e[TABLE_1 + 1] * eta
+        + e[TABLE_1 + 2] * eta_two
+        + e[TABLE_1 + 3] * eta_three;
+    let derived_entry_1 = e[W_L] + params.gamma + e[Q_R] * e[W_L_SHIFT];
+    let derived_entry_2 = e[W_R] + e[Q_M] * e[W_R_SHIFT];
+    let derived_entry_3 = e[W_O] + e[Q_C] * e[W_O_SHIFT];
+    let read_term =
+        derived_entry_1 + derived_entry_2 * eta + derived_entry_3 * eta_two + e[Q_O] * eta_three;
+
+    let inverses = e[LOOKUP_INVERSES];
+    let read_inverse = inverses * write_term;
+    let write_inverse = inverses * read_term;
+    let tags = e[LOOKUP_READ_TAGS];
+    let inverse_exists = tags + e[Q_LOOKUP] - tags * e[Q_LOOKUP];
+    evals[4] = (read_term * write_term * inverses - inverse_exists) * pow;
+    // Only holds over the whole hypercube, thus not scaled.
+    evals[5] = e[Q_LOOKUP] * read_inverse - e[LOOKUP_READ_COUNTS] * write_inverse;
+}
+
+fn delta_range(e: &[Fr; NUMBER_OF_ENTITIES], evals: &mut [Fr], pow: Fr) {
+    let deltas = [
+        e[W_R] - e[W_L],
+        e[W_O] - e[W_R],
+        e[W_4] - e[W_O],
+        e[W_L_SHIFT] - e[W_4],
+    ];
+    let (one, two, three) = (Fr::one(), Fr::from(2u64), Fr::from(3u64));
+    for (eval, delta) in evals[6..10].iter_mut().zip(deltas) {
+        *eval = delta * (delta - one) * (delta - two) * (delta - three) * e[Q_RANGE] * pow;
+    }
+}
+
+fn elliptic(e: &[Fr; NUMBER_OF_ENTITIES], evals: &mut [Fr], pow: Fr) {
+    let (x_1, y_1) = (e[W_R], e[W_O]);
+    let (x_2, y_2) = (e[W_L_SHIFT], e[W_4_SHIFT]);
+    let (x_3, y_3) = (e[W_R_SHIFT], e[W_O_SHIFT]);
+    let q_sign = e[Q_L];
+    let q_is_double = e[Q_M];
+    let q_add = e[Q_ELLIPTIC] * (Fr::one() - q_is_double) * pow;
+    let q_double = e[Q_ELLIPTIC] * q_is_double * pow;
+
+    let x_diff = x_2 - x_1;
+    let y1_sqr = y_1.square();
+    let y1y2 = y_1 * y_2 * q_sign;
+    let x_add_identity = (x_3 + x_2 + x_1) * x_diff.square() - y_2.square() - y1_sqr + y1y2 + y1y2;
+    let y_add_identity = (y_1 + y_3) * x_diff + (x_3 - x_1) * (y_2 * q_sign - y_1);
+
+    // `x_1^4 = (y_1^2 + 17) * x_1` on the curve.
+    let x1_pow_4 = (y1_sqr + GRUMPKIN_B_NEG) * x_1;
+    let x_double_identity = (x_3 + x_1 + x_1) * y1_sqr * Fr::from(4u64) - x1_pow_4 * Fr::from(9u64);
+    let y_double_identity = x_1.square() * Fr::from(3u64) * (x_1 - x_3) - (y_1 + y_1) * (y_1 + y_3);
+
+    evals[10] = x_add_identity * q_add + x_double_identity * q_double;
+    evals[11] = y_add_identity * q_add + y_double_identity * q_double;
+}
+
+fn auxiliary(e: &[Fr; NUMBER_OF_ENTITIES], params: &Parameters, evals: &mut [Fr], pow: Fr) {
+    let (w_1, w_2, w_3, w_4) = (e[W_L], e[W_R], e[W_O], e[W_4]);
+    let (w_1_shift, w_2_shift, w_3_shift, w_4_shift) =
+        (e[W_L_SHIFT], e[W_R_SHIFT], e[W_O_SHIFT], e[W_4_SHIFT]);
+    let (q_1, q_2, q_3, q_4, q_m, q_c) = (e[Q_L], e[Q_R], e[Q_O], e[Q_4], e[Q_M], e[Q_C]);
+    let q_aux = e[Q_AUX] * pow;
+    let minus_one = -Fr::one();
+
+    // Non native field arithmetic.
+    let limb_subproduct = w_1 * w_2_shift + w_1_shift * w_2;
+    let gate_2 =
+        ((w_1 * w_4 + w_2 * w_3 - w_3_shift) * LIMB_SIZE - w_4_shift + limb_subproduct) * q_4;
+    let limb_subproduct = limb_subproduct * LIMB_SIZE + w_1_shift * w_2_shift;
+    let gate_1 = (limb_subproduct - (w_3 + w_4)) * q_3;
+    let gate_3 = (limb_subproduct + w_4 - (w_3_shift + w_4_shift)) * q_m;
+    let non_native_field_identity = (gate_1 + gate_2 + gate_3) * q_2;
+
+    let horner = |limbs: [Fr; 5]| {
+        limbs
+            .iter()
+            .fold(Fr::from(0u64), |acc, limb| acc * SUBLIMB_SHIFT + limb)
+    };
+    let limb_accumulator_1 = (horner([w_2_shift, w_1_shift, w_3, w_2, w_1]) - w_4) * q_4;
+    let limb_accumulator_2 =
+        (horner([w_3_shift, w_2_shift, w_1_shift, w_4, w_3]) - w_4_shift) * q_m;
+    let limb_accumulator_identity = (limb_accumulator_1 + limb_accumulator_2) * q_3;
+
+    // ROM and RAM consistency.
+    let partial_record_check =
+        w_3 * params.eta_three + w_2 * params.eta_two + w_1 * params.eta + q_c;
+    let memory_record_check = partial_record_check - w_4;
+    let index_delta = w_1_shift - w_1;
+    let record_delta = w_4_shift - w_4;
+    let index_is_monotonically_increasing = index_delta.square() - index_delta;
+    let indices_differ = index_delta * minus_one + Fr::one();
+    let adjacent_values_match_if_adjacent_indices_match = indices_differ * record_delta;
+    let q_rom = q_1 * q_2;
+    evals[13] = adjacent_values_match_if_adjacent_indices_match * q_rom * q_aux;
+    evals[14] = index_is_monotonically_increasing * q_rom * q_aux;
+    let rom_consistency_check_identity = memory_record_check * q_rom;
+
+    let access_type = w_4 - partial_record_check;
+    let access_check = access_type.square() - access_type;
+    let next_gate_access_type = w_4_shift
+        - (w_3_shift * params.eta_three + w_2_shift * params.eta_two + w_1_shift * params.eta);
+    let value_delta = w_3_shift - w_3;
+    let adjacent_values_match_if_next_is_read =
+        indices_differ * value_delta * (next_gate_access_type * minus_one + Fr::one());
+    let next_gate_access_type_is_boolean = next_gate_access_type.square() - next_gate_access_type;
+    let q_ram = e[Q_ARITH];
+    evals[15] = adjacent_values_match_if_next_is_read * q_ram * q_aux;
+    evals[16] = index_is_monotonically_increasing * q_ram * q_aux;
+    evals[17] = next_gate_access_type_is_boolean * q_ram * q_aux;
+    let ram_consistency_check_identity = access_check * q_ram;
+
+    let timestamp_delta = w_2_shift - w_2;
+    let ram_timestamp_check_identity = indices_differ * timestamp_delta - w_3;
+
+    let memory_identity = rom_consistency_check_identity
+        + ram_timestamp_check_identity * q_4 * q_1
+        + memory_record_check * q_m * q_1
+        + ram_consistency_check_identity;
+    evals[12] = (memory_identity + non_native_field_identity + limb_accumulator_identity) * q_aux;
+}
+
+fn poseidon2_external(e: &[Fr; NUMBER_OF_ENTITIES], evals: &mut [Fr], pow: Fr) {
+    let u: [Fr; 4] = core::array::from_fn(|i| (e[W_L + i] + e[Q_L + i]).pow([5]));
+    // Multiplication by the external matrix
+    // `[[5, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]]`.
+    let t0 = u[0] + u[1];
+    let t1 = u[2] + u[3];
+    let t2 = u[1].double() + t1;
+    let t3 = u[3].double() + t0;
+    let v4 = t1.double().double() + t3;
+    let v2 = t0.double().double() + t2;
+    let v1 = t3 + v2;
+    let v3 = t2 + v4;
+    let q_pos = e[Q_POSEIDON2_EXTERNAL] * pow;
+    for (i, v) in [v1, v2, v3, v4].into_iter().enumerate() {
+        evals[18 + i] = q_pos * (v - e[W_L_SHIFT + i]);
+    }
+}
+
+fn poseidon2_internal(e: &[Fr; NUMBER_OF_ENTITIES], evals: &mut [Fr], pow: Fr) {
+    let u = [(e[W_L] + e[Q_L]).pow([5]), e[W_R], e[W_O], e[W_4]];
+    let u_sum = u.iter().sum::<Fr>();
+    let q_pos = e[Q_POSEIDON2_INTERNAL] * pow;
+    for (i, (u, diagonal)) in u.iter().zip(POSEIDON2_INTERNAL_DIAGONAL).enumerate() {
+        evals[22 + i] = q_pos * (*u * diagonal + u_sum - e[W_L_SHIFT + i]);
+    }
+}
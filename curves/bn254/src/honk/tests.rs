@@ -0,0 +1,570 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::{
+    metering::{GasSchedule, MeteredHooks, ThreadBudget, OUT_OF_GAS},
+    native::NativeHooks,
+    plonk::tests::{Poly, Setup},
+    Fq,
+};
+use ark_ff::UniformRand;
+use ark_poly::{DenseUVPolynomial, Polynomial};
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+use relations::*;
+
+const LOG_N: u32 = 4;
+const N: usize = 1 << LOG_N;
+const OFFSET: usize = 1;
+
+type Columns = [[Fr; N]; NUMBER_UNSHIFTED];
+
+/// Divides `poly - poly(point)` by `X - point`.
+fn quotient(poly: &Poly, point: Fr) -> Poly {
+    let mut coeffs = vec![Fr::zero(); poly.coeffs.len().saturating_sub(1)];
+    let mut acc = Fr::zero();
+    for i in (1..poly.coeffs.len()).rev() {
+        acc = acc * point + poly.coeffs[i];
+        coeffs[i - 1] = acc;
+    }
+    Poly::from_coefficients_vec(coeffs)
+}
+
+/// Returns a point of the Grumpkin curve `y^2 = x^3 - 17` with `x >= start`.
+fn grumpkin_point(start: u64) -> (Fr, Fr) {
+    (start..)
+        .find_map(|x| {
+            let x = Fr::from(x);
+            (x.pow([3]) - Fr::from(17u64)).sqrt().map(|y| (x, y))
+        })
+        .unwrap()
+}
+
+/// Fills the columns of a circuit with public inputs `a` and `b` exercising the
+/// relations, except the auxiliary one.
+fn circuit(a: Fr, b: Fr) -> Columns {
+    let mut c = [[Fr::zero(); N]; NUMBER_UNSHIFTED];
+    let one = Fr::one();
+    let mut gate = |row: usize, wires: [Fr; 4], selectors: &[(usize, Fr)]| {
+        for (i, wire) in wires.into_iter().enumerate() {
+            c[W_L + i][row] = wire;
+        }
+        for (selector, value) in selectors {
+            c[*selector][row] = *value;
+        }
+    };
+    let zero = Fr::zero();
+
+    // Public inputs, copied from the second wire, and `a * b + a`.
+    for (row, input) in [(OFFSET, a), (OFFSET + 1, b)] {
+        gate(
+            row,
+            [input, input, zero, zero],
+            &[(Q_ARITH, one), (Q_L, one), (Q_R, -one)],
+        );
+    }
+    gate(
+        3,
+        [a, b, a * b, zero],
+        &[(Q_ARITH, one), (Q_M, one), (Q_O, -one)],
+    );
+    gate(
+        4,
+        [a * b, a, a * b + a, zero],
+        &[(Q_ARITH, one), (Q_L, one), (Q_R, one), (Q_O, -one)],
+    );
+
+    // Lookup of `(3, 9)` in the table of squares, stored at row 8.
+    gate(
+        5,
+        [Fr::from(3u64), Fr::from(9u64), zero, zero],
+        &[(Q_LOOKUP, one)],
+    );
+    gate(
+        8,
+        [zero; 4],
+        &[(TABLE_1, Fr::from(3u64)), (TABLE_1 + 1, Fr::from(9u64))],
+    );
+    gate(
+        8,
+        [zero; 4],
+        &[(LOOKUP_READ_COUNTS, one), (LOOKUP_READ_TAGS, one)],
+    );
+
+    // Delta range gate, continued on the next row.
+    gate(6, [0u64, 1, 2, 3].map(Fr::from), &[(Q_RANGE, one)]);
+    gate(7, [Fr::from(3u64), zero, zero, zero], &[]);
+
+    // Grumpkin addition and doubling.
+    let (x1, y1) = grumpkin_point(1);
+    let (x2, y2) = grumpkin_point(x1.into_bigint().0[0] + 1);
+    let lambda = (y2 - y1) / (x2 - x1);
+    let x3 = lambda.square() - x1 - x2;
+    let y3 = lambda * (x1 - x3) - y1;
+    gate(9, [zero, x1, y1, zero], &[(Q_ELLIPTIC, one), (Q_L, one)]);
+    gate(10, [x2, x3, y3, y2], &[]);
+    let lambda = x1.square() * Fr::from(3u64) / y1.double();
+    let x3 = lambda.square() - x1.double();
+    let y3 = lambda * (x1 - x3) - y1;
+    gate(11, [zero, x1, y1, zero], &[(Q_ELLIPTIC, one), (Q_M, one)]);
+    gate(12, [zero, x3, y3, zero], &[]);
+
+    // Poseidon2 first external round, followed by an internal round.
+    let constants: [Fr; 4] = [
+        MontFp!("11633431549750490989983886834189948010834808234699737327785600195936805266405"),
+        MontFp!("17353750182810071758476407404624088842693631054828301270920107619055744005334"),
+        MontFp!("11575173631114898451293296430061690731976535592475236587664058405912382527658"),
+        MontFp!("9724643380371653925020965751082872123058642683375812487991079305063678725624"),
+    ];
+    let state = [a, b, a * b, a + b];
+    let u = core::array::from_fn::<_, 4, _>(|i| (state[i] + constants[i]).pow([5]));
+    let matrix = [[5u64, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]];
+    let external = matrix.map(|row| {
+        row.iter()
+            .zip(&u)
+            .map(|(m, u)| Fr::from(*m) * u)
+            .sum::<Fr>()
+    });
+    gate(
+        13,
+        state,
+        &[
+            (Q_POSEIDON2_EXTERNAL, one),
+            (Q_L, constants[0]),
+            (Q_R, constants[1]),
+        ],
+    );
+    gate(13, state, &[(Q_O, constants[2]), (Q_4, constants[3])]);
+    let u = [
+        (external[0] + one).pow([5]),
+        external[1],
+        external[2],
+        external[3],
+    ];
+    let sum = u.iter().sum::<Fr>();
+    let internal: [Fr; 4] = core::array::from_fn(|i| u[i] * POSEIDON2_INTERNAL_DIAGONAL[i] + sum);
+    gate(14, external, &[(Q_POSEIDON2_INTERNAL, one), (Q_L, one)]);
+    gate(15, internal, &[]);
+
+    // Identity permutation, except for the copy cycles and the public inputs.
+    for i in 0..4 {
+        let ids: [Fr; N] = core::array::from_fn(|row| Fr::from((i * N + row) as u64));
+        (c[ID_1 + i], c[SIGMA_1 + i]) = (ids, ids);
+    }
+    let cycles = [
+        vec![(0, OFFSET), (0, 3), (1, 4)],
+        vec![(0, OFFSET + 1), (1, 3)],
+        vec![(2, 3), (0, 4)],
+    ];
+    for cycle in cycles {
+        for (cell, next) in cycle.iter().zip(cycle.iter().cycle().skip(1)) {
+            c[SIGMA_1 + cell.0][cell.1] = Fr::from((next.0 * N + next.1) as u64);
+        }
+    }
+    for (row, sigma) in c[SIGMA_1 + 1].iter_mut().enumerate().skip(OFFSET).take(2) {
+        *sigma = -Fr::from(row as u64 + 1);
+    }
+    c[LAGRANGE_FIRST][0] = one;
+    c[LAGRANGE_LAST][N - 1] = one;
+    c
+}
+
+/// Fills the lookup inverses and the permutation grand product.
+fn derived_columns(c: &mut Columns, params: &Parameters) {
+    for row in 0..N {
+        let shifted = |column: usize| c[column].get(row + 1).copied().unwrap_or_default();
+        let write_term = c[TABLE_1][row]
+            + params.gamma
+            + c[TABLE_1 + 1][row] * params.eta
+            + c[TABLE_1 + 2][row] * params.eta_two
+            + c[TABLE_1 + 3][row] * params.eta_three;
+        let read_term = c[W_L][row]
+            + params.gamma
+            + c[Q_R][row] * shifted(W_L)
+            + (c[W_R][row] + c[Q_M][row] * shifted(W_R)) * params.eta
+            + (c[W_O][row] + c[Q_C][row] * shifted(W_O)) * params.eta_two
+            + c[Q_O][row] * params.eta_three;
+        if !(c[Q_LOOKUP][row].is_zero() && c[LOOKUP_READ_TAGS][row].is_zero()) {
+            c[LOOKUP_INVERSES][row] = (read_term * write_term).inverse().unwrap();
+        }
+    }
+
+    let mut z = Fr::one();
+    for row in 0..N - 1 {
+        let (mut num, mut den) = (Fr::one(), Fr::one());
+        for i in 0..4 {
+            let wire = c[W_L + i][row] + params.gamma;
+            num *= wire + c[ID_1 + i][row] * params.beta;
+            den *= wire + c[SIGMA_1 + i][row] * params.beta;
+        }
+        z *= num / den;
+        c[Z_PERM][row + 1] = z;
+    }
+}
+
+/// Builds a key for the test circuit and proves it, the same way Barretenberg
+/// does.
+pub(crate) fn prove<H: CurveHooks>(
+    seed: u64,
+) -> (
+    kzg::PreparedVerifierKey<H>,
+    VerifyingKey<H>,
+    Proof<H>,
+    Vec<Fr>,
+) {
+    let rng = &mut StdRng::seed_from_u64(seed);
+    let setup = Setup::new(rng);
+    let (a, b) = (Fr::rand(rng), Fr::rand(rng));
+    let inputs = vec![a, b];
+    let mut c = circuit(a, b);
+    let poly = |column: &[Fr; N]| Poly::from_coefficients_slice(column);
+    let commit = |column: &[Fr; N]| setup.commit::<H>(&poly(column));
+
+    let pvk = kzg::prepare_verifier_key(kzg::VerifierKey {
+        g1: G1Affine::<H>::generator(),
+        g2: G2Affine::<H>::generator(),
+        tau_g2: setup.tau_g2(),
+    })
+    .unwrap();
+    let vk = VerifyingKey {
+        log_circuit_size: LOG_N,
+        num_public_inputs: inputs.len(),
+        pub_inputs_offset: OFFSET as u64,
+        commitments: core::array::from_fn(|i| commit(&c[i])),
+    };
+
+    let mut transcript = Transcript::new();
+    transcript.add_u64(N as u64);
+    transcript.add_u64(inputs.len() as u64);
+    transcript.add_u64(OFFSET as u64);
+    inputs.iter().for_each(|input| transcript.add_scalar(input));
+    let w: [G1Affine<H>; 4] = core::array::from_fn(|i| commit(&c[W_L + i]));
+    w[..3].iter().for_each(|point| transcript.add_point(point));
+    let (eta, eta_two) = transcript.challenge();
+    let (eta_three, _) = transcript.challenge();
+    let lookup_read_counts = commit(&c[LOOKUP_READ_COUNTS]);
+    let lookup_read_tags = commit(&c[LOOKUP_READ_TAGS]);
+    transcript.add_point(&lookup_read_counts);
+    transcript.add_point(&lookup_read_tags);
+    transcript.add_point(&w[3]);
+    let (beta, gamma) = transcript.challenge();
+    let params = Parameters {
+        eta,
+        eta_two,
+        eta_three,
+        beta,
+        gamma,
+        public_inputs_delta: public_inputs_delta(&inputs, beta, gamma, N as u64, OFFSET as u64)
+            .unwrap(),
+    };
+
+    derived_columns(&mut c, &params);
+    let lookup_inverses = commit(&c[LOOKUP_INVERSES]);
+    let z_perm = commit(&c[Z_PERM]);
+    transcript.add_point(&lookup_inverses);
+    transcript.add_point(&z_perm);
+    let mut alphas = [Fr::zero(); NUMBER_OF_ALPHAS];
+    for pair in alphas.chunks_mut(2) {
+        let (first, second) = transcript.challenge();
+        pair[0] = first;
+        if let Some(alpha) = pair.get_mut(1) {
+            *alpha = second;
+        }
+    }
+    let gate: Vec<Fr> = (0..LOG_N).map(|_| transcript.challenge().0).collect();
+
+    // Sumcheck over the entities, the shifted ones included.
+    let mut tables: Vec<Vec<Fr>> = c.iter().map(|column| column.to_vec()).collect();
+    for column in &c[W_L..=Z_PERM] {
+        let mut shifted = column[1..].to_vec();
+        shifted.push(Fr::zero());
+        tables.push(shifted);
+    }
+    let (mut target, mut pow) = (Fr::zero(), Fr::one());
+    let mut sumcheck_univariates = Vec::new();
+    let mut u = Vec::new();
+    for (round, beta) in gate.iter().enumerate() {
+        let half = tables[0].len() / 2;
+        let mut univariate = [Fr::zero(); BATCHED_RELATION_PARTIAL_LENGTH];
+        for j in 0..half {
+            let rest = gate[round + 1..]
+                .iter()
+                .enumerate()
+                .filter(|(bit, _)| j >> bit & 1 == 1)
+                .map(|(_, beta)| *beta)
+                .product::<Fr>();
+            for (x, eval) in univariate.iter_mut().enumerate() {
+                let x = Fr::from(x as u64);
+                let entities = core::array::from_fn(|k| {
+                    tables[k][2 * j] + x * (tables[k][2 * j + 1] - tables[k][2 * j])
+                });
+                let pow = pow * (Fr::one() + x * (*beta - Fr::one())) * rest;
+                *eval += relations::accumulate(&entities, &params, &alphas, pow);
+            }
+        }
+        assert_eq!(univariate[0] + univariate[1], target);
+        univariate
+            .iter()
+            .for_each(|eval| transcript.add_scalar(eval));
+        let (challenge, _) = transcript.challenge();
+        target = evaluate_univariate(&univariate, challenge);
+        for table in tables.iter_mut() {
+            *table = (0..half)
+                .map(|j| table[2 * j] + challenge * (table[2 * j + 1] - table[2 * j]))
+                .collect();
+        }
+        pow *= Fr::one() + challenge * (*beta - Fr::one());
+        sumcheck_univariates.push(univariate);
+        u.push(challenge);
+    }
+    let sumcheck_evaluations: [Fr; NUMBER_OF_ENTITIES] = core::array::from_fn(|k| tables[k][0]);
+    assert_eq!(
+        relations::accumulate(&sumcheck_evaluations, &params, &alphas, pow),
+        target
+    );
+    sumcheck_evaluations
+        .iter()
+        .for_each(|eval| transcript.add_scalar(eval));
+    let (rho, _) = transcript.challenge();
+
+    // Gemini folds of `A_0 = F + G / X`.
+    let (mut f, mut g) = (Poly::zero(), Poly::zero());
+    let mut rho_power = Fr::one();
+    for column in &c {
+        f += (rho_power, &poly(column));
+        rho_power *= rho;
+    }
+    for column in &c[W_L..=Z_PERM] {
+        g += (rho_power, &poly(column));
+        rho_power *= rho;
+    }
+    let mut folds = vec![&f + &Poly::from_coefficients_slice(&g.coeffs[1..])];
+    for u in &u[..LOG_N as usize - 1] {
+        let a = &folds[folds.len() - 1].coeffs;
+        let coeff = |i: usize| a.get(i).copied().unwrap_or_default();
+        folds.push(Poly::from_coefficients_vec(
+            (0..a.len().div_ceil(2))
+                .map(|j| coeff(2 * j) + *u * (coeff(2 * j + 1) - coeff(2 * j)))
+                .collect(),
+        ));
+    }
+    let gemini_fold_comms: Vec<G1Affine<H>> =
+        folds[1..].iter().map(|fold| setup.commit(fold)).collect();
+    gemini_fold_comms
+        .iter()
+        .for_each(|point| transcript.add_point(point));
+    let (r, _) = transcript.challenge();
+    let mut r_power = r;
+    let mut gemini_a_evaluations = Vec::new();
+    for fold in &folds {
+        gemini_a_evaluations.push(fold.evaluate(&-r_power));
+        r_power.square_in_place();
+    }
+    gemini_a_evaluations
+        .iter()
+        .for_each(|eval| transcript.add_scalar(eval));
+    let (nu, _) = transcript.challenge();
+
+    // Shplonk batching of the openings.
+    let g_over_r = &g * r.inverse().unwrap();
+    let mut openings = vec![(&f + &g_over_r, r), (&f - &g_over_r, -r)];
+    let mut r_power = r;
+    for fold in &folds[1..] {
+        r_power.square_in_place();
+        openings.push((fold.clone(), -r_power));
+    }
+    let mut q = Poly::zero();
+    let mut nu_power = Fr::one();
+    for (poly, point) in &openings {
+        q += (nu_power, &quotient(poly, *point));
+        nu_power *= nu;
+    }
+    let shplonk_q = setup.commit(&q);
+    transcript.add_point(&shplonk_q);
+    let (z, _) = transcript.challenge();
+    let mut l = q;
+    let mut nu_power = Fr::one();
+    for (poly, point) in &openings {
+        let value = Poly::from_coefficients_slice(&[poly.evaluate(point)]);
+        l += (-nu_power / (z - point), &(poly - &value));
+        nu_power *= nu;
+    }
+    assert!(l.evaluate(&z).is_zero());
+
+    let proof = Proof {
+        w,
+        lookup_read_counts,
+        lookup_read_tags,
+        lookup_inverses,
+        z_perm,
+        sumcheck_univariates,
+        sumcheck_evaluations,
+        gemini_fold_comms,
+        gemini_a_evaluations,
+        shplonk_q,
+        kzg_quotient: setup.commit(&quotient(&l, z)),
+    };
+    (pvk, vk, proof, inputs)
+}
+
+fn invalid_point<H: CurveHooks>() -> G1Affine<H> {
+    G1Affine::<H>::new_unchecked(Fq::one(), Fq::one())
+}
+
+#[test]
+fn verify_works() {
+    let (pvk, vk, proof, inputs) = prove::<NativeHooks>(0);
+    assert_eq!(verify_with_kzg_key(&pvk, &vk, &proof, &inputs), Ok(true));
+}
+
+#[test]
+fn verify_rejects_wrong_proofs() {
+    let (pvk, vk, proof, inputs) = prove::<NativeHooks>(1);
+    let verify =
+        |proof: &Proof<NativeHooks>, inputs: &[Fr]| verify_with_kzg_key(&pvk, &vk, proof, inputs);
+    assert_eq!(verify(&proof, &[inputs[1], inputs[0]]), Ok(false));
+
+    let mut wrong = proof.clone();
+    wrong.sumcheck_univariates[1][3] += Fr::one();
+    assert_eq!(verify(&wrong, &inputs), Ok(false));
+    let mut wrong = proof.clone();
+    wrong.sumcheck_evaluations[W_O] += Fr::one();
+    assert_eq!(verify(&wrong, &inputs), Ok(false));
+    let mut wrong = proof.clone();
+    wrong.sumcheck_evaluations[Z_PERM_SHIFT] += Fr::one();
+    assert_eq!(verify(&wrong, &inputs), Ok(false));
+    let mut wrong = proof.clone();
+    wrong.gemini_a_evaluations[2] += Fr::one();
+    assert_eq!(verify(&wrong, &inputs), Ok(false));
+    let mut wrong = proof.clone();
+    wrong.gemini_fold_comms.swap(0, 1);
+    assert_eq!(verify(&wrong, &inputs), Ok(false));
+    let mut wrong = proof.clone();
+    (wrong.shplonk_q, wrong.kzg_quotient) = (proof.kzg_quotient, proof.shplonk_q);
+    assert_eq!(verify(&wrong, &inputs), Ok(false));
+    let mut wrong = proof;
+    wrong.w.swap(0, 1);
+    assert_eq!(verify(&wrong, &inputs), Ok(false));
+}
+
+#[test]
+fn verify_rejects_malformed_inputs() {
+    let (pvk, vk, proof, inputs) = prove::<NativeHooks>(2);
+    assert_eq!(
+        verify_with_kzg_key(&pvk, &vk, &proof, &inputs[1..]),
+        Err(Error::InvalidPublicInputsLength {
+            expected: 2,
+            found: 1
+        })
+    );
+
+    let mut invalid_commitment = vk.clone();
+    invalid_commitment.commitments[5] = invalid_point();
+    let wrong_keys = [
+        VerifyingKey {
+            log_circuit_size: 0,
+            ..vk.clone()
+        },
+        VerifyingKey {
+            pub_inputs_offset: N as u64 - 1,
+            ..vk.clone()
+        },
+        invalid_commitment,
+    ];
+    for wrong_key in wrong_keys {
+        assert_eq!(
+            verify_with_kzg_key(&pvk, &wrong_key, &proof, &inputs),
+            Err(Error::MalformedVerifyingKey)
+        );
+    }
+
+    let mut short = proof.clone();
+    short.sumcheck_univariates.pop();
+    let mut invalid = proof;
+    invalid.z_perm = invalid_point();
+    for wrong_proof in [short, invalid] {
+        assert_eq!(
+            verify_with_kzg_key(&pvk, &vk, &wrong_proof, &inputs),
+            Err(Error::MalformedProof)
+        );
+    }
+}
+
+#[test]
+fn artifacts_round_trip() {
+    let (pvk, vk, proof, inputs) = prove::<NativeHooks>(3);
+    let vk_bytes = vk_to_bytes(&vk);
+    assert_eq!(vk_bytes.len(), VK_LEN);
+    let proof_bytes = proof_to_bytes(&proof);
+    assert_eq!(proof_bytes.len(), proof_len(LOG_N));
+    let vk = vk_from_bytes::<NativeHooks>(&vk_bytes).unwrap();
+    let proof = proof_from_bytes::<NativeHooks>(&proof_bytes).unwrap();
+    assert_eq!(vk_to_bytes(&vk), vk_bytes);
+    assert_eq!(proof_to_bytes(&proof), proof_bytes);
+    assert_eq!(verify_with_kzg_key(&pvk, &vk, &proof, &inputs), Ok(true));
+
+    // Circuit size not matching its logarithm.
+    let mut wrong = vk_bytes.clone();
+    wrong[WORD_LEN - 1] += 1;
+    assert_eq!(
+        vk_from_bytes::<NativeHooks>(&wrong),
+        Err(Error::MalformedVerifyingKey)
+    );
+    assert_eq!(
+        vk_from_bytes::<NativeHooks>(&vk_bytes[1..]),
+        Err(Error::MalformedVerifyingKey)
+    );
+    assert_eq!(
+        proof_from_bytes::<NativeHooks>(&proof_bytes[WORD_LEN..]),
+        Err(Error::MalformedProof)
+    );
+    // Non canonical scalar in the sumcheck univariates.
+    let mut wrong = proof_bytes;
+    wrong[8 * G1_LEN..8 * G1_LEN + WORD_LEN].fill(0xff);
+    assert_eq!(
+        proof_from_bytes::<NativeHooks>(&wrong),
+        Err(Error::MalformedProof)
+    );
+}
+
+#[test]
+fn ignition_key_is_valid() {
+    let vk = kzg_verifier_key::<NativeHooks>();
+    assert!(kzg::prepare_verifier_key(vk).is_ok());
+    assert!(vk.tau_g2.is_on_curve());
+    assert!(vk.tau_g2.is_in_correct_subgroup_assuming_on_curve());
+}
+
+#[test]
+fn verify_uses_the_hooks() {
+    type Hooks = MeteredHooks<NativeHooks, ThreadBudget>;
+    let schedule = GasSchedule::EIP_1108;
+
+    // Proving goes through the metered hooks as well.
+    ThreadBudget::reset(u64::MAX);
+    let (pvk, vk, proof, inputs) = prove::<Hooks>(4);
+    let bases = 1 + NUMBER_OF_PRECOMPUTED + 8 + LOG_N as usize - 1 + 2;
+    ThreadBudget::reset(u64::MAX);
+    assert_eq!(verify_with_kzg_key(&pvk, &vk, &proof, &inputs), Ok(true));
+    assert_eq!(
+        ThreadBudget::used(),
+        schedule.msm_g1_cost(bases) + schedule.ec_pairing_cost(2)
+    );
+    ThreadBudget::reset(schedule.msm_g1_cost(bases));
+    assert_eq!(
+        verify_with_kzg_key(&pvk, &vk, &proof, &inputs),
+        Err(Error::Hook(OUT_OF_GAS))
+    );
+}
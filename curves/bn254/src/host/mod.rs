@@ -0,0 +1,297 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Byte-level API for the hooks.
+//!
+//! The functions of this module are meant to be called by the host: the inputs are
+//! decoded as *Arkworks* upstream types with the given [`Codec`], the upstream
+//! implementation is executed and the result is returned encoded.
+//!
+//! [`HostHooks`] is the matching guest side adapter: it encodes the hooks inputs
+//! and forwards them to a user-supplied set of [`HostFunctions`], using the codec
+//! the host functions declare.
+
+use ark_bn254::{
+    g1::Config as ArkG1Config, g2::Config as ArkG2Config, Bn254 as ArkBn254, Fq12, Fr,
+    G1Affine as ArkG1Affine, G1Projective as ArkG1Projective, G2Affine as ArkG2Affine,
+    G2Projective as ArkG2Projective,
+};
+use ark_ec::{
+    pairing::{MillerLoopOutput, Pairing},
+    short_weierstrass::SWCurveConfig,
+    VariableBaseMSM,
+};
+use ark_ff::One;
+use ark_models_ext::CurveConfig;
+use ark_scale::{
+    ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate},
+    is_compressed, is_validated, make_usage, Usage,
+};
+use ark_std::{marker::PhantomData, vec::Vec};
+
+use crate::{
+    g1, g2, native::SCALE_USAGE, Bn254, CurveHooks, G1Projective, G2Projective, HookError,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// SCALE codec settings for the *Arkworks* types crossing the hook boundary.
+///
+/// The encoding is the same of [`ArkScale`](ark_scale::ArkScale) with the
+/// equivalent [`Usage`].
+///
+/// Validation is safe to use within the hooks, as subgroup checks never jump into
+/// the hooks themselves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Codec(Usage);
+
+impl Codec {
+    /// Default codec, compressed unless the `scale-no-compress` feature is enabled
+    /// and never validated.
+    pub const DEFAULT: Codec = Codec(SCALE_USAGE);
+
+    /// Codec with the given compression and validation settings.
+    pub const fn new(compress: Compress, validate: Validate) -> Self {
+        Codec(make_usage(compress, validate))
+    }
+
+    /// Equivalent [`ArkScale`](ark_scale::ArkScale) usage.
+    pub const fn usage(self) -> Usage {
+        self.0
+    }
+
+    /// Compression setting.
+    pub const fn compress(self) -> Compress {
+        is_compressed(self.0)
+    }
+
+    /// Validation setting.
+    pub const fn validate(self) -> Validate {
+        is_validated(self.0)
+    }
+
+    /// Encodes the given value.
+    pub fn encode<T: CanonicalSerialize>(self, value: T) -> Result<Vec<u8>, HookError> {
+        let mut buf = Vec::with_capacity(value.serialized_size(self.compress()));
+        value
+            .serialize_with_mode(&mut buf, self.compress())
+            .map_err(|_| HookError::Encoding)?;
+        Ok(buf)
+    }
+
+    /// Decodes a value from the given bytes, which must be entirely consumed.
+    pub fn decode<T: CanonicalDeserialize>(self, mut bytes: &[u8]) -> Result<T, HookError> {
+        let value = T::deserialize_with_mode(&mut bytes, self.compress(), self.validate())
+            .map_err(|_| HookError::Encoding)?;
+        if !bytes.is_empty() {
+            return Err(HookError::Encoding);
+        }
+        Ok(value)
+    }
+}
+
+impl Default for Codec {
+    fn default() -> Self {
+        Codec::DEFAULT
+    }
+}
+
+/// Multi Miller loop.
+///
+/// Takes encoded `Vec<G1Affine>` and `Vec<G2Affine>` and returns the encoded `Fq12`.
+pub fn multi_miller_loop(codec: Codec, g1: &[u8], g2: &[u8]) -> Result<Vec<u8>, HookError> {
+    let g1 = codec.decode::<Vec<ArkG1Affine>>(g1)?;
+    let g2 = codec.decode::<Vec<ArkG2Affine>>(g2)?;
+
+    let res = ArkBn254::multi_miller_loop(g1, g2).0;
+    codec.encode(res)
+}
+
+/// Final exponentiation.
+///
+/// Takes an encoded `Fq12` and returns the encoded `Fq12`.
+pub fn final_exponentiation(codec: Codec, target: &[u8]) -> Result<Vec<u8>, HookError> {
+    let target = codec.decode::<Fq12>(target)?;
+
+    let res =
+        ArkBn254::final_exponentiation(MillerLoopOutput(target)).ok_or(HookError::InvalidInput)?;
+    codec.encode(res.0)
+}
+
+/// Pairing product check.
+///
+/// Takes encoded `Vec<G1Affine>` and `Vec<G2Affine>` and returns `true` if the
+/// product of the pairings is one.
+pub fn pairing_check(codec: Codec, g1: &[u8], g2: &[u8]) -> Result<bool, HookError> {
+    let g1 = codec.decode::<Vec<ArkG1Affine>>(g1)?;
+    let g2 = codec.decode::<Vec<ArkG2Affine>>(g2)?;
+
+    let res = ArkBn254::multi_pairing(g1, g2);
+    Ok(res.0.is_one())
+}
+
+/// Multi scalar multiplication on G1.
+///
+/// Takes encoded `Vec<G1Affine>` and `Vec<Fr>` and returns the encoded `G1Projective`.
+pub fn msm_g1(codec: Codec, bases: &[u8], scalars: &[u8]) -> Result<Vec<u8>, HookError> {
+    let bases = codec.decode::<Vec<ArkG1Affine>>(bases)?;
+    let scalars = codec.decode::<Vec<Fr>>(scalars)?;
+
+    let res = ArkG1Projective::msm(&bases, &scalars).map_err(|_| HookError::LengthMismatch)?;
+    codec.encode(res)
+}
+
+/// Multi scalar multiplication on G2.
+///
+/// Takes encoded `Vec<G2Affine>` and `Vec<Fr>` and returns the encoded `G2Projective`.
+pub fn msm_g2(codec: Codec, bases: &[u8], scalars: &[u8]) -> Result<Vec<u8>, HookError> {
+    let bases = codec.decode::<Vec<ArkG2Affine>>(bases)?;
+    let scalars = codec.decode::<Vec<Fr>>(scalars)?;
+
+    let res = ArkG2Projective::msm(&bases, &scalars).map_err(|_| HookError::LengthMismatch)?;
+    codec.encode(res)
+}
+
+/// Projective multiplication on G1.
+///
+/// Takes encoded `G1Projective` and `Vec<u64>` and returns the encoded `G1Projective`.
+pub fn mul_projective_g1(codec: Codec, base: &[u8], scalar: &[u8]) -> Result<Vec<u8>, HookError> {
+    let base = codec.decode::<ArkG1Projective>(base)?;
+    let scalar = codec.decode::<Vec<u64>>(scalar)?;
+
+    let res = <ArkG1Config as SWCurveConfig>::mul_projective(&base, &scalar);
+    codec.encode(res)
+}
+
+/// Projective multiplication on G2.
+///
+/// Takes encoded `G2Projective` and `Vec<u64>` and returns the encoded `G2Projective`.
+pub fn mul_projective_g2(codec: Codec, base: &[u8], scalar: &[u8]) -> Result<Vec<u8>, HookError> {
+    let base = codec.decode::<ArkG2Projective>(base)?;
+    let scalar = codec.decode::<Vec<u64>>(scalar)?;
+
+    let res = <ArkG2Config as SWCurveConfig>::mul_projective(&base, &scalar);
+    codec.encode(res)
+}
+
+/// Byte-level functions provided by the host.
+///
+/// Inputs and outputs are encoded with [`Self::CODEC`] as expected by the homonymous
+/// functions of this module, which are the natural implementation on the host side.
+pub trait HostFunctions: 'static {
+    /// Codec used to encode the functions inputs and outputs.
+    const CODEC: Codec = Codec::DEFAULT;
+
+    /// Multi Miller loop.
+    fn bn254_multi_miller_loop(g1: &[u8], g2: &[u8]) -> Result<Vec<u8>, HookError>;
+
+    /// Final exponentiation.
+    fn bn254_final_exponentiation(target: &[u8]) -> Result<Vec<u8>, HookError>;
+
+    /// Pairing product check.
+    ///
+    /// Defaults to a multi Miller loop followed by a final exponentiation.
+    fn bn254_pairing_check(g1: &[u8], g2: &[u8]) -> Result<bool, HookError> {
+        let target = Self::bn254_multi_miller_loop(g1, g2)?;
+        let res = Self::bn254_final_exponentiation(&target)?;
+        Self::CODEC.decode::<Fq12>(&res).map(|res| res.is_one())
+    }
+
+    /// Multi scalar multiplication on G1.
+    fn bn254_msm_g1(bases: &[u8], scalars: &[u8]) -> Result<Vec<u8>, HookError>;
+
+    /// Multi scalar multiplication on G2.
+    fn bn254_msm_g2(bases: &[u8], scalars: &[u8]) -> Result<Vec<u8>, HookError>;
+
+    /// Projective multiplication on G1.
+    fn bn254_mul_projective_g1(base: &[u8], scalar: &[u8]) -> Result<Vec<u8>, HookError>;
+
+    /// Projective multiplication on G2.
+    fn bn254_mul_projective_g2(base: &[u8], scalar: &[u8]) -> Result<Vec<u8>, HookError>;
+}
+
+/// Hooks forwarding every operation to the host functions `F`.
+pub struct HostHooks<F: HostFunctions>(PhantomData<fn() -> F>);
+
+impl<F: HostFunctions> CurveHooks for HostHooks<F> {
+    fn bn254_multi_miller_loop(
+        g1: impl Iterator<Item = <Bn254<Self> as Pairing>::G1Prepared>,
+        g2: impl Iterator<Item = <Bn254<Self> as Pairing>::G2Prepared>,
+    ) -> Result<<Bn254<Self> as Pairing>::TargetField, HookError> {
+        let g1 = F::CODEC.encode(g1.map(|item| item.0).collect::<Vec<_>>())?;
+        let g2 = F::CODEC.encode(g2.map(|item| item.0).collect::<Vec<_>>())?;
+
+        F::CODEC.decode(&F::bn254_multi_miller_loop(&g1, &g2)?)
+    }
+
+    fn bn254_final_exponentiation(
+        target: <Bn254<Self> as Pairing>::TargetField,
+    ) -> Result<<Bn254<Self> as Pairing>::TargetField, HookError> {
+        let target = F::CODEC.encode(target)?;
+
+        F::CODEC.decode(&F::bn254_final_exponentiation(&target)?)
+    }
+
+    fn bn254_pairing_check(
+        g1: impl Iterator<Item = <Bn254<Self> as Pairing>::G1Prepared>,
+        g2: impl Iterator<Item = <Bn254<Self> as Pairing>::G2Prepared>,
+    ) -> Result<bool, HookError> {
+        let g1 = F::CODEC.encode(g1.map(|item| item.0).collect::<Vec<_>>())?;
+        let g2 = F::CODEC.encode(g2.map(|item| item.0).collect::<Vec<_>>())?;
+
+        F::bn254_pairing_check(&g1, &g2)
+    }
+
+    fn bn254_msm_g1(
+        bases: &[g1::G1Affine<Self>],
+        scalars: &[<g1::Config<Self> as CurveConfig>::ScalarField],
+    ) -> Result<G1Projective<Self>, HookError> {
+        let bases = F::CODEC.encode(bases)?;
+        let scalars = F::CODEC.encode(scalars)?;
+
+        F::CODEC.decode(&F::bn254_msm_g1(&bases, &scalars)?)
+    }
+
+    fn bn254_msm_g2(
+        bases: &[g2::G2Affine<Self>],
+        scalars: &[<g2::Config<Self> as CurveConfig>::ScalarField],
+    ) -> Result<G2Projective<Self>, HookError> {
+        let bases = F::CODEC.encode(bases)?;
+        let scalars = F::CODEC.encode(scalars)?;
+
+        F::CODEC.decode(&F::bn254_msm_g2(&bases, &scalars)?)
+    }
+
+    fn bn254_mul_projective_g1(
+        base: &G1Projective<Self>,
+        scalar: &[u64],
+    ) -> Result<G1Projective<Self>, HookError> {
+        let base = F::CODEC.encode(*base)?;
+        let scalar = F::CODEC.encode(scalar)?;
+
+        F::CODEC.decode(&F::bn254_mul_projective_g1(&base, &scalar)?)
+    }
+
+    fn bn254_mul_projective_g2(
+        base: &G2Projective<Self>,
+        scalar: &[u64],
+    ) -> Result<G2Projective<Self>, HookError> {
+        let base = F::CODEC.encode(*base)?;
+        let scalar = F::CODEC.encode(scalar)?;
+
+        F::CODEC.decode(&F::bn254_mul_projective_g2(&base, &scalar)?)
+    }
+}
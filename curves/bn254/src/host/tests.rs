@@ -0,0 +1,188 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::{
+    native::{Bn254Native, G1AffineNative, G1Native, G2AffineNative, G2Native},
+    FromArk, G2Affine, ToArk, TryCurveOps, TryPairing,
+};
+use ark_ff::UniformRand;
+use ark_models_ext::{AffineRepr, CurveGroup};
+use ark_std::test_rng;
+
+struct TestHost<const COMPRESS: bool, const VALIDATE: bool>;
+
+impl<const COMPRESS: bool, const VALIDATE: bool> HostFunctions for TestHost<COMPRESS, VALIDATE> {
+    const CODEC: Codec = Codec::new(
+        if COMPRESS {
+            Compress::Yes
+        } else {
+            Compress::No
+        },
+        if VALIDATE {
+            Validate::Yes
+        } else {
+            Validate::No
+        },
+    );
+
+    fn bn254_multi_miller_loop(g1: &[u8], g2: &[u8]) -> Result<Vec<u8>, HookError> {
+        multi_miller_loop(Self::CODEC, g1, g2)
+    }
+
+    fn bn254_final_exponentiation(target: &[u8]) -> Result<Vec<u8>, HookError> {
+        final_exponentiation(Self::CODEC, target)
+    }
+
+    fn bn254_msm_g1(bases: &[u8], scalars: &[u8]) -> Result<Vec<u8>, HookError> {
+        msm_g1(Self::CODEC, bases, scalars)
+    }
+
+    fn bn254_msm_g2(bases: &[u8], scalars: &[u8]) -> Result<Vec<u8>, HookError> {
+        msm_g2(Self::CODEC, bases, scalars)
+    }
+
+    fn bn254_mul_projective_g1(base: &[u8], scalar: &[u8]) -> Result<Vec<u8>, HookError> {
+        mul_projective_g1(Self::CODEC, base, scalar)
+    }
+
+    fn bn254_mul_projective_g2(base: &[u8], scalar: &[u8]) -> Result<Vec<u8>, HookError> {
+        mul_projective_g2(Self::CODEC, base, scalar)
+    }
+}
+
+fn host_hooks_match_native_hooks<Hooks: CurveHooks>() {
+    let mut rng = test_rng();
+    let p = G1Native::rand(&mut rng);
+    let q = G2Native::rand(&mut rng);
+    let s = Fr::rand(&mut rng);
+
+    let host_p = G1Projective::<Hooks>::from_ark(p.to_ark());
+    let host_q = G2Projective::<Hooks>::from_ark(q.to_ark());
+
+    let expected = Bn254Native::try_pairing(p, q).unwrap();
+    let res = Bn254::<Hooks>::try_pairing(host_p, host_q).unwrap();
+    assert_eq!(res.0, expected.0);
+    assert_eq!(
+        Bn254::<Hooks>::pairing_check([host_p, -host_p], [host_q, host_q]),
+        Ok(true)
+    );
+
+    let res = G1Projective::<Hooks>::try_msm(&[host_p.into_affine()], &[s]).unwrap();
+    assert_eq!(res.to_ark(), (p * s).to_ark());
+    let res = G2Projective::<Hooks>::try_msm(&[host_q.into_affine()], &[s]).unwrap();
+    assert_eq!(res.to_ark(), (q * s).to_ark());
+
+    assert_eq!(host_p.try_mul(&s).unwrap().to_ark(), (p * s).to_ark());
+    assert_eq!(host_q.try_mul(&s).unwrap().to_ark(), (q * s).to_ark());
+}
+
+#[test]
+fn host_hooks_work_with_compressed_codec() {
+    host_hooks_match_native_hooks::<HostHooks<TestHost<true, false>>>();
+}
+
+#[test]
+fn host_hooks_work_with_uncompressed_codec() {
+    host_hooks_match_native_hooks::<HostHooks<TestHost<false, false>>>();
+}
+
+#[test]
+fn host_hooks_work_with_validating_codec() {
+    type Hooks = HostHooks<TestHost<true, true>>;
+    host_hooks_match_native_hooks::<Hooks>();
+
+    // Decoding a G2 point of the hooked curve runs its subgroup check, which must
+    // not go through the hooks it is decoded for.
+    let q = G2Native::rand(&mut test_rng());
+    let codec = TestHost::<true, true>::CODEC;
+    let bytes = codec.encode(q.into_affine()).unwrap();
+    let decoded = codec.decode::<G2Affine<Hooks>>(&bytes).unwrap();
+    assert_eq!(decoded.to_ark(), q.into_affine().to_ark());
+}
+
+#[test]
+fn codecs_are_not_interchangeable() {
+    let compressed = Codec::new(Compress::Yes, Validate::Yes);
+    let uncompressed = Codec::new(Compress::No, Validate::Yes);
+    let points = vec![G1AffineNative::generator()];
+
+    let bytes = uncompressed.encode(points).unwrap();
+    assert_eq!(
+        uncompressed
+            .decode::<Vec<ArkG1Affine>>(&bytes)
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(
+        compressed.decode::<Vec<ArkG1Affine>>(&bytes),
+        Err(HookError::Encoding)
+    );
+}
+
+#[test]
+fn host_functions_reject_malformed_input() {
+    let codec = Codec::DEFAULT;
+    let mut g1 = codec.encode(vec![G1AffineNative::generator()]).unwrap();
+    let g2 = codec.encode(vec![G2AffineNative::generator()]).unwrap();
+    assert!(multi_miller_loop(codec, &g1, &g2).is_ok());
+
+    g1.push(0);
+    assert_eq!(multi_miller_loop(codec, &g1, &g2), Err(HookError::Encoding));
+    assert_eq!(msm_g1(codec, &[], &[]), Err(HookError::Encoding));
+
+    let bases = codec.encode(vec![G1AffineNative::generator()]).unwrap();
+    let scalars = codec.encode(Vec::<Fr>::new()).unwrap();
+    assert_eq!(
+        msm_g1(codec, &bases, &scalars),
+        Err(HookError::LengthMismatch)
+    );
+}
+
+struct ValidatingHooks;
+
+impl CurveHooks for ValidatingHooks {
+    fn bn254_mul_projective_g2(
+        base: &G2Projective<Self>,
+        scalar: &[u64],
+    ) -> Result<G2Projective<Self>, HookError> {
+        // Decoding with validation runs the subgroup check, which must not jump
+        // back into this hook.
+        let codec = Codec::new(Compress::Yes, Validate::Yes);
+        let base = codec.decode::<G2Projective<Self>>(&codec.encode(*base)?)?;
+        crate::native::mul_projective_g2(&base, scalar)
+    }
+}
+
+#[test]
+fn validating_decode_does_not_reenter_hooks() {
+    let mut rng = test_rng();
+    let q = G2Native::rand(&mut rng);
+    let s = Fr::rand(&mut rng);
+
+    let point = G2Projective::<ValidatingHooks>::from_ark(q.to_ark());
+    assert!(point
+        .into_affine()
+        .is_in_correct_subgroup_assuming_on_curve());
+    assert_eq!(point.try_mul(&s).unwrap().to_ark(), (q * s).to_ark());
+
+    let codec = Codec::new(Compress::No, Validate::Yes);
+    let bytes = codec.encode(point).unwrap();
+    assert_eq!(
+        codec.decode::<G2Projective<ValidatingHooks>>(&bytes),
+        Ok(point)
+    );
+}
@@ -0,0 +1,305 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [KZG](https://www.iacr.org/archive/asiacrypt2010/6477178/6477178.pdf) polynomial
+//! commitment opening verification.
+//!
+//! Openings are checked through the `msm_g1` hook and a single two pairs pairing
+//! check, no matter how many openings are verified at once:
+//! * [`verify`] checks a single opening;
+//! * [`verify_batch`] checks openings at different points, folded with the powers
+//!   of a challenge;
+//! * [`verify_shplonk`] checks the [SHPLONK](https://eprint.iacr.org/2020/081.pdf)
+//!   batch opening of many polynomials at many points.
+//!
+//! Challenges must be unpredictable by the prover, i.e. either sampled by the
+//! verifier or derived from a transcript of the proof.
+
+use ark_ff::{Field, One, Zero};
+use ark_models_ext::{AffineRepr, CurveGroup};
+use ark_std::vec::Vec;
+use educe::Educe;
+
+use crate::{
+    groth16::is_valid, Bn254, CurveHooks, Fr, G1Affine, G1Projective, G2Affine, HookError,
+    TryCurveOps, TryPairing,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Error returned by the verifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// Verifier key points are not valid.
+    MalformedVerifierKey,
+    /// Commitment or proof points are not valid.
+    MalformedOpening,
+    /// Opening points and values don't match or points are repeated.
+    InvalidQuery,
+    /// Failure reported by the hooks.
+    Hook(HookError),
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::MalformedVerifierKey => write!(f, "malformed verifier key"),
+            Error::MalformedOpening => write!(f, "malformed opening"),
+            Error::InvalidQuery => write!(f, "invalid query"),
+            Error::Hook(err) => write!(f, "hook failure: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl From<HookError> for Error {
+    fn from(err: HookError) -> Self {
+        Error::Hook(err)
+    }
+}
+
+/// KZG verifier key, taken from a powers of tau SRS.
+#[derive(Educe)]
+#[educe(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct VerifierKey<H: CurveHooks> {
+    /// The G1 generator.
+    pub g1: G1Affine<H>,
+    /// The G2 generator.
+    pub g2: G2Affine<H>,
+    /// `tau` in G2.
+    pub tau_g2: G2Affine<H>,
+}
+
+/// KZG verifier key prepared for verification.
+#[derive(Educe)]
+#[educe(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PreparedVerifierKey<H: CurveHooks> {
+    /// The original verifier key.
+    pub vk: VerifierKey<H>,
+    tau_g2_neg: G2Affine<H>,
+}
+
+impl<H: CurveHooks> TryFrom<VerifierKey<H>> for PreparedVerifierKey<H> {
+    type Error = Error;
+
+    fn try_from(vk: VerifierKey<H>) -> Result<Self, Self::Error> {
+        prepare_verifier_key(vk)
+    }
+}
+
+/// Opening of a committed polynomial `f` at a point, i.e. `f(point) = value`.
+#[derive(Educe)]
+#[educe(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Opening<H: CurveHooks> {
+    /// Commitment to `f`.
+    pub commitment: G1Affine<H>,
+    /// Evaluation point.
+    pub point: Fr,
+    /// Claimed `f(point)`.
+    pub value: Fr,
+    /// Commitment to `(f(X) - value) / (X - point)`.
+    pub proof: G1Affine<H>,
+}
+
+/// Claimed evaluations of a committed polynomial on a set of points.
+#[derive(Educe)]
+#[educe(Clone, Debug, PartialEq, Eq)]
+pub struct Query<H: CurveHooks> {
+    /// Commitment to the polynomial.
+    pub commitment: G1Affine<H>,
+    /// Evaluation points, all distinct.
+    pub points: Vec<Fr>,
+    /// Claimed evaluations, one per point.
+    pub values: Vec<Fr>,
+}
+
+/// SHPLONK batch opening proof.
+#[derive(Educe)]
+#[educe(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ShplonkProof<H: CurveHooks> {
+    /// Commitment to the quotient `W`.
+    pub w: G1Affine<H>,
+    /// Opening proof `W'` of the linearized polynomial at the evaluation challenge.
+    pub w_prime: G1Affine<H>,
+}
+
+/// Validates and prepares the verifier key.
+pub fn prepare_verifier_key<H: CurveHooks>(
+    vk: VerifierKey<H>,
+) -> Result<PreparedVerifierKey<H>, Error> {
+    let valid = !vk.g1.is_zero()
+        && !vk.g2.is_zero()
+        && is_valid(&vk.g1)
+        && is_valid(&vk.g2)
+        && is_valid(&vk.tau_g2);
+    if !valid {
+        return Err(Error::MalformedVerifierKey);
+    }
+    Ok(PreparedVerifierKey {
+        tau_g2_neg: -vk.tau_g2,
+        vk,
+    })
+}
+
+/// Checks `e(lhs, g2) = e(proof, tau_g2)`.
+pub(crate) fn check<H: CurveHooks>(
+    pvk: &PreparedVerifierKey<H>,
+    lhs: G1Projective<H>,
+    proof: G1Projective<H>,
+) -> Result<bool, Error> {
+    let g1 = [lhs.into_affine(), proof.into_affine()];
+    let g2 = [pvk.vk.g2, pvk.tau_g2_neg];
+    Ok(Bn254::<H>::pairing_check(g1, g2)?)
+}
+
+/// Verifies a single opening.
+///
+/// Checks `e(C - value * g1 + point * proof, g2) = e(proof, tau_g2)`.
+pub fn verify<H: CurveHooks>(
+    pvk: &PreparedVerifierKey<H>,
+    opening: &Opening<H>,
+) -> Result<bool, Error> {
+    if !is_valid(&opening.commitment) || !is_valid(&opening.proof) {
+        return Err(Error::MalformedOpening);
+    }
+    let lhs = G1Projective::<H>::try_msm(
+        &[opening.commitment, pvk.vk.g1, opening.proof],
+        &[Fr::one(), -opening.value, opening.point],
+    )?;
+    check(pvk, lhs, opening.proof.into())
+}
+
+/// Verifies many openings, possibly at different points.
+///
+/// The openings equations are folded with the powers of `challenge`, so that all
+/// of them are checked with two MSMs and a single pairing check.
+pub fn verify_batch<H: CurveHooks>(
+    pvk: &PreparedVerifierKey<H>,
+    openings: &[Opening<H>],
+    challenge: Fr,
+) -> Result<bool, Error> {
+    if openings.is_empty() {
+        return Ok(true);
+    }
+    let mut bases = Vec::with_capacity(2 * openings.len() + 1);
+    let mut scalars = Vec::with_capacity(2 * openings.len() + 1);
+    let mut proofs = Vec::with_capacity(openings.len());
+    let mut powers = Vec::with_capacity(openings.len());
+    let mut value = Fr::zero();
+    let mut power = Fr::one();
+    for opening in openings {
+        if !is_valid(&opening.commitment) || !is_valid(&opening.proof) {
+            return Err(Error::MalformedOpening);
+        }
+        bases.extend([opening.commitment, opening.proof]);
+        scalars.extend([power, power * opening.point]);
+        proofs.push(opening.proof);
+        powers.push(power);
+        value += power * opening.value;
+        power *= challenge;
+    }
+    bases.push(pvk.vk.g1);
+    scalars.push(-value);
+    let lhs = G1Projective::<H>::try_msm(&bases, &scalars)?;
+    let proof = G1Projective::<H>::try_msm(&proofs, &powers)?;
+    check(pvk, lhs, proof)
+}
+
+/// Verifies the SHPLONK batch opening of the queries.
+///
+/// With `T` the union of the queries points, `S_i` the points and `r_i` the
+/// polynomial interpolating the values of the `i`-th query, checks
+///
+/// `e(F + z * W', g2) = e(W', tau_g2)`
+///
+/// where `F = sum_i gamma^i * Z_{T \ S_i}(z) * (C_i - r_i(z) * g1) - Z_T(z) * W`,
+/// `gamma` being the challenge folding the queries and `z` the evaluation
+/// challenge.
+pub fn verify_shplonk<H: CurveHooks>(
+    pvk: &PreparedVerifierKey<H>,
+    queries: &[Query<H>],
+    proof: &ShplonkProof<H>,
+    gamma: Fr,
+    z: Fr,
+) -> Result<bool, Error> {
+    let mut all_points: Vec<Fr> = Vec::new();
+    for query in queries {
+        let valid = query.points.len() == query.values.len()
+            && query
+                .points
+                .iter()
+                .enumerate()
+                .all(|(i, point)| !query.points[..i].contains(point));
+        if !valid {
+            return Err(Error::InvalidQuery);
+        }
+        if !is_valid(&query.commitment) {
+            return Err(Error::MalformedOpening);
+        }
+        for point in &query.points {
+            if !all_points.contains(point) {
+                all_points.push(*point);
+            }
+        }
+    }
+    if !is_valid(&proof.w) || !is_valid(&proof.w_prime) {
+        return Err(Error::MalformedOpening);
+    }
+
+    let mut bases = Vec::with_capacity(queries.len() + 3);
+    let mut scalars = Vec::with_capacity(queries.len() + 3);
+    let mut value = Fr::zero();
+    let mut power = Fr::one();
+    for query in queries {
+        let others = all_points
+            .iter()
+            .filter(|point| !query.points.contains(point));
+        let factor = power * vanishing(others, z);
+        bases.push(query.commitment);
+        scalars.push(factor);
+        value += factor * interpolate(&query.points, &query.values, z);
+        power *= gamma;
+    }
+    bases.extend([pvk.vk.g1, proof.w, proof.w_prime]);
+    scalars.extend([-value, -vanishing(all_points.iter(), z), z]);
+    let lhs = G1Projective::<H>::try_msm(&bases, &scalars)?;
+    check(pvk, lhs, proof.w_prime.into())
+}
+
+/// Evaluates at `z` the polynomial vanishing on `points`.
+pub(crate) fn vanishing<'a>(points: impl Iterator<Item = &'a Fr>, z: Fr) -> Fr {
+    points.fold(Fr::one(), |acc, point| acc * (z - point))
+}
+
+/// Evaluates at `z` the polynomial interpolating `values` on the distinct `points`.
+pub(crate) fn interpolate(points: &[Fr], values: &[Fr], z: Fr) -> Fr {
+    let mut res = Fr::zero();
+    for (i, (xi, yi)) in points.iter().zip(values).enumerate() {
+        let mut num = Fr::one();
+        let mut den = Fr::one();
+        for (j, xj) in points.iter().enumerate() {
+            if i != j {
+                num *= z - xj;
+                den *= *xi - xj;
+            }
+        }
+        res += *yi * num * den.inverse().expect("points are distinct");
+    }
+    res
+}
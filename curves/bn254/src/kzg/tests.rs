@@ -0,0 +1,199 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::{
+    metering::{GasSchedule, MeteredHooks, ThreadBudget},
+    native::NativeHooks,
+};
+use ark_ff::UniformRand;
+use ark_models_ext::AffineRepr;
+use ark_std::{rand::Rng, test_rng, vec, vec::Vec};
+
+/// Trusted setup whose trapdoor is known, so that the tests can act as the prover.
+struct Setup<H: CurveHooks> {
+    tau: Fr,
+    pvk: PreparedVerifierKey<H>,
+}
+
+impl<H: CurveHooks> Setup<H> {
+    fn new(rng: &mut impl Rng) -> Self {
+        let tau = Fr::rand(rng);
+        let vk = VerifierKey {
+            g1: G1Affine::<H>::generator(),
+            g2: G2Affine::<H>::generator(),
+            tau_g2: (G2Affine::<H>::generator() * tau).into_affine(),
+        };
+        let pvk = prepare_verifier_key(vk).unwrap();
+        Setup { tau, pvk }
+    }
+
+    fn commit(&self, scalar: Fr) -> G1Affine<H> {
+        (self.pvk.vk.g1 * scalar).into_affine()
+    }
+
+    fn open(&self, poly: &[Fr], point: Fr) -> Opening<H> {
+        let value = eval(poly, point);
+        Opening {
+            commitment: self.commit(eval(poly, self.tau)),
+            point,
+            value,
+            proof: self.commit((eval(poly, self.tau) - value) / (self.tau - point)),
+        }
+    }
+}
+
+fn eval(poly: &[Fr], x: Fr) -> Fr {
+    poly.iter().rev().fold(Fr::zero(), |acc, c| acc * x + c)
+}
+
+fn random_poly(rng: &mut impl Rng, degree: usize) -> Vec<Fr> {
+    (0..=degree).map(|_| Fr::rand(rng)).collect()
+}
+
+#[test]
+fn verify_works() {
+    let rng = &mut test_rng();
+    let setup = Setup::<NativeHooks>::new(rng);
+    let poly = random_poly(rng, 8);
+    let opening = setup.open(&poly, Fr::rand(rng));
+    assert_eq!(verify(&setup.pvk, &opening), Ok(true));
+
+    let wrong_value = Opening {
+        value: opening.value + Fr::one(),
+        ..opening
+    };
+    assert_eq!(verify(&setup.pvk, &wrong_value), Ok(false));
+    let wrong_point = Opening {
+        point: opening.point + Fr::one(),
+        ..opening
+    };
+    assert_eq!(verify(&setup.pvk, &wrong_point), Ok(false));
+
+    let off_curve = G1Affine::new_unchecked(opening.proof.x, opening.proof.y + opening.proof.y);
+    let malformed = Opening {
+        proof: off_curve,
+        ..opening
+    };
+    assert_eq!(verify(&setup.pvk, &malformed), Err(Error::MalformedOpening));
+    let vk = VerifierKey {
+        g1: off_curve,
+        ..setup.pvk.vk
+    };
+    assert_eq!(prepare_verifier_key(vk), Err(Error::MalformedVerifierKey));
+}
+
+#[test]
+fn verify_batch_works() {
+    let rng = &mut test_rng();
+    let setup = Setup::<NativeHooks>::new(rng);
+    let mut openings: Vec<_> = (0..4)
+        .map(|degree| setup.open(&random_poly(rng, degree), Fr::rand(rng)))
+        .collect();
+    let challenge = Fr::rand(rng);
+    assert_eq!(verify_batch(&setup.pvk, &openings, challenge), Ok(true));
+    assert_eq!(verify_batch(&setup.pvk, &[], challenge), Ok(true));
+
+    openings[2].value += Fr::one();
+    assert_eq!(verify_batch(&setup.pvk, &openings, challenge), Ok(false));
+}
+
+#[test]
+fn verify_batch_uses_one_pairing_check() {
+    type Hooks = MeteredHooks<NativeHooks, ThreadBudget>;
+
+    // Proving goes through the metered hooks as well.
+    ThreadBudget::reset(u64::MAX);
+    let rng = &mut test_rng();
+    let setup = Setup::<Hooks>::new(rng);
+    let openings: Vec<_> = (0..3)
+        .map(|degree| setup.open(&random_poly(rng, degree), Fr::rand(rng)))
+        .collect();
+
+    ThreadBudget::reset(u64::MAX);
+    assert_eq!(verify_batch(&setup.pvk, &openings, Fr::rand(rng)), Ok(true));
+    let schedule = GasSchedule::EIP_1108;
+    assert_eq!(
+        ThreadBudget::used(),
+        schedule.msm_g1_cost(7) + schedule.msm_g1_cost(3) + schedule.ec_pairing_cost(2)
+    );
+}
+
+#[test]
+fn verify_shplonk_works() {
+    let rng = &mut test_rng();
+    let setup = Setup::<NativeHooks>::new(rng);
+    let [x0, x1, x2, x3] = [(); 4].map(|_| Fr::rand(rng));
+    let polys: Vec<_> = (0..3).map(|_| random_poly(rng, 6)).collect();
+    let points = [vec![x0], vec![x0, x1], vec![x2, x3, x1]];
+    let queries: Vec<Query<NativeHooks>> = polys
+        .iter()
+        .zip(&points)
+        .map(|(poly, points)| Query {
+            commitment: setup.commit(eval(poly, setup.tau)),
+            points: points.clone(),
+            values: points.iter().map(|x| eval(poly, *x)).collect(),
+        })
+        .collect();
+    let (gamma, z) = (Fr::rand(rng), Fr::rand(rng));
+
+    // W = [sum_i gamma^i * (f_i - r_i) / Z_{S_i}] and W' = [L / (X - z)].
+    let all_points = [x0, x1, x2, x3];
+    let tau = setup.tau;
+    let mut w = Fr::zero();
+    let mut l = Fr::zero();
+    let mut power = Fr::one();
+    for (poly, query) in polys.iter().zip(&queries) {
+        let r_tau = interpolate(&query.points, &query.values, tau);
+        let r_z = interpolate(&query.points, &query.values, z);
+        w += power * (eval(poly, tau) - r_tau) / vanishing(query.points.iter(), tau);
+        let others = all_points.iter().filter(|x| !query.points.contains(x));
+        l += power * vanishing(others, z) * (eval(poly, tau) - r_z);
+        power *= gamma;
+    }
+    l -= vanishing(all_points.iter(), z) * w;
+    let proof = ShplonkProof {
+        w: setup.commit(w),
+        w_prime: setup.commit(l / (tau - z)),
+    };
+    assert_eq!(
+        verify_shplonk(&setup.pvk, &queries, &proof, gamma, z),
+        Ok(true)
+    );
+    assert_eq!(
+        verify_shplonk(&setup.pvk, &queries, &proof, gamma + Fr::one(), z),
+        Ok(false)
+    );
+
+    let mut wrong = queries.clone();
+    wrong[2].values[1] += Fr::one();
+    assert_eq!(
+        verify_shplonk(&setup.pvk, &wrong, &proof, gamma, z),
+        Ok(false)
+    );
+
+    let mut repeated = queries.clone();
+    repeated[1].points[1] = x0;
+    assert_eq!(
+        verify_shplonk(&setup.pvk, &repeated, &proof, gamma, z),
+        Err(Error::InvalidQuery)
+    );
+    let mut mismatched = queries;
+    mismatched[0].values.push(Fr::one());
+    assert_eq!(
+        verify_shplonk(&setup.pvk, &mismatched, &proof, gamma, z),
+        Err(Error::InvalidQuery)
+    );
+}
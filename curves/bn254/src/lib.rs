@@ -47,10 +47,34 @@
     rust_2018_idioms,
     unsafe_code
 )]
-#![allow(clippy::result_unit_err)]
 
-mod base_impl;
 mod curves;
+pub mod eth;
+#[cfg(feature = "fflonk")]
+pub mod fflonk;
+#[cfg(feature = "gnark")]
+pub mod gnark;
+pub mod groth16;
+#[cfg(feature = "halo2")]
+pub mod halo2;
+#[cfg(feature = "honk")]
+pub mod honk;
+pub mod host;
+pub mod kzg;
+pub mod metering;
+pub mod native;
+#[cfg(feature = "plonk")]
+pub mod plonk;
+#[cfg(feature = "ptau")]
+pub mod ptau;
+#[cfg(feature = "snarkjs")]
+pub mod snarkjs;
+#[cfg(feature = "snarkpack")]
+pub mod snarkpack;
+#[cfg(feature = "zkey")]
+pub mod zkey;
+#[cfg(feature = "zkvm")]
+pub mod zkvm;
 
 pub use ark_bn254::{fq, fq::*, fq12, fq12::*, fq2, fq2::*, fq6, fq6::*, fr, fr::*};
 
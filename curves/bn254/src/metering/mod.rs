@@ -0,0 +1,310 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Gas metering for the hooks.
+//!
+//! [`GasSchedule`] prices the hook-backed operations: the Ethereum precompiles
+//! follow [EIP-1108](https://eips.ethereum.org/EIPS/eip-1108), while the
+//! multi scalar multiplications follow the size-parameterized discount model of
+//! [EIP-2537](https://eips.ethereum.org/EIPS/eip-2537).
+//!
+//! [`MeteredHooks`] wraps a set of [`CurveHooks`] and charges each call to a
+//! [`GasMeter`] before forwarding it, aborting with [`OUT_OF_GAS`] when
+//! the budget is exceeded.
+
+use ark_models_ext::{pairing::Pairing, CurveConfig};
+use ark_std::{marker::PhantomData, vec::Vec};
+
+use crate::{
+    convert::{convert_affine, convert_projective},
+    g1, g2, Bn254, CurveHooks, G1Projective, G2Projective, HookError,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Error returned when the gas budget is exceeded.
+///
+/// Metering is a backend concern, so it is reported with a [`HookError::Backend`]
+/// code reserved by this module: hooks wrapped by [`MeteredHooks`] shouldn't use
+/// it for their own failures.
+pub const OUT_OF_GAS: HookError = HookError::Backend(u32::MAX);
+
+/// Divisor of the MSM discount tables entries.
+pub const MSM_MULTIPLIER: u64 = 1000;
+
+/// EIP-2537 discount table for G1 MSM, indexed by the number of pairs minus one.
+pub static DISCOUNT_TABLE_G1_MSM: [u16; 128] = [
+    1000, 949, 848, 797, 764, 750, 738, 728, 719, 712, 705, 698, 692, 687, 682, 677, 673, 669, 665,
+    661, 658, 654, 651, 648, 645, 642, 640, 637, 635, 632, 630, 627, 625, 623, 621, 619, 617, 615,
+    613, 611, 609, 608, 606, 604, 603, 601, 599, 598, 596, 595, 593, 592, 591, 589, 588, 586, 585,
+    584, 582, 581, 580, 579, 577, 576, 575, 574, 573, 572, 570, 569, 568, 567, 566, 565, 564, 563,
+    562, 561, 560, 559, 558, 557, 556, 555, 554, 553, 552, 551, 550, 549, 548, 547, 547, 546, 545,
+    544, 543, 542, 541, 540, 540, 539, 538, 537, 536, 536, 535, 534, 533, 532, 532, 531, 530, 529,
+    528, 528, 527, 526, 525, 525, 524, 523, 522, 522, 521, 520, 520, 519,
+];
+
+/// EIP-2537 discount table for G2 MSM, indexed by the number of pairs minus one.
+pub static DISCOUNT_TABLE_G2_MSM: [u16; 128] = [
+    1000, 1000, 923, 884, 855, 832, 812, 796, 782, 770, 759, 749, 740, 732, 724, 717, 711, 704,
+    699, 693, 688, 683, 679, 674, 670, 666, 663, 659, 655, 652, 649, 646, 643, 640, 637, 634, 632,
+    629, 627, 624, 622, 620, 618, 615, 613, 611, 609, 607, 606, 604, 602, 600, 598, 597, 595, 593,
+    592, 590, 589, 587, 586, 584, 583, 582, 580, 579, 578, 576, 575, 574, 573, 571, 570, 569, 568,
+    567, 566, 565, 563, 562, 561, 560, 559, 558, 557, 556, 555, 554, 553, 552, 552, 551, 550, 549,
+    548, 547, 546, 545, 545, 544, 543, 542, 541, 541, 540, 539, 538, 537, 537, 536, 535, 535, 534,
+    533, 532, 532, 531, 530, 530, 529, 528, 528, 527, 526, 526, 525, 524, 524,
+];
+
+/// Size-parameterized cost of a multi scalar multiplication.
+///
+/// The cost of `k` pairs is `k * mul * discount[k - 1] / MSM_MULTIPLIER`, where the
+/// last discount applies to any larger `k`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MsmCost {
+    /// Cost of a single scalar multiplication.
+    pub mul: u64,
+    /// Discounts, in thousandths, by number of pairs. Must not be empty.
+    pub discount: &'static [u16],
+}
+
+impl MsmCost {
+    /// Cost of a multi scalar multiplication of `k` pairs.
+    pub fn cost(&self, k: usize) -> u64 {
+        if k == 0 {
+            return 0;
+        }
+        let index = k.min(self.discount.len()) - 1;
+        let discount = self.discount[index] as u64;
+        (k as u64).saturating_mul(self.mul).saturating_mul(discount) / MSM_MULTIPLIER
+    }
+}
+
+/// Gas cost of the hook-backed operations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GasSchedule {
+    /// Cost of `ecAdd`.
+    pub ec_add: u64,
+    /// Cost of `ecMul`.
+    pub ec_mul: u64,
+    /// Cost of a G2 scalar multiplication.
+    pub ec_mul_g2: u64,
+    /// Fixed cost of `ecPairing`, charged for the final exponentiation.
+    pub pairing_base: u64,
+    /// Cost of each pair of `ecPairing`, charged for the Miller loop.
+    pub pairing_per_pair: u64,
+    /// Cost of G1 multi scalar multiplications.
+    pub msm_g1: MsmCost,
+    /// Cost of G2 multi scalar multiplications.
+    pub msm_g2: MsmCost,
+}
+
+impl GasSchedule {
+    /// EIP-1108 (Istanbul) costs.
+    ///
+    /// G2 operations have no Ethereum counterpart: `ec_mul_g2` and the G2 MSM base
+    /// cost are extrapolated from `ecMul` with the G2/G1 ratio (22500/12000) of the
+    /// BLS12-381 multiplication costs of EIP-2537.
+    pub const EIP_1108: GasSchedule = GasSchedule {
+        ec_add: 150,
+        ec_mul: 6_000,
+        ec_mul_g2: 11_250,
+        pairing_base: 45_000,
+        pairing_per_pair: 34_000,
+        msm_g1: MsmCost {
+            mul: 6_000,
+            discount: &DISCOUNT_TABLE_G1_MSM,
+        },
+        msm_g2: MsmCost {
+            mul: 11_250,
+            discount: &DISCOUNT_TABLE_G2_MSM,
+        },
+    };
+
+    /// Cost of `ecAdd`.
+    pub fn ec_add_cost(&self) -> u64 {
+        self.ec_add
+    }
+
+    /// Cost of `ecMul`.
+    pub fn ec_mul_cost(&self) -> u64 {
+        self.ec_mul
+    }
+
+    /// Cost of a G2 scalar multiplication.
+    pub fn ec_mul_g2_cost(&self) -> u64 {
+        self.ec_mul_g2
+    }
+
+    /// Cost of `ecPairing` over the given number of pairs.
+    pub fn ec_pairing_cost(&self, pairs: usize) -> u64 {
+        self.pairing_base
+            .saturating_add(self.multi_miller_loop_cost(pairs))
+    }
+
+    /// Cost of a multi Miller loop over the given number of pairs.
+    pub fn multi_miller_loop_cost(&self, pairs: usize) -> u64 {
+        (pairs as u64).saturating_mul(self.pairing_per_pair)
+    }
+
+    /// Cost of a final exponentiation.
+    pub fn final_exponentiation_cost(&self) -> u64 {
+        self.pairing_base
+    }
+
+    /// Cost of a G1 multi scalar multiplication of `k` pairs.
+    pub fn msm_g1_cost(&self, k: usize) -> u64 {
+        self.msm_g1.cost(k)
+    }
+
+    /// Cost of a G2 multi scalar multiplication of `k` pairs.
+    pub fn msm_g2_cost(&self, k: usize) -> u64 {
+        self.msm_g2.cost(k)
+    }
+}
+
+impl Default for GasSchedule {
+    fn default() -> Self {
+        GasSchedule::EIP_1108
+    }
+}
+
+/// Gas budget charged by [`MeteredHooks`].
+///
+/// Hooks have no state, thus the budget is owned by the implementer (e.g. a
+/// thread local or a global of the execution environment).
+pub trait GasMeter: 'static {
+    /// Costs of the metered operations.
+    const SCHEDULE: GasSchedule = GasSchedule::EIP_1108;
+
+    /// Charges the given amount.
+    ///
+    /// Returns [`OUT_OF_GAS`] if the budget is exceeded, in which case the
+    /// metered operation is not executed.
+    fn charge(amount: u64) -> Result<(), HookError>;
+}
+
+/// Hooks charging each call to the [`GasMeter`] `M` before forwarding it to `H`.
+pub struct MeteredHooks<H, M>(PhantomData<fn() -> (H, M)>);
+
+impl<H: CurveHooks, M: GasMeter> CurveHooks for MeteredHooks<H, M> {
+    fn bn254_multi_miller_loop(
+        g1: impl Iterator<Item = <Bn254<Self> as Pairing>::G1Prepared>,
+        g2: impl Iterator<Item = <Bn254<Self> as Pairing>::G2Prepared>,
+    ) -> Result<<Bn254<Self> as Pairing>::TargetField, HookError> {
+        let g1: Vec<_> = g1.map(|item| convert_affine(item.0).into()).collect();
+        let g2: Vec<_> = g2.map(|item| convert_affine(item.0).into()).collect();
+        M::charge(M::SCHEDULE.multi_miller_loop_cost(g1.len().min(g2.len())))?;
+        H::bn254_multi_miller_loop(g1.into_iter(), g2.into_iter())
+    }
+
+    fn bn254_final_exponentiation(
+        target: <Bn254<Self> as Pairing>::TargetField,
+    ) -> Result<<Bn254<Self> as Pairing>::TargetField, HookError> {
+        M::charge(M::SCHEDULE.final_exponentiation_cost())?;
+        H::bn254_final_exponentiation(target)
+    }
+
+    fn bn254_pairing_check(
+        g1: impl Iterator<Item = <Bn254<Self> as Pairing>::G1Prepared>,
+        g2: impl Iterator<Item = <Bn254<Self> as Pairing>::G2Prepared>,
+    ) -> Result<bool, HookError> {
+        let g1: Vec<_> = g1.map(|item| convert_affine(item.0).into()).collect();
+        let g2: Vec<_> = g2.map(|item| convert_affine(item.0).into()).collect();
+        M::charge(M::SCHEDULE.ec_pairing_cost(g1.len().min(g2.len())))?;
+        H::bn254_pairing_check(g1.into_iter(), g2.into_iter())
+    }
+
+    fn bn254_msm_g1(
+        bases: &[g1::G1Affine<Self>],
+        scalars: &[<g1::Config<Self> as CurveConfig>::ScalarField],
+    ) -> Result<G1Projective<Self>, HookError> {
+        M::charge(M::SCHEDULE.msm_g1_cost(bases.len().min(scalars.len())))?;
+        let bases: Vec<_> = bases.iter().map(|base| convert_affine(*base)).collect();
+        H::bn254_msm_g1(&bases, scalars).map(convert_projective)
+    }
+
+    fn bn254_msm_g2(
+        bases: &[g2::G2Affine<Self>],
+        scalars: &[<g2::Config<Self> as CurveConfig>::ScalarField],
+    ) -> Result<G2Projective<Self>, HookError> {
+        M::charge(M::SCHEDULE.msm_g2_cost(bases.len().min(scalars.len())))?;
+        let bases: Vec<_> = bases.iter().map(|base| convert_affine(*base)).collect();
+        H::bn254_msm_g2(&bases, scalars).map(convert_projective)
+    }
+
+    fn bn254_mul_projective_g1(
+        base: &G1Projective<Self>,
+        scalar: &[u64],
+    ) -> Result<G1Projective<Self>, HookError> {
+        M::charge(M::SCHEDULE.ec_mul_cost())?;
+        H::bn254_mul_projective_g1(&convert_projective(*base), scalar).map(convert_projective)
+    }
+
+    fn bn254_mul_projective_g2(
+        base: &G2Projective<Self>,
+        scalar: &[u64],
+    ) -> Result<G2Projective<Self>, HookError> {
+        M::charge(M::SCHEDULE.ec_mul_g2_cost())?;
+        H::bn254_mul_projective_g2(&convert_projective(*base), scalar).map(convert_projective)
+    }
+}
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    static THREAD_BUDGET: core::cell::Cell<(u64, u64)> = const { core::cell::Cell::new((0, 0)) };
+}
+
+/// [`GasMeter`] accumulating the charged gas against a per-thread budget.
+///
+/// The budget is zero until [`ThreadBudget::reset`] is called.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ThreadBudget;
+
+#[cfg(feature = "std")]
+impl ThreadBudget {
+    /// Sets the budget of the current thread to `limit` and clears the used gas.
+    pub fn reset(limit: u64) {
+        THREAD_BUDGET.with(|budget| budget.set((limit, 0)));
+    }
+
+    /// Gas used by the current thread since the last reset.
+    pub fn used() -> u64 {
+        THREAD_BUDGET.with(|budget| budget.get().1)
+    }
+
+    /// Gas still available to the current thread.
+    pub fn remaining() -> u64 {
+        THREAD_BUDGET.with(|budget| {
+            let (limit, used) = budget.get();
+            limit - used
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl GasMeter for ThreadBudget {
+    fn charge(amount: u64) -> Result<(), HookError> {
+        THREAD_BUDGET.with(|budget| {
+            let (limit, used) = budget.get();
+            let used = used
+                .checked_add(amount)
+                .filter(|used| *used <= limit)
+                .ok_or(OUT_OF_GAS)?;
+            budget.set((limit, used));
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::{
+    eth,
+    native::{G1Native, G2Native, NativeHooks},
+    Fr, FromArk, ToArk, TryCurveOps, TryPairing,
+};
+use ark_ff::UniformRand;
+use ark_models_ext::CurveGroup;
+use ark_std::test_rng;
+
+type Hooks = MeteredHooks<NativeHooks, ThreadBudget>;
+
+#[test]
+fn eip_1108_costs() {
+    let schedule = GasSchedule::default();
+    assert_eq!(schedule.ec_add_cost(), 150);
+    assert_eq!(schedule.ec_mul_cost(), 6_000);
+    assert_eq!(schedule.ec_mul_g2_cost(), 11_250);
+    assert_eq!(schedule.ec_pairing_cost(0), 45_000);
+    assert_eq!(schedule.ec_pairing_cost(2), 113_000);
+    assert_eq!(
+        schedule.multi_miller_loop_cost(2) + schedule.final_exponentiation_cost(),
+        schedule.ec_pairing_cost(2)
+    );
+}
+
+#[test]
+fn msm_costs() {
+    let schedule = GasSchedule::default();
+    assert_eq!(schedule.msm_g1_cost(0), 0);
+    assert_eq!(schedule.msm_g1_cost(1), schedule.ec_mul_cost());
+    assert_eq!(schedule.msm_g1_cost(2), 2 * 6_000 * 949 / 1000);
+    assert_eq!(schedule.msm_g1_cost(128), 128 * 6_000 * 519 / 1000);
+    assert_eq!(schedule.msm_g1_cost(1000), 1000 * 6_000 * 519 / 1000);
+    assert_eq!(schedule.msm_g2_cost(2), 2 * 11_250);
+    assert!(schedule.msm_g1_cost(64) < 64 * schedule.msm_g1_cost(1));
+}
+
+#[test]
+fn metered_hooks_charge_the_budget() {
+    let mut rng = test_rng();
+    let native_p = G1Native::rand(&mut rng);
+    let p = G1Projective::<Hooks>::from_ark(native_p.to_ark());
+    let q = G2Projective::<Hooks>::from_ark(G2Native::rand(&mut rng).to_ark());
+    let s = Fr::rand(&mut rng);
+    let schedule = GasSchedule::EIP_1108;
+
+    ThreadBudget::reset(1_000_000);
+    assert_eq!(Bn254::<Hooks>::pairing_check([p, -p], [q, q]), Ok(true));
+    assert_eq!(ThreadBudget::used(), schedule.ec_pairing_cost(2));
+
+    ThreadBudget::reset(1_000_000);
+    assert!(Bn254::<Hooks>::try_pairing(p, q).is_ok());
+    assert_eq!(ThreadBudget::used(), schedule.ec_pairing_cost(1));
+
+    ThreadBudget::reset(1_000_000);
+    let res = G1Projective::<Hooks>::try_msm(&[p.into_affine(); 3], &[s; 3]).unwrap();
+    assert_eq!(res.to_ark(), (native_p * s * Fr::from(3u8)).to_ark());
+    assert!(p.try_mul(&s).is_ok());
+    assert!(q.try_mul(&s).is_ok());
+    assert_eq!(
+        ThreadBudget::used(),
+        schedule.msm_g1_cost(3) + schedule.ec_mul_cost() + schedule.ec_mul_g2_cost()
+    );
+    assert_eq!(ThreadBudget::remaining(), 1_000_000 - ThreadBudget::used());
+}
+
+#[test]
+fn metered_hooks_abort_when_out_of_gas() {
+    let mut rng = test_rng();
+    let p = G1Projective::<Hooks>::from_ark(G1Native::rand(&mut rng).to_ark());
+    let q = G2Projective::<Hooks>::from_ark(G2Native::rand(&mut rng).to_ark());
+
+    let cost = GasSchedule::EIP_1108.ec_pairing_cost(1);
+    ThreadBudget::reset(cost - 1);
+    assert_eq!(Bn254::<Hooks>::pairing_check([p], [q]), Err(OUT_OF_GAS));
+    assert_eq!(ThreadBudget::used(), 0);
+
+    // Miller loop is charged, final exponentiation exceeds the budget.
+    assert_eq!(Bn254::<Hooks>::try_pairing(p, q), Err(OUT_OF_GAS));
+    assert_eq!(
+        ThreadBudget::remaining(),
+        cost - 1 - GasSchedule::EIP_1108.multi_miller_loop_cost(1)
+    );
+
+    ThreadBudget::reset(cost);
+    assert!(Bn254::<Hooks>::pairing_check([p], [q]).is_ok());
+    assert_eq!(ThreadBudget::remaining(), 0);
+    assert_eq!(p.try_mul(&Default::default()), Err(OUT_OF_GAS));
+
+    // Precompiles dispatching through the metered hooks are metered as well.
+    ThreadBudget::reset(GasSchedule::EIP_1108.ec_mul_cost() - 1);
+    assert_eq!(eth::ec_mul::<Hooks>(&[]), Err(eth::Error::Hook(OUT_OF_GAS)));
+}
@@ -0,0 +1,225 @@
+// Copyright 2022 arkworks contributors
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Native software implementation of the hooks.
+//!
+//! The *BN-254* helpers convert from Arkworks-Ext types to Arkworks upstream types
+//! by copying the coordinates and jump into the *Arkworks* upstream methods.
+//! These are the same code used by the default [`CurveHooks`] implementations,
+//! and can be reused by the host side of a runtime to serve the hooks.
+//!
+//! The `*_generic` helpers work with any pair of curves sharing the same encoding
+//! by safely transmuting from Arkworks-Ext types to Arkworks upstream types via
+//! encoding and decoding.
+
+use ark_ec::{
+    pairing::{MillerLoopOutput, Pairing},
+    short_weierstrass::{Affine as SWAffine, Projective as SWProjective, SWCurveConfig},
+    twisted_edwards::{Affine as TEAffine, Projective as TEProjective, TECurveConfig},
+    CurveConfig, VariableBaseMSM,
+};
+use ark_scale::{
+    ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate},
+    scale::{Decode, Encode},
+};
+use ark_std::vec::Vec;
+
+use crate::{
+    CurveHooks, FromArk, G1Affine, G1Projective, G2Affine, G2Projective, HookError, ToArk,
+};
+use ark_bn254::{
+    g1::Config as ArkG1Config, g2::Config as ArkG2Config, Bn254 as ArkBn254, Fq12, Fr,
+};
+use ark_models_ext::bn::{G1Prepared, G2Prepared};
+
+#[cfg(feature = "scale-no-compress")]
+const SCALE_COMPRESS: Compress = Compress::No;
+#[cfg(not(feature = "scale-no-compress"))]
+const SCALE_COMPRESS: Compress = Compress::Yes;
+
+/// SCALE codec usage settings.
+///
+/// Determines whether compression and validation has been enabled for SCALE codec
+/// with respect to ARK related types. Used by default by the [`Codec`](crate::host::Codec)
+/// of the hook bridging layer.
+pub(crate) const SCALE_USAGE: u8 = ark_scale::make_usage(SCALE_COMPRESS, Validate::No);
+
+type ArkScale<T> = ark_scale::ArkScale<T, SCALE_USAGE>;
+
+trait TryTransmute {
+    fn try_transmute<U: CanonicalDeserialize>(self) -> Result<U, HookError>;
+}
+
+impl<T: CanonicalSerialize> TryTransmute for T {
+    fn try_transmute<U: CanonicalDeserialize>(self) -> Result<U, HookError> {
+        let buf = ArkScale::from(self).encode();
+        ArkScale::<U>::decode(&mut &buf[..])
+            .map(|v| v.0)
+            .map_err(|_| HookError::Encoding)
+    }
+}
+
+/// *BN-254* multi Miller loop computed by the upstream engine.
+pub fn multi_miller_loop<H: CurveHooks>(
+    g1: impl Iterator<Item = G1Prepared<crate::Config<H>>>,
+    g2: impl Iterator<Item = G2Prepared<crate::Config<H>>>,
+) -> Result<Fq12, HookError> {
+    let g1 = g1.map(ToArk::to_ark);
+    let g2 = g2.map(ToArk::to_ark);
+    Ok(ArkBn254::multi_miller_loop(g1, g2).0)
+}
+
+/// *BN-254* final exponentiation computed by the upstream engine.
+pub fn final_exponentiation(target: Fq12) -> Result<Fq12, HookError> {
+    ArkBn254::final_exponentiation(MillerLoopOutput(target))
+        .map(|res| res.0)
+        .ok_or(HookError::InvalidInput)
+}
+
+/// *BN-254* multi scalar multiplication on G1 computed by the upstream curve.
+pub fn msm_g1<H: CurveHooks>(
+    bases: &[G1Affine<H>],
+    scalars: &[Fr],
+) -> Result<G1Projective<H>, HookError> {
+    let bases: Vec<_> = bases.iter().map(|base| base.to_ark()).collect();
+    let res = <SWProjective<ArkG1Config> as VariableBaseMSM>::msm(&bases, scalars)
+        .map_err(|_| HookError::LengthMismatch)?;
+    Ok(FromArk::from_ark(res))
+}
+
+/// *BN-254* multi scalar multiplication on G2 computed by the upstream curve.
+pub fn msm_g2<H: CurveHooks>(
+    bases: &[G2Affine<H>],
+    scalars: &[Fr],
+) -> Result<G2Projective<H>, HookError> {
+    let bases: Vec<_> = bases.iter().map(|base| base.to_ark()).collect();
+    let res = <SWProjective<ArkG2Config> as VariableBaseMSM>::msm(&bases, scalars)
+        .map_err(|_| HookError::LengthMismatch)?;
+    Ok(FromArk::from_ark(res))
+}
+
+/// *BN-254* projective multiplication on G1 computed by the upstream curve.
+pub fn mul_projective_g1<H: CurveHooks>(
+    base: &G1Projective<H>,
+    scalar: &[u64],
+) -> Result<G1Projective<H>, HookError> {
+    let res = <ArkG1Config as SWCurveConfig>::mul_projective(&base.to_ark(), scalar);
+    Ok(FromArk::from_ark(res))
+}
+
+/// *BN-254* projective multiplication on G2 computed by the upstream curve.
+pub fn mul_projective_g2<H: CurveHooks>(
+    base: &G2Projective<H>,
+    scalar: &[u64],
+) -> Result<G2Projective<H>, HookError> {
+    let res = <ArkG2Config as SWCurveConfig>::mul_projective(&base.to_ark(), scalar);
+    Ok(FromArk::from_ark(res))
+}
+
+/// Multi Miller loop computed by the `ArkPairing` upstream engine.
+pub fn multi_miller_loop_generic<ExtPairing: Pairing, ArkPairing: Pairing>(
+    g1: impl Iterator<Item = ExtPairing::G1Prepared>,
+    g2: impl Iterator<Item = ExtPairing::G2Prepared>,
+) -> Result<ExtPairing::TargetField, HookError> {
+    let g1: Vec<ArkPairing::G1Affine> = g1.collect::<Vec<_>>().try_transmute()?;
+    let g2: Vec<ArkPairing::G2Affine> = g2.collect::<Vec<_>>().try_transmute()?;
+
+    let res = ArkPairing::multi_miller_loop(g1, g2).0;
+    res.try_transmute()
+}
+
+/// Final exponentiation computed by the `ArkPairing` upstream engine.
+pub fn final_exponentiation_generic<ExtPairing: Pairing, ArkPairing: Pairing>(
+    target: ExtPairing::TargetField,
+) -> Result<ExtPairing::TargetField, HookError> {
+    let target: ArkPairing::TargetField = target.try_transmute()?;
+
+    let res = ArkPairing::final_exponentiation(MillerLoopOutput(target))
+        .ok_or(HookError::InvalidInput)?;
+    res.try_transmute()
+}
+
+/// Short Weierstrass multi scalar multiplication computed by the `ArkCurve`
+/// upstream curve.
+pub fn msm_sw_generic<ExtCurve: SWCurveConfig, ArkCurve: SWCurveConfig>(
+    bases: &[SWAffine<ExtCurve>],
+    scalars: &[ExtCurve::ScalarField],
+) -> Result<SWProjective<ExtCurve>, HookError> {
+    let bases: Vec<SWAffine<ArkCurve>> = bases.try_transmute()?;
+    let scalars: Vec<ArkCurve::ScalarField> = scalars.try_transmute()?;
+
+    let res = <SWProjective<ArkCurve> as VariableBaseMSM>::msm(&bases, &scalars)
+        .map_err(|_| HookError::LengthMismatch)?;
+    res.try_transmute()
+}
+
+/// Twisted Edwards multi scalar multiplication computed by the `ArkConfig`
+/// upstream curve.
+pub fn msm_te_generic<ExtConfig: TECurveConfig, ArkConfig: TECurveConfig>(
+    bases: &[TEAffine<ExtConfig>],
+    scalars: &[ExtConfig::ScalarField],
+) -> Result<TEProjective<ExtConfig>, HookError> {
+    let bases: Vec<TEAffine<ArkConfig>> = bases.try_transmute()?;
+    let scalars: Vec<<ArkConfig as CurveConfig>::ScalarField> = scalars.try_transmute()?;
+
+    let res = <TEProjective<ArkConfig> as VariableBaseMSM>::msm(&bases, &scalars)
+        .map_err(|_| HookError::LengthMismatch)?;
+    res.try_transmute()
+}
+
+/// Short Weierstrass projective multiplication computed by the `ArkConfig`
+/// upstream curve.
+pub fn mul_projective_sw_generic<ExtConfig: SWCurveConfig, ArkConfig: SWCurveConfig>(
+    base: &SWProjective<ExtConfig>,
+    scalar: &[u64],
+) -> Result<SWProjective<ExtConfig>, HookError> {
+    let base: SWProjective<ArkConfig> = base.try_transmute()?;
+
+    let res = <ArkConfig as SWCurveConfig>::mul_projective(&base, scalar);
+    res.try_transmute()
+}
+
+/// Twisted Edwards projective multiplication computed by the `ArkConfig`
+/// upstream curve.
+pub fn mul_projective_te_generic<ExtConfig: TECurveConfig, ArkConfig: TECurveConfig>(
+    base: &TEProjective<ExtConfig>,
+    scalar: &[u64],
+) -> Result<TEProjective<ExtConfig>, HookError> {
+    let base: TEProjective<ArkConfig> = base.try_transmute()?;
+
+    let res = <ArkConfig as TECurveConfig>::mul_projective(&base, scalar);
+    res.try_transmute()
+}
+
+/// Hooks running the upstream *Arkworks* implementation of every operation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NativeHooks;
+
+impl CurveHooks for NativeHooks {}
+
+/// Kept for backward compatibility, equivalent to [`NativeHooks`].
+impl CurveHooks for () {}
+
+/// *BN-254* pairing engine running the native hooks.
+pub type Bn254Native = crate::Bn254<NativeHooks>;
+/// *BN-254* G1 projective point running the native hooks.
+pub type G1Native = crate::G1Projective<NativeHooks>;
+/// *BN-254* G2 projective point running the native hooks.
+pub type G2Native = crate::G2Projective<NativeHooks>;
+/// *BN-254* G1 affine point running the native hooks.
+pub type G1AffineNative = crate::G1Affine<NativeHooks>;
+/// *BN-254* G2 affine point running the native hooks.
+pub type G2AffineNative = crate::G2Affine<NativeHooks>;
@@ -0,0 +1,331 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! gnark PLONK verifier.
+//!
+//! Mirrors `backend/plonk/bn254/verify.go` of gnark: the challenges are derived
+//! with a SHA-256 transcript where each challenge hashes its name, the previous
+//! challenge and its bindings, the openings at `zeta` are folded into a single one
+//! and checked together with the opening of `Z` at `zeta * w` with one pairing
+//! check. BSB22 commitments are hashed into extra public inputs.
+//!
+//! Keys and proofs are read with [`crate::gnark::plonk_vk_from_bytes`] and
+//! [`crate::gnark::plonk_proof_from_bytes`].
+
+use ark_ff::{Field, One, PrimeField, Zero};
+use ark_models_ext::CurveGroup;
+use ark_std::{vec, vec::Vec};
+use educe::Educe;
+use sha2::{Digest, Sha256};
+
+use super::Error;
+use crate::{
+    gnark::{fr_to_bytes, g1_to_raw, hash_to_fr},
+    groth16::is_valid,
+    kzg, CurveHooks, Fr, G1Affine, G1Projective, TryCurveOps,
+};
+
+/// Domain separation tag used to hash BSB22 commitments into public inputs.
+pub const BSB22_DST: &[u8] = b"BSB22-Plonk";
+
+/// gnark PLONK verifying key.
+#[derive(Educe)]
+#[educe(Clone, Debug, PartialEq, Eq)]
+pub struct VerifyingKey<H: CurveHooks> {
+    /// Size of the evaluation domain.
+    pub size: u64,
+    /// Inverse of the domain size.
+    pub size_inv: Fr,
+    /// Generator of the evaluation domain.
+    pub generator: Fr,
+    /// Number of public inputs.
+    pub nb_public_variables: usize,
+    /// Coset shift of the permutation, the wires being labelled by `1`, `u` and `u^2`
+    /// cosets.
+    pub coset_shift: Fr,
+    /// Permutation commitments.
+    pub s: [G1Affine<H>; 3],
+    /// Left selector commitment.
+    pub ql: G1Affine<H>,
+    /// Right selector commitment.
+    pub qr: G1Affine<H>,
+    /// Multiplication selector commitment.
+    pub qm: G1Affine<H>,
+    /// Output selector commitment.
+    pub qo: G1Affine<H>,
+    /// Constant selector commitment.
+    pub qk: G1Affine<H>,
+    /// BSB22 commitments selectors commitments.
+    pub qcp: Vec<G1Affine<H>>,
+    /// KZG verifier key.
+    pub kzg: kzg::VerifierKey<H>,
+    /// For each BSB22 commitment, the index of its constraint, public inputs excluded.
+    pub commitment_constraint_indexes: Vec<usize>,
+}
+
+/// gnark PLONK proof.
+#[derive(Educe)]
+#[educe(Clone, Debug, PartialEq, Eq)]
+pub struct Proof<H: CurveHooks> {
+    /// Wires commitments.
+    pub lro: [G1Affine<H>; 3],
+    /// Permutation polynomial commitment.
+    pub z: G1Affine<H>,
+    /// Quotient polynomial commitments.
+    pub h: [G1Affine<H>; 3],
+    /// BSB22 commitments.
+    pub bsb22_commitments: Vec<G1Affine<H>>,
+    /// Evaluations at `zeta` of the wires, of the first two permutation polynomials
+    /// and of the BSB22 commitments selectors.
+    pub claimed_values: Vec<Fr>,
+    /// Batched opening proof at `zeta`.
+    pub batched_proof: G1Affine<H>,
+    /// Evaluation of the permutation polynomial at `zeta * w`.
+    pub z_shifted_value: Fr,
+    /// Opening proof of the permutation polynomial at `zeta * w`.
+    pub z_shifted_proof: G1Affine<H>,
+}
+
+/// SHA-256 transcript of gnark, computing one challenge.
+pub(super) struct Transcript(Sha256);
+
+impl Transcript {
+    /// Starts the challenge `name`, following the `previous` one if any.
+    pub(super) fn new(name: &str, previous: Option<&[u8; 32]>) -> Self {
+        let mut hasher = Sha256::new_with_prefix(name);
+        if let Some(previous) = previous {
+            hasher.update(previous);
+        }
+        Transcript(hasher)
+    }
+
+    pub(super) fn bind(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    pub(super) fn bind_point<H: CurveHooks>(&mut self, point: &G1Affine<H>) {
+        self.bind(&g1_to_raw(point));
+    }
+
+    pub(super) fn bind_scalar(&mut self, scalar: &Fr) {
+        self.bind(&fr_to_bytes(scalar));
+    }
+
+    /// Computes the challenge, returning its raw value as well.
+    pub(super) fn challenge(self) -> ([u8; 32], Fr) {
+        let value: [u8; 32] = self.0.finalize().into();
+        (value, Fr::from_be_bytes_mod_order(&value))
+    }
+}
+
+/// Derives the challenge folding the openings at `zeta`.
+pub(super) fn fold_challenge<H: CurveHooks>(
+    zeta: &Fr,
+    digests: &[G1Affine<H>],
+    values: &[Fr],
+    z_shifted_value: &Fr,
+) -> Fr {
+    let mut transcript = Transcript::new("gamma", None);
+    transcript.bind_scalar(zeta);
+    digests
+        .iter()
+        .for_each(|digest| transcript.bind_point(digest));
+    values
+        .iter()
+        .for_each(|value| transcript.bind_scalar(value));
+    transcript.bind_scalar(z_shifted_value);
+    transcript.challenge().1
+}
+
+/// Verifies the proof against the verifying key and the public inputs.
+pub fn verify<H: CurveHooks>(
+    vk: &VerifyingKey<H>,
+    proof: &Proof<H>,
+    public_inputs: &[Fr],
+) -> Result<bool, Error> {
+    let nb_commitments = vk.qcp.len();
+    let vk_points = vk.s.iter().chain([&vk.ql, &vk.qr, &vk.qm, &vk.qo, &vk.qk]);
+    let valid_vk = vk.size.is_power_of_two()
+        && (vk.size_inv * Fr::from(vk.size)).is_one()
+        && super::is_primitive_root(&vk.generator, vk.size)
+        && vk.commitment_constraint_indexes.len() == nb_commitments
+        && vk_points.chain(&vk.qcp).all(is_valid);
+    if !valid_vk {
+        return Err(Error::MalformedVerifyingKey);
+    }
+    let pvk = kzg::prepare_verifier_key(vk.kzg)?;
+    let valid_proof = proof.bsb22_commitments.len() == nb_commitments
+        && proof.claimed_values.len() == 5 + nb_commitments
+        && proof
+            .lro
+            .iter()
+            .chain(&proof.h)
+            .chain(&proof.bsb22_commitments)
+            .chain([&proof.z, &proof.batched_proof, &proof.z_shifted_proof])
+            .all(is_valid);
+    if !valid_proof {
+        return Err(Error::MalformedProof);
+    }
+    if public_inputs.len() != vk.nb_public_variables {
+        return Err(Error::InvalidPublicInputsLength {
+            expected: vk.nb_public_variables,
+            found: public_inputs.len(),
+        });
+    }
+
+    let mut transcript = Transcript::new("gamma", None);
+    vk.s.iter()
+        .chain([&vk.ql, &vk.qr, &vk.qm, &vk.qo, &vk.qk])
+        .chain(&vk.qcp)
+        .for_each(|point| transcript.bind_point(point));
+    public_inputs
+        .iter()
+        .for_each(|input| transcript.bind_scalar(input));
+    proof
+        .lro
+        .iter()
+        .for_each(|point| transcript.bind_point(point));
+    let (previous, gamma) = transcript.challenge();
+
+    let (previous, beta) = Transcript::new("beta", Some(&previous)).challenge();
+
+    let mut transcript = Transcript::new("alpha", Some(&previous));
+    proof
+        .bsb22_commitments
+        .iter()
+        .chain([&proof.z])
+        .for_each(|point| transcript.bind_point(point));
+    let (previous, alpha) = transcript.challenge();
+
+    let mut transcript = Transcript::new("zeta", Some(&previous));
+    proof
+        .h
+        .iter()
+        .for_each(|point| transcript.bind_point(point));
+    let (zeta_challenge, zeta) = transcript.challenge();
+
+    // Lagrange basis evaluation `L_i(zeta) = w^i * (zeta^n - 1) / (n * (zeta - w^i))`.
+    let zh = zeta.pow([vk.size]) - Fr::one();
+    let lagrange = |w_i: Fr| {
+        (zeta - w_i)
+            .inverse()
+            .map(|den| w_i * zh * den * vk.size_inv)
+    };
+    let Some(lagrange_one) = lagrange(Fr::one()) else {
+        return Ok(false);
+    };
+    let mut pi = Fr::zero();
+    let mut w_i = Fr::one();
+    for input in public_inputs {
+        let Some(l) = lagrange(w_i) else {
+            return Ok(false);
+        };
+        pi += l * input;
+        w_i *= vk.generator;
+    }
+    for (commitment, index) in proof
+        .bsb22_commitments
+        .iter()
+        .zip(&vk.commitment_constraint_indexes)
+    {
+        let hash = hash_to_fr(&g1_to_raw(commitment), BSB22_DST);
+        let w_i = vk.generator.pow([(vk.nb_public_variables + index) as u64]);
+        let Some(l) = lagrange(w_i) else {
+            return Ok(false);
+        };
+        pi += l * hash;
+    }
+
+    let [l, r, o, s1, s2] = [0, 1, 2, 3, 4].map(|i| proof.claimed_values[i]);
+    let zu = proof.z_shifted_value;
+    let alpha2_lagrange_one = alpha.square() * lagrange_one;
+    let perm_l = l + beta * s1 + gamma;
+    let perm_r = r + beta * s2 + gamma;
+
+    // Opening of the linearized polynomial at `zeta`.
+    let const_lin = -(alpha * perm_l * perm_r * (o + gamma) * zu - alpha2_lagrange_one + pi);
+    let s3_coeff = alpha * perm_l * perm_r * beta * zu;
+    let u = vk.coset_shift;
+    let z_coeff = alpha2_lagrange_one
+        - alpha
+            * (l + beta * zeta + gamma)
+            * (r + beta * u * zeta + gamma)
+            * (o + beta * u.square() * zeta + gamma);
+    let zeta_n_plus_two = zeta.pow([vk.size + 2]);
+
+    // sum_i qcp_i(zeta) * [Pi_i] + l * [Ql] + r * [Qr] + lr * [Qm] + o * [Qo] + [Qk]
+    //     + s3_coeff * [S3] + z_coeff * [Z]
+    //     - zh * ([H0] + zeta^(n+2) * [H1] + zeta^(2(n+2)) * [H2])
+    let mut bases = proof.bsb22_commitments.clone();
+    bases.extend([
+        vk.ql, vk.qr, vk.qm, vk.qo, vk.qk, vk.s[2], proof.z, proof.h[0], proof.h[1], proof.h[2],
+    ]);
+    let mut scalars = proof.claimed_values[5..].to_vec();
+    scalars.extend([
+        l,
+        r,
+        l * r,
+        o,
+        Fr::one(),
+        s3_coeff,
+        z_coeff,
+        -zh,
+        -zh * zeta_n_plus_two,
+        -zh * zeta_n_plus_two.square(),
+    ]);
+    let linearized = G1Projective::<H>::try_msm(&bases, &scalars)?.into_affine();
+
+    // Fold the openings at `zeta`.
+    let mut digests = vec![linearized];
+    digests.extend(proof.lro);
+    digests.extend([vk.s[0], vk.s[1]]);
+    digests.extend(&vk.qcp);
+    let mut values = vec![const_lin];
+    values.extend(&proof.claimed_values);
+    let fold = fold_challenge(&zeta, &digests, &values, &zu);
+    let powers: Vec<Fr> = core::iter::successors(Some(Fr::one()), |power| Some(*power * fold))
+        .take(digests.len())
+        .collect();
+    let folded_digest = G1Projective::<H>::try_msm(&digests, &powers)?.into_affine();
+    let folded_value = values.iter().zip(&powers).map(|(v, p)| *v * p).sum();
+
+    let mut transcript = Transcript::new("u", Some(&zeta_challenge));
+    transcript.bind_scalar(&fold);
+    for point in [
+        folded_digest,
+        proof.z,
+        proof.batched_proof,
+        proof.z_shifted_proof,
+    ] {
+        transcript.bind_point(&point);
+    }
+    let (_, challenge) = transcript.challenge();
+
+    let openings = [
+        kzg::Opening {
+            commitment: folded_digest,
+            point: zeta,
+            value: folded_value,
+            proof: proof.batched_proof,
+        },
+        kzg::Opening {
+            commitment: proof.z,
+            point: zeta * vk.generator,
+            value: zu,
+            proof: proof.z_shifted_proof,
+        },
+    ];
+    Ok(kzg::verify_batch(&pvk, &openings, challenge)?)
+}
@@ -0,0 +1,93 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [PLONK](https://eprint.iacr.org/2019/953.pdf) verifiers.
+//!
+//! Provers differ in the linearization of the constraints, in the batching of the
+//! openings and in the Fiat-Shamir transcript, so each of them gets its own
+//! verifier:
+//! * [`snarkjs`] verifies proofs of snarkjs, with a Keccak-256 transcript;
+//! * [`gnark`] verifies proofs of gnark, with a SHA-256 transcript and the BSB22
+//!   commitments extension.
+//!
+//! All the group operations go through the `msm_g1` hook and the openings are
+//! checked with a single KZG pairing check (see [`crate::kzg`]).
+
+use ark_ff::{Field, One};
+
+use crate::{kzg, Fr, HookError};
+
+pub mod gnark;
+pub mod snarkjs;
+
+#[cfg(test)]
+pub(crate) mod tests;
+
+/// Error returned by the verifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// Verifying key is not consistent or its points are not valid.
+    MalformedVerifyingKey,
+    /// Proof shape doesn't match the verifying key or its points are not valid.
+    MalformedProof,
+    /// Number of public inputs doesn't match the verifying key.
+    InvalidPublicInputsLength {
+        /// Number of public inputs expected by the verifying key.
+        expected: usize,
+        /// Number of public inputs provided.
+        found: usize,
+    },
+    /// Failure reported by the hooks.
+    Hook(HookError),
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::MalformedVerifyingKey => write!(f, "malformed verifying key"),
+            Error::MalformedProof => write!(f, "malformed proof"),
+            Error::InvalidPublicInputsLength { expected, found } => write!(
+                f,
+                "invalid public inputs length (expected {expected}, found {found})"
+            ),
+            Error::Hook(err) => write!(f, "hook failure: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl From<HookError> for Error {
+    fn from(err: HookError) -> Self {
+        Error::Hook(err)
+    }
+}
+
+impl From<kzg::Error> for Error {
+    fn from(err: kzg::Error) -> Self {
+        match err {
+            kzg::Error::MalformedVerifierKey => Error::MalformedVerifyingKey,
+            kzg::Error::Hook(err) => Error::Hook(err),
+            _ => Error::MalformedProof,
+        }
+    }
+}
+
+/// Checks that `root` generates the domain of size `n`, a power of two.
+fn is_primitive_root(root: &Fr, n: u64) -> bool {
+    root.pow([n]).is_one() && (n == 1 || !root.pow([n / 2]).is_one())
+}
@@ -0,0 +1,325 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! snarkjs PLONK verifier.
+//!
+//! Mirrors `plonk_verify.js` of snarkjs: the challenges are derived with a
+//! Keccak-256 transcript over the big-endian encoding of scalars and of the
+//! uncompressed points, and the two openings at `xi` and `xi * w` are checked with
+//! a single pairing check
+//!
+//! `e(Wxi + u * Wxiw, X_2) = e(xi * Wxi + u * xi * w * Wxiw + F - E, g2)`
+//!
+//! where both sides are computed with one MSM each.
+
+use ark_ff::{FftField, Field, One, PrimeField};
+use ark_models_ext::AffineRepr;
+use ark_std::vec::Vec;
+use educe::Educe;
+use sha3::{Digest, Keccak256};
+
+use super::Error;
+use crate::{
+    gnark::{fr_to_bytes, g1_to_raw},
+    groth16::is_valid,
+    kzg, CurveHooks, Fr, G1Affine, G1Projective, G2Affine, TryCurveOps,
+};
+
+/// snarkjs PLONK verifying key.
+#[derive(Educe)]
+#[educe(Clone, Debug, PartialEq, Eq)]
+pub struct VerifyingKey<H: CurveHooks> {
+    /// Number of public inputs.
+    pub n_public: usize,
+    /// Base two logarithm of the domain size.
+    pub power: u32,
+    /// Coset shift of the second wire.
+    pub k1: Fr,
+    /// Coset shift of the third wire.
+    pub k2: Fr,
+    /// Multiplication selector commitment.
+    pub qm: G1Affine<H>,
+    /// Left selector commitment.
+    pub ql: G1Affine<H>,
+    /// Right selector commitment.
+    pub qr: G1Affine<H>,
+    /// Output selector commitment.
+    pub qo: G1Affine<H>,
+    /// Constant selector commitment.
+    pub qc: G1Affine<H>,
+    /// First permutation commitment.
+    pub s1: G1Affine<H>,
+    /// Second permutation commitment.
+    pub s2: G1Affine<H>,
+    /// Third permutation commitment.
+    pub s3: G1Affine<H>,
+    /// `tau` in G2.
+    pub x_2: G2Affine<H>,
+    /// Generator of the evaluation domain.
+    pub w: Fr,
+}
+
+/// snarkjs PLONK proof.
+#[derive(Educe)]
+#[educe(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Proof<H: CurveHooks> {
+    /// First wire commitment.
+    pub a: G1Affine<H>,
+    /// Second wire commitment.
+    pub b: G1Affine<H>,
+    /// Third wire commitment.
+    pub c: G1Affine<H>,
+    /// Permutation polynomial commitment.
+    pub z: G1Affine<H>,
+    /// Low degree part of the quotient polynomial commitment.
+    pub t1: G1Affine<H>,
+    /// Middle degree part of the quotient polynomial commitment.
+    pub t2: G1Affine<H>,
+    /// High degree part of the quotient polynomial commitment.
+    pub t3: G1Affine<H>,
+    /// Opening proof at `xi`.
+    pub wxi: G1Affine<H>,
+    /// Opening proof at `xi * w`.
+    pub wxiw: G1Affine<H>,
+    /// First wire evaluation at `xi`.
+    pub eval_a: Fr,
+    /// Second wire evaluation at `xi`.
+    pub eval_b: Fr,
+    /// Third wire evaluation at `xi`.
+    pub eval_c: Fr,
+    /// First permutation evaluation at `xi`.
+    pub eval_s1: Fr,
+    /// Second permutation evaluation at `xi`.
+    pub eval_s2: Fr,
+    /// Permutation polynomial evaluation at `xi * w`.
+    pub eval_zw: Fr,
+}
+
+/// Keccak-256 transcript of snarkjs.
+///
+/// Every challenge hashes the data added since the previous one.
+pub(crate) struct Transcript(Keccak256);
+
+impl Transcript {
+    pub(crate) fn new() -> Self {
+        Transcript(Keccak256::new())
+    }
+
+    pub(crate) fn add_point<H: CurveHooks>(&mut self, point: &G1Affine<H>) {
+        self.0.update(g1_to_raw(point));
+    }
+
+    pub(crate) fn add_scalar(&mut self, scalar: &Fr) {
+        self.0.update(fr_to_bytes(scalar));
+    }
+
+    pub(crate) fn challenge(&mut self) -> Fr {
+        Fr::from_be_bytes_mod_order(&self.0.finalize_reset())
+    }
+}
+
+/// Fiat-Shamir challenges of the protocol.
+pub(super) struct Challenges {
+    pub(super) beta: Fr,
+    pub(super) gamma: Fr,
+    pub(super) alpha: Fr,
+    pub(super) xi: Fr,
+    pub(super) v: Fr,
+    pub(super) u: Fr,
+}
+
+impl Challenges {
+    pub(super) fn derive<H: CurveHooks>(
+        vk: &VerifyingKey<H>,
+        proof: &Proof<H>,
+        public_inputs: &[Fr],
+    ) -> Self {
+        let mut transcript = Transcript::new();
+        for point in [vk.qm, vk.ql, vk.qr, vk.qo, vk.qc, vk.s1, vk.s2, vk.s3] {
+            transcript.add_point(&point);
+        }
+        public_inputs
+            .iter()
+            .for_each(|input| transcript.add_scalar(input));
+        for point in [proof.a, proof.b, proof.c] {
+            transcript.add_point(&point);
+        }
+        let beta = transcript.challenge();
+
+        transcript.add_scalar(&beta);
+        let gamma = transcript.challenge();
+
+        transcript.add_scalar(&beta);
+        transcript.add_scalar(&gamma);
+        transcript.add_point(&proof.z);
+        let alpha = transcript.challenge();
+
+        transcript.add_scalar(&alpha);
+        for point in [proof.t1, proof.t2, proof.t3] {
+            transcript.add_point(&point);
+        }
+        let xi = transcript.challenge();
+
+        transcript.add_scalar(&xi);
+        for eval in proof.evals() {
+            transcript.add_scalar(&eval);
+        }
+        let v = transcript.challenge();
+
+        transcript.add_point(&proof.wxi);
+        transcript.add_point(&proof.wxiw);
+        let u = transcript.challenge();
+
+        Challenges {
+            beta,
+            gamma,
+            alpha,
+            xi,
+            v,
+            u,
+        }
+    }
+}
+
+impl<H: CurveHooks> Proof<H> {
+    /// Evaluations in transcript order.
+    fn evals(&self) -> [Fr; 6] {
+        [
+            self.eval_a,
+            self.eval_b,
+            self.eval_c,
+            self.eval_s1,
+            self.eval_s2,
+            self.eval_zw,
+        ]
+    }
+}
+
+/// Verifies the proof against the verifying key and the public inputs.
+pub fn verify<H: CurveHooks>(
+    vk: &VerifyingKey<H>,
+    proof: &Proof<H>,
+    public_inputs: &[Fr],
+) -> Result<bool, Error> {
+    if public_inputs.len() != vk.n_public {
+        return Err(Error::InvalidPublicInputsLength {
+            expected: vk.n_public,
+            found: public_inputs.len(),
+        });
+    }
+    let n = 1u64
+        .checked_shl(vk.power)
+        .filter(|_| vk.power <= Fr::TWO_ADICITY)
+        .ok_or(Error::MalformedVerifyingKey)?;
+    let vk_points = [vk.qm, vk.ql, vk.qr, vk.qo, vk.qc, vk.s1, vk.s2, vk.s3];
+    if !super::is_primitive_root(&vk.w, n) || !vk_points.iter().all(is_valid) {
+        return Err(Error::MalformedVerifyingKey);
+    }
+    let pvk = kzg::prepare_verifier_key(kzg::VerifierKey {
+        g1: G1Affine::<H>::generator(),
+        g2: G2Affine::<H>::generator(),
+        tau_g2: vk.x_2,
+    })?;
+    let proof_points = [
+        proof.a, proof.b, proof.c, proof.z, proof.t1, proof.t2, proof.t3, proof.wxi, proof.wxiw,
+    ];
+    if !proof_points.iter().all(is_valid) {
+        return Err(Error::MalformedProof);
+    }
+
+    let Challenges {
+        beta,
+        gamma,
+        alpha,
+        xi,
+        v,
+        u,
+    } = Challenges::derive(vk, proof, public_inputs);
+    let v: Vec<Fr> = core::iter::successors(Some(v), |power| Some(*power * v))
+        .take(5)
+        .collect();
+
+    // Lagrange basis evaluations `L_i(xi) = w^i * (xi^n - 1) / (n * (xi - w^i))`.
+    let xin = xi.pow([n]);
+    let zh = xin - Fr::one();
+    let mut lagrange = Vec::with_capacity(vk.n_public.max(1));
+    let mut w = Fr::one();
+    for _ in 0..vk.n_public.max(1) {
+        let Some(den) = (Fr::from(n) * (xi - w)).inverse() else {
+            return Ok(false);
+        };
+        lagrange.push(w * zh * den);
+        w *= vk.w;
+    }
+    let l1 = lagrange[0];
+    let pi = -public_inputs
+        .iter()
+        .zip(&lagrange)
+        .map(|(input, l)| *input * l)
+        .sum::<Fr>();
+
+    let Proof {
+        eval_a: a,
+        eval_b: b,
+        eval_c: c,
+        eval_s1: s1,
+        eval_s2: s2,
+        eval_zw: zw,
+        ..
+    } = *proof;
+    let alpha2 = alpha.square();
+    let perm_a = a + beta * s1 + gamma;
+    let perm_b = b + beta * s2 + gamma;
+    let r0 = pi - l1 * alpha2 - alpha * perm_a * perm_b * (c + gamma) * zw;
+
+    // D = ab * Qm + a * Ql + b * Qr + c * Qo + Qc + z_coeff * Z - s3_coeff * S3
+    //     - zh * (T1 + xi^n * T2 + xi^2n * T3)
+    let betaxi = beta * xi;
+    let z_coeff =
+        alpha * (a + betaxi + gamma) * (b + betaxi * vk.k1 + gamma) * (c + betaxi * vk.k2 + gamma)
+            + l1 * alpha2
+            + u;
+    let s3_coeff = perm_a * perm_b * alpha * beta * zw;
+    let e = -r0 + v[0] * a + v[1] * b + v[2] * c + v[3] * s1 + v[4] * s2 + u * zw;
+
+    // xi * Wxi + u * xi * w * Wxiw + F - E, with F = D + v1 * A + ... + v5 * S2.
+    let bases = [
+        proof.wxi, proof.wxiw, vk.qm, vk.ql, vk.qr, vk.qo, vk.qc, proof.z, vk.s3, proof.t1,
+        proof.t2, proof.t3, proof.a, proof.b, proof.c, vk.s1, vk.s2, pvk.vk.g1,
+    ];
+    let scalars = [
+        xi,
+        u * xi * vk.w,
+        a * b,
+        a,
+        b,
+        c,
+        Fr::one(),
+        z_coeff,
+        -s3_coeff,
+        -zh,
+        -zh * xin,
+        -zh * xin.square(),
+        v[0],
+        v[1],
+        v[2],
+        v[3],
+        v[4],
+        -e,
+    ];
+    let lhs = G1Projective::<H>::try_msm(&bases, &scalars)?;
+    let proofs = G1Projective::<H>::try_msm(&[proof.wxi, proof.wxiw], &[Fr::one(), u])?;
+    Ok(kzg::check(&pvk, lhs, proofs)?)
+}
@@ -0,0 +1,794 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::{
+    gnark::{g1_to_raw, hash_to_fr},
+    metering::{GasSchedule, MeteredHooks, ThreadBudget, OUT_OF_GAS},
+    native::NativeHooks,
+    CurveHooks, Fq, Fr, G1Affine, G2Affine,
+};
+use ark_ff::{FftField, Field, One, UniformRand, Zero};
+use ark_models_ext::{AffineRepr, CurveGroup};
+use ark_poly::{
+    univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Polynomial,
+    Radix2EvaluationDomain,
+};
+use ark_std::{
+    rand::{rngs::StdRng, Rng, SeedableRng},
+    vec,
+    vec::Vec,
+};
+
+pub(crate) type Poly = DensePolynomial<Fr>;
+
+/// Size of the evaluation domain of the test circuit.
+pub(crate) const N: usize = 8;
+
+/// Wire slots are numbered `wire * N + row`.
+pub(crate) fn slot(wire: usize, row: usize) -> usize {
+    wire * N + row
+}
+
+fn constant(value: Fr) -> Poly {
+    Poly::from_coefficients_slice(&[value])
+}
+
+/// Circuit proving the knowledge of `y` such that `y^3 + y + 5 = x`, `x` being the
+/// public input.
+pub(crate) struct Circuit {
+    /// `ql`, `qr`, `qm`, `qo` and `qc` selectors by row.
+    pub(crate) selectors: [[Fr; N]; 5],
+    /// Wire values by row.
+    pub(crate) wires: [[Fr; N]; 3],
+    /// Copy constraints, as cycles of slots.
+    pub(crate) cycles: Vec<Vec<usize>>,
+    /// Public inputs polynomial by row.
+    pub(crate) pi: [Fr; N],
+}
+
+impl Circuit {
+    /// The public input row is `public_sign * a + PI = 0`, the sign being a prover
+    /// convention.
+    pub(crate) fn cubic(y: Fr, public_sign: Fr) -> (Self, Fr) {
+        let y2 = y * y;
+        let y3 = y2 * y;
+        let x = y3 + y + Fr::from(5u64);
+        let zero = Fr::zero();
+        let one = Fr::one();
+        let mut selectors = [[zero; N]; 5];
+        let [ql, qr, qm, qo, qc] = &mut selectors;
+        ql[0] = public_sign;
+        (qm[1], qo[1]) = (one, -one);
+        (qm[2], qo[2]) = (one, -one);
+        (ql[3], qr[3], qo[3]) = (one, one, -one);
+        (ql[4], qo[4], qc[4]) = (one, -one, Fr::from(5u64));
+        (ql[5], qr[5]) = (one, -one);
+        let mut pi = [zero; N];
+        pi[0] = -public_sign * x;
+
+        let wires = [
+            [x, y, y2, y3, y3 + y, x, zero, zero],
+            [zero, y, y, y, zero, x, zero, zero],
+            [zero, y2, y3, y3 + y, x, zero, zero, zero],
+        ];
+        let cycles = vec![
+            vec![slot(0, 0), slot(1, 5)],
+            vec![slot(0, 1), slot(1, 1), slot(1, 2), slot(1, 3)],
+            vec![slot(2, 1), slot(0, 2)],
+            vec![slot(2, 2), slot(0, 3)],
+            vec![slot(2, 3), slot(0, 4)],
+            vec![slot(2, 4), slot(0, 5)],
+        ];
+        let circuit = Circuit {
+            selectors,
+            wires,
+            cycles,
+            pi,
+        };
+        (circuit, x)
+    }
+
+    fn value(&self, slot: usize) -> Fr {
+        self.wires[slot / N][slot % N]
+    }
+
+    fn sigma(&self) -> Vec<usize> {
+        let mut sigma: Vec<usize> = (0..3 * N).collect();
+        for cycle in &self.cycles {
+            for (i, from) in cycle.iter().enumerate() {
+                assert_eq!(self.value(*from), self.value(cycle[0]));
+                sigma[*from] = cycle[(i + 1) % cycle.len()];
+            }
+        }
+        sigma
+    }
+}
+
+/// Circuit polynomials.
+pub(crate) struct Polys {
+    pub(crate) q: [Poly; 5],
+    pub(crate) s: [Poly; 3],
+    pub(crate) w: [Poly; 3],
+    pub(crate) pi: Poly,
+}
+
+/// Trusted setup whose trapdoor is known, so that the tests can act as the prover.
+pub(crate) struct Setup {
+    pub(crate) tau: Fr,
+    pub(crate) domain: Radix2EvaluationDomain<Fr>,
+}
+
+impl Setup {
+    pub(crate) fn new(rng: &mut impl Rng) -> Self {
+        Setup {
+            tau: Fr::rand(rng),
+            domain: Radix2EvaluationDomain::new(N).unwrap(),
+        }
+    }
+
+    pub(crate) fn tau_g2<H: CurveHooks>(&self) -> G2Affine<H> {
+        (G2Affine::<H>::generator() * self.tau).into_affine()
+    }
+
+    pub(crate) fn interpolate(&self, evals: &[Fr]) -> Poly {
+        Poly::from_coefficients_vec(self.domain.ifft(evals))
+    }
+
+    pub(crate) fn commit_scalar<H: CurveHooks>(&self, scalar: Fr) -> G1Affine<H> {
+        (G1Affine::<H>::generator() * scalar).into_affine()
+    }
+
+    pub(crate) fn commit<H: CurveHooks>(&self, poly: &Poly) -> G1Affine<H> {
+        self.commit_scalar(poly.evaluate(&self.tau))
+    }
+
+    pub(crate) fn open<H: CurveHooks>(&self, poly: &Poly, point: Fr) -> G1Affine<H> {
+        let value = poly.evaluate(&point);
+        self.commit_scalar((poly.evaluate(&self.tau) - value) / (self.tau - point))
+    }
+
+    /// Wire label of a slot, the wires being labelled by the `k` cosets.
+    pub(crate) fn label(&self, k: &[Fr; 3], slot: usize) -> Fr {
+        k[slot / N] * self.domain.element(slot % N)
+    }
+
+    pub(crate) fn polys(&self, circuit: &Circuit, k: &[Fr; 3]) -> Polys {
+        let sigma = circuit.sigma();
+        let s = [0, 1, 2].map(|wire| {
+            let labels: Vec<Fr> = (0..N)
+                .map(|row| self.label(k, sigma[slot(wire, row)]))
+                .collect();
+            self.interpolate(&labels)
+        });
+        Polys {
+            q: circuit.selectors.map(|evals| self.interpolate(&evals)),
+            s,
+            w: circuit.wires.map(|evals| self.interpolate(&evals)),
+            pi: self.interpolate(&circuit.pi),
+        }
+    }
+
+    /// Permutation polynomial, with `Z(w^(i+1)) = Z(w^i) * id(w^i) / sigma(w^i)`.
+    pub(crate) fn permutation(&self, circuit: &Circuit, k: &[Fr; 3], beta: Fr, gamma: Fr) -> Poly {
+        let sigma = circuit.sigma();
+        let mut z = vec![Fr::one()];
+        for row in 0..N {
+            let mut ratio = Fr::one();
+            for wire in 0..3 {
+                let value = circuit.wires[wire][row] + gamma;
+                ratio *= value + beta * self.label(k, slot(wire, row));
+                ratio /= value + beta * self.label(k, sigma[slot(wire, row)]);
+            }
+            z.push(z[row] * ratio);
+        }
+        assert_eq!(z.pop(), Some(Fr::one()));
+        self.interpolate(&z)
+    }
+
+    /// Gate constraint `gate + extra + PI`.
+    pub(crate) fn gate(&self, polys: &Polys, extra: &Poly) -> Poly {
+        let [ql, qr, qm, qo, qc] = &polys.q;
+        let [a, b, c] = &polys.w;
+        let mut gate = &(ql * a) + &(qr * b);
+        gate += &(&(qm * a) * b);
+        gate += &(qo * c);
+        gate += qc;
+        gate += extra;
+        gate += &polys.pi;
+        gate
+    }
+
+    /// Permutation constraints: `id * Z - sigma * Z(wX)` and `L1 * (Z - 1)`.
+    pub(crate) fn permutation_constraints(
+        &self,
+        polys: &Polys,
+        z: &Poly,
+        k: &[Fr; 3],
+        [beta, gamma]: [Fr; 2],
+    ) -> [Poly; 2] {
+        let mut id = constant(Fr::one());
+        let mut sigma = constant(Fr::one());
+        for ((w, s), k) in polys.w.iter().zip(&polys.s).zip(k) {
+            let w = w + &constant(gamma);
+            id = &id * &(&w + &Poly::from_coefficients_slice(&[Fr::zero(), beta * k]));
+            sigma = &sigma * &(&w + &(s * beta));
+        }
+        let mut shifted = z.clone();
+        let omega = self.domain.group_gen();
+        let mut power = Fr::one();
+        for coeff in &mut shifted.coeffs {
+            *coeff *= power;
+            power *= omega;
+        }
+        let permutation = &(&id * z) - &(&sigma * &shifted);
+
+        let mut l1 = [Fr::zero(); N];
+        l1[0] = Fr::one();
+        let first_row = &self.interpolate(&l1) * &(z - &constant(Fr::one()));
+        [permutation, first_row]
+    }
+
+    /// Divides `poly` by the vanishing polynomial, which must divide it.
+    pub(crate) fn divide(&self, poly: &Poly) -> Poly {
+        let (quotient, remainder) = poly.divide_by_vanishing_poly(self.domain);
+        assert!(remainder.is_zero());
+        quotient
+    }
+
+    /// Quotient of `gate + sign * alpha * permutation + alpha^2 * first_row` by the
+    /// vanishing polynomial.
+    fn quotient(
+        &self,
+        polys: &Polys,
+        extra: &Poly,
+        z: &Poly,
+        k: &[Fr; 3],
+        [beta, gamma, alpha]: [Fr; 3],
+        sign: Fr,
+    ) -> Poly {
+        let mut numerator = self.gate(polys, extra);
+        let [permutation, first_row] = self.permutation_constraints(polys, z, k, [beta, gamma]);
+        numerator += (sign * alpha, &permutation);
+        numerator += (alpha.square(), &first_row);
+        self.divide(&numerator)
+    }
+}
+
+/// Splits `poly` in three chunks of `len` coefficients.
+fn split(poly: &Poly, len: usize) -> [Poly; 3] {
+    assert!(poly.coeffs.len() <= 3 * len);
+    [0, 1, 2].map(|i| {
+        let coeffs = poly.coeffs.iter().skip(i * len).take(len).copied();
+        Poly::from_coefficients_vec(coeffs.collect())
+    })
+}
+
+pub(crate) fn lagrange_one(setup: &Setup, x: Fr) -> Fr {
+    setup.domain.evaluate_all_lagrange_coefficients(x)[0]
+}
+
+fn snarkjs_prove<H: CurveHooks>(
+    seed: u64,
+) -> (snarkjs::VerifyingKey<H>, snarkjs::Proof<H>, Vec<Fr>) {
+    use snarkjs::Transcript;
+
+    let rng = &mut StdRng::seed_from_u64(seed);
+    let setup = Setup::new(rng);
+    let (circuit, x) = Circuit::cubic(Fr::rand(rng), Fr::one());
+    let k = [Fr::one(), Fr::from(2u64), Fr::from(3u64)];
+    let polys = setup.polys(&circuit, &k);
+    let [ql, qr, qm, qo, qc] = &polys.q;
+    let [s1, s2, s3] = &polys.s;
+    let [a, b, c] = &polys.w;
+    let vk = snarkjs::VerifyingKey {
+        n_public: 1,
+        power: N.trailing_zeros(),
+        k1: k[1],
+        k2: k[2],
+        qm: setup.commit(qm),
+        ql: setup.commit(ql),
+        qr: setup.commit(qr),
+        qo: setup.commit(qo),
+        qc: setup.commit(qc),
+        s1: setup.commit(s1),
+        s2: setup.commit(s2),
+        s3: setup.commit(s3),
+        x_2: setup.tau_g2(),
+        w: setup.domain.group_gen(),
+    };
+
+    let mut transcript = Transcript::new();
+    for point in [vk.qm, vk.ql, vk.qr, vk.qo, vk.qc, vk.s1, vk.s2, vk.s3] {
+        transcript.add_point(&point);
+    }
+    transcript.add_scalar(&x);
+    let [a_cm, b_cm, c_cm] = [a, b, c].map(|poly| setup.commit::<H>(poly));
+    for point in [a_cm, b_cm, c_cm] {
+        transcript.add_point(&point);
+    }
+    let beta = transcript.challenge();
+    transcript.add_scalar(&beta);
+    let gamma = transcript.challenge();
+
+    let z = setup.permutation(&circuit, &k, beta, gamma);
+    let z_cm = setup.commit::<H>(&z);
+    transcript.add_scalar(&beta);
+    transcript.add_scalar(&gamma);
+    transcript.add_point(&z_cm);
+    let alpha = transcript.challenge();
+
+    let t = setup.quotient(
+        &polys,
+        &Poly::zero(),
+        &z,
+        &k,
+        [beta, gamma, alpha],
+        Fr::one(),
+    );
+    let t = split(&t, N);
+    let t_cm = t.each_ref().map(|poly| setup.commit::<H>(poly));
+    transcript.add_scalar(&alpha);
+    t_cm.iter().for_each(|point| transcript.add_point(point));
+    let xi = transcript.challenge();
+
+    let xiw = xi * vk.w;
+    let [eval_a, eval_b, eval_c, eval_s1, eval_s2] = [a, b, c, s1, s2].map(|p| p.evaluate(&xi));
+    let eval_zw = z.evaluate(&xiw);
+    transcript.add_scalar(&xi);
+    for eval in [eval_a, eval_b, eval_c, eval_s1, eval_s2, eval_zw] {
+        transcript.add_scalar(&eval);
+    }
+    let v = transcript.challenge();
+
+    // Linearization polynomial, opened along with the wires and permutations at xi.
+    let xin = xi.pow([N as u64]);
+    let zh = xin - Fr::one();
+    let perm_a = eval_a + beta * eval_s1 + gamma;
+    let perm_b = eval_b + beta * eval_s2 + gamma;
+    let z_coeff = alpha
+        * (eval_a + beta * xi + gamma)
+        * (eval_b + beta * k[1] * xi + gamma)
+        * (eval_c + beta * k[2] * xi + gamma)
+        + alpha.square() * lagrange_one(&setup, xi);
+    let s3_coeff = alpha * perm_a * perm_b * beta * eval_zw;
+    let mut r = qm * eval_a * eval_b;
+    r += (eval_a, ql);
+    r += (eval_b, qr);
+    r += (eval_c, qo);
+    r += qc;
+    r += (z_coeff, &z);
+    r += (-s3_coeff, s3);
+    r += (-zh, &t[0]);
+    r += (-zh * xin, &t[1]);
+    r += (-zh * xin.square(), &t[2]);
+    let mut batched = r;
+    let mut power = v;
+    for poly in [a, b, c, s1, s2] {
+        batched += (power, poly);
+        power *= v;
+    }
+
+    let proof = snarkjs::Proof {
+        a: a_cm,
+        b: b_cm,
+        c: c_cm,
+        z: z_cm,
+        t1: t_cm[0],
+        t2: t_cm[1],
+        t3: t_cm[2],
+        wxi: setup.open(&batched, xi),
+        wxiw: setup.open(&z, xiw),
+        eval_a,
+        eval_b,
+        eval_c,
+        eval_s1,
+        eval_s2,
+        eval_zw,
+    };
+    (vk, proof, vec![x])
+}
+
+fn gnark_prove<H: CurveHooks>(seed: u64) -> (gnark::VerifyingKey<H>, gnark::Proof<H>, Vec<Fr>) {
+    use gnark::{fold_challenge, Transcript, BSB22_DST};
+
+    let rng = &mut StdRng::seed_from_u64(seed);
+    let setup = Setup::new(rng);
+    let y = Fr::rand(rng);
+    let (mut circuit, x) = Circuit::cubic(y, -Fr::one());
+    let coset_shift = Fr::GENERATOR;
+    let k = [Fr::one(), coset_shift, coset_shift.square()];
+
+    // Row 6 commits to `y`, whose commitment is hashed into the public input of row 7.
+    let mut qcp = [Fr::zero(); N];
+    let mut committed = [Fr::zero(); N];
+    (qcp[6], committed[6]) = (Fr::one(), y);
+    circuit.selectors[3][6] = -Fr::one();
+    circuit.wires[2][6] = y;
+    circuit.cycles[1].push(slot(2, 6));
+    let (qcp, committed) = (setup.interpolate(&qcp), setup.interpolate(&committed));
+    let bsb22_commitment = setup.commit::<H>(&committed);
+    let hash = hash_to_fr(&g1_to_raw(&bsb22_commitment), BSB22_DST);
+    circuit.selectors[0][7] = -Fr::one();
+    circuit.wires[0][7] = hash;
+    circuit.pi[7] = hash;
+
+    let polys = setup.polys(&circuit, &k);
+    let [ql, qr, qm, qo, qk] = &polys.q;
+    let [s1, s2, s3] = &polys.s;
+    let [l, r, o] = &polys.w;
+    let vk = gnark::VerifyingKey {
+        size: N as u64,
+        size_inv: Fr::from(N as u64).inverse().unwrap(),
+        generator: setup.domain.group_gen(),
+        nb_public_variables: 1,
+        coset_shift,
+        s: polys.s.each_ref().map(|poly| setup.commit(poly)),
+        ql: setup.commit(ql),
+        qr: setup.commit(qr),
+        qm: setup.commit(qm),
+        qo: setup.commit(qo),
+        qk: setup.commit(qk),
+        qcp: vec![setup.commit(&qcp)],
+        kzg: kzg::VerifierKey {
+            g1: G1Affine::<H>::generator(),
+            g2: G2Affine::<H>::generator(),
+            tau_g2: setup.tau_g2(),
+        },
+        commitment_constraint_indexes: vec![6],
+    };
+
+    let lro = polys.w.each_ref().map(|poly| setup.commit::<H>(poly));
+    let mut transcript = Transcript::new("gamma", None);
+    vk.s.iter()
+        .chain([&vk.ql, &vk.qr, &vk.qm, &vk.qo, &vk.qk])
+        .chain(&vk.qcp)
+        .for_each(|point| transcript.bind_point(point));
+    transcript.bind_scalar(&x);
+    lro.iter().for_each(|point| transcript.bind_point(point));
+    let (previous, gamma) = transcript.challenge();
+    let (previous, beta) = Transcript::new("beta", Some(&previous)).challenge();
+
+    let z = setup.permutation(&circuit, &k, beta, gamma);
+    let z_cm = setup.commit::<H>(&z);
+    let mut transcript = Transcript::new("alpha", Some(&previous));
+    transcript.bind_point(&bsb22_commitment);
+    transcript.bind_point(&z_cm);
+    let (previous, alpha) = transcript.challenge();
+
+    let h = setup.quotient(
+        &polys,
+        &(&qcp * &committed),
+        &z,
+        &k,
+        [beta, gamma, alpha],
+        -Fr::one(),
+    );
+    let h = split(&h, N + 2);
+    let h_cm = h.each_ref().map(|poly| setup.commit::<H>(poly));
+    let mut transcript = Transcript::new("zeta", Some(&previous));
+    h_cm.iter().for_each(|point| transcript.bind_point(point));
+    let (_, zeta) = transcript.challenge();
+
+    let zeta_shifted = zeta * vk.generator;
+    let [l_zeta, r_zeta, o_zeta, s1_zeta, s2_zeta, qcp_zeta] =
+        [l, r, o, s1, s2, &qcp].map(|poly| poly.evaluate(&zeta));
+    let zu = z.evaluate(&zeta_shifted);
+
+    // Linearized polynomial, folded with the wires, the permutations and the BSB22
+    // selector.
+    let zh = zeta.pow([N as u64]) - Fr::one();
+    let zeta_n_plus_two = zeta.pow([N as u64 + 2]);
+    let s3_coeff =
+        alpha * (l_zeta + beta * s1_zeta + gamma) * (r_zeta + beta * s2_zeta + gamma) * beta * zu;
+    let z_coeff = alpha.square() * lagrange_one(&setup, zeta)
+        - alpha
+            * (l_zeta + beta * zeta + gamma)
+            * (r_zeta + beta * k[1] * zeta + gamma)
+            * (o_zeta + beta * k[2] * zeta + gamma);
+    let mut lin = &committed * qcp_zeta;
+    lin += (l_zeta, ql);
+    lin += (r_zeta, qr);
+    lin += (l_zeta * r_zeta, qm);
+    lin += (o_zeta, qo);
+    lin += qk;
+    lin += (s3_coeff, s3);
+    lin += (z_coeff, &z);
+    lin += (-zh, &h[0]);
+    lin += (-zh * zeta_n_plus_two, &h[1]);
+    lin += (-zh * zeta_n_plus_two.square(), &h[2]);
+
+    let folded = [&lin, l, r, o, s1, s2, &qcp];
+    let digests: Vec<G1Affine<H>> = folded.iter().map(|poly| setup.commit(poly)).collect();
+    let values: Vec<Fr> = folded.iter().map(|poly| poly.evaluate(&zeta)).collect();
+    let fold = fold_challenge(&zeta, &digests, &values, &zu);
+    let mut batched = Poly::zero();
+    let mut power = Fr::one();
+    for poly in folded {
+        batched += (power, poly);
+        power *= fold;
+    }
+
+    let proof = gnark::Proof {
+        lro,
+        z: z_cm,
+        h: h_cm,
+        bsb22_commitments: vec![bsb22_commitment],
+        claimed_values: values[1..].to_vec(),
+        batched_proof: setup.open(&batched, zeta),
+        z_shifted_value: zu,
+        z_shifted_proof: setup.open(&z, zeta_shifted),
+    };
+    (vk, proof, vec![x])
+}
+
+fn invalid_point<H: CurveHooks>() -> G1Affine<H> {
+    G1Affine::<H>::new_unchecked(Fq::one(), Fq::one())
+}
+
+#[test]
+fn snarkjs_verify_works() {
+    let (vk, proof, inputs) = snarkjs_prove::<NativeHooks>(0);
+    assert_eq!(snarkjs::verify(&vk, &proof, &inputs), Ok(true));
+}
+
+#[test]
+fn snarkjs_verify_rejects_wrong_proofs() {
+    let (vk, proof, inputs) = snarkjs_prove::<NativeHooks>(1);
+    let wrong_input = [inputs[0] + Fr::one()];
+    assert_eq!(snarkjs::verify(&vk, &proof, &wrong_input), Ok(false));
+
+    let wrong_eval = snarkjs::Proof {
+        eval_zw: proof.eval_zw + Fr::one(),
+        ..proof
+    };
+    assert_eq!(snarkjs::verify(&vk, &wrong_eval, &inputs), Ok(false));
+    let wrong_commitment = snarkjs::Proof {
+        t2: proof.t1,
+        ..proof
+    };
+    assert_eq!(snarkjs::verify(&vk, &wrong_commitment, &inputs), Ok(false));
+    let swapped_openings = snarkjs::Proof {
+        wxi: proof.wxiw,
+        wxiw: proof.wxi,
+        ..proof
+    };
+    assert_eq!(snarkjs::verify(&vk, &swapped_openings, &inputs), Ok(false));
+}
+
+#[test]
+fn snarkjs_verify_rejects_malformed_inputs() {
+    let (vk, proof, inputs) = snarkjs_prove::<NativeHooks>(2);
+    assert_eq!(
+        snarkjs::verify(&vk, &proof, &[]),
+        Err(Error::InvalidPublicInputsLength {
+            expected: 1,
+            found: 0
+        })
+    );
+
+    let wrong_power = snarkjs::VerifyingKey {
+        power: vk.power - 1,
+        ..vk.clone()
+    };
+    assert_eq!(
+        snarkjs::verify(&wrong_power, &proof, &inputs),
+        Err(Error::MalformedVerifyingKey)
+    );
+    // `w^2` satisfies `w^n = 1` but only generates half of the domain.
+    let wrong_w = snarkjs::VerifyingKey {
+        w: vk.w.square(),
+        ..vk.clone()
+    };
+    assert_eq!(
+        snarkjs::verify(&wrong_w, &proof, &inputs),
+        Err(Error::MalformedVerifyingKey)
+    );
+    let huge_power = snarkjs::VerifyingKey {
+        power: 64,
+        ..vk.clone()
+    };
+    assert_eq!(
+        snarkjs::verify(&huge_power, &proof, &inputs),
+        Err(Error::MalformedVerifyingKey)
+    );
+    let invalid_selector = snarkjs::VerifyingKey {
+        qm: invalid_point(),
+        ..vk.clone()
+    };
+    assert_eq!(
+        snarkjs::verify(&invalid_selector, &proof, &inputs),
+        Err(Error::MalformedVerifyingKey)
+    );
+
+    let invalid_point = snarkjs::Proof {
+        z: invalid_point(),
+        ..proof
+    };
+    assert_eq!(
+        snarkjs::verify(&vk, &invalid_point, &inputs),
+        Err(Error::MalformedProof)
+    );
+}
+
+#[test]
+fn gnark_verify_works() {
+    let (vk, proof, inputs) = gnark_prove::<NativeHooks>(0);
+    assert_eq!(gnark::verify(&vk, &proof, &inputs), Ok(true));
+}
+
+#[test]
+fn gnark_verify_rejects_wrong_proofs() {
+    let (vk, proof, inputs) = gnark_prove::<NativeHooks>(1);
+    let wrong_input = [inputs[0] + Fr::one()];
+    assert_eq!(gnark::verify(&vk, &proof, &wrong_input), Ok(false));
+
+    let mut wrong_value = proof.clone();
+    wrong_value.claimed_values[5] += Fr::one();
+    assert_eq!(gnark::verify(&vk, &wrong_value, &inputs), Ok(false));
+    let wrong_shifted_value = gnark::Proof {
+        z_shifted_value: proof.z_shifted_value + Fr::one(),
+        ..proof.clone()
+    };
+    assert_eq!(gnark::verify(&vk, &wrong_shifted_value, &inputs), Ok(false));
+
+    // The BSB22 commitment is hashed into the public inputs.
+    let wrong_commitment = gnark::Proof {
+        bsb22_commitments: vec![proof.lro[0]],
+        ..proof.clone()
+    };
+    assert_eq!(gnark::verify(&vk, &wrong_commitment, &inputs), Ok(false));
+    let wrong_index = gnark::VerifyingKey {
+        commitment_constraint_indexes: vec![5],
+        ..vk.clone()
+    };
+    assert_eq!(gnark::verify(&wrong_index, &proof, &inputs), Ok(false));
+}
+
+#[test]
+fn gnark_verify_rejects_malformed_inputs() {
+    let (vk, proof, inputs) = gnark_prove::<NativeHooks>(2);
+    assert_eq!(
+        gnark::verify(&vk, &proof, &[inputs[0], inputs[0]]),
+        Err(Error::InvalidPublicInputsLength {
+            expected: 1,
+            found: 2
+        })
+    );
+
+    let wrong_size = gnark::VerifyingKey {
+        size: 6,
+        ..vk.clone()
+    };
+    assert_eq!(
+        gnark::verify(&wrong_size, &proof, &inputs),
+        Err(Error::MalformedVerifyingKey)
+    );
+    let wrong_generator = gnark::VerifyingKey {
+        generator: vk.generator.square(),
+        ..vk.clone()
+    };
+    assert_eq!(
+        gnark::verify(&wrong_generator, &proof, &inputs),
+        Err(Error::MalformedVerifyingKey)
+    );
+    let missing_index = gnark::VerifyingKey {
+        commitment_constraint_indexes: vec![],
+        ..vk.clone()
+    };
+    assert_eq!(
+        gnark::verify(&missing_index, &proof, &inputs),
+        Err(Error::MalformedVerifyingKey)
+    );
+    let invalid_kzg = gnark::VerifyingKey {
+        kzg: kzg::VerifierKey {
+            g1: G1Affine::zero(),
+            ..vk.kzg
+        },
+        ..vk.clone()
+    };
+    assert_eq!(
+        gnark::verify(&invalid_kzg, &proof, &inputs),
+        Err(Error::MalformedVerifyingKey)
+    );
+
+    let mut missing_value = proof.clone();
+    missing_value.claimed_values.pop();
+    assert_eq!(
+        gnark::verify(&vk, &missing_value, &inputs),
+        Err(Error::MalformedProof)
+    );
+    let invalid_point = gnark::Proof {
+        batched_proof: invalid_point(),
+        ..proof
+    };
+    assert_eq!(
+        gnark::verify(&vk, &invalid_point, &inputs),
+        Err(Error::MalformedProof)
+    );
+}
+
+#[test]
+fn gnark_proofs_are_read_from_bytes() {
+    let (vk, proof, inputs) = gnark_prove::<NativeHooks>(3);
+    for compressed in [false, true] {
+        let bytes = crate::gnark::plonk_proof_to_bytes(&proof, compressed);
+        assert_eq!(
+            crate::gnark::plonk_proof_from_bytes(&bytes),
+            Ok(proof.clone())
+        );
+    }
+
+    let mut solidity = Vec::new();
+    for point in proof.lro.iter().chain(&proof.h) {
+        solidity.extend(g1_to_raw(point));
+    }
+    for value in &proof.claimed_values[..5] {
+        solidity.extend(crate::gnark::fr_to_bytes(value));
+    }
+    solidity.extend(g1_to_raw(&proof.z));
+    solidity.extend(crate::gnark::fr_to_bytes(&proof.z_shifted_value));
+    solidity.extend(g1_to_raw(&proof.batched_proof));
+    solidity.extend(g1_to_raw(&proof.z_shifted_proof));
+    solidity.extend(crate::gnark::fr_to_bytes(&proof.claimed_values[5]));
+    solidity.extend(g1_to_raw(&proof.bsb22_commitments[0]));
+    let read = crate::gnark::plonk_proof_from_solidity_bytes(&solidity).unwrap();
+    assert_eq!(read, proof);
+    assert_eq!(gnark::verify(&vk, &read, &inputs), Ok(true));
+
+    assert_eq!(
+        crate::gnark::plonk_proof_from_solidity_bytes::<NativeHooks>(&solidity[1..]),
+        Err(crate::gnark::Error::InvalidLength)
+    );
+}
+
+#[test]
+fn verify_uses_the_hooks() {
+    type Hooks = MeteredHooks<NativeHooks, ThreadBudget>;
+    let schedule = GasSchedule::EIP_1108;
+
+    // Proving goes through the metered hooks as well.
+    ThreadBudget::reset(u64::MAX);
+    let (vk, proof, inputs) = snarkjs_prove::<Hooks>(4);
+    ThreadBudget::reset(u64::MAX);
+    assert_eq!(snarkjs::verify(&vk, &proof, &inputs), Ok(true));
+    assert_eq!(
+        ThreadBudget::used(),
+        schedule.msm_g1_cost(18) + schedule.msm_g1_cost(2) + schedule.ec_pairing_cost(2)
+    );
+    ThreadBudget::reset(schedule.msm_g1_cost(18));
+    assert_eq!(
+        snarkjs::verify(&vk, &proof, &inputs),
+        Err(Error::Hook(OUT_OF_GAS))
+    );
+
+    ThreadBudget::reset(u64::MAX);
+    let (vk, proof, inputs) = gnark_prove::<Hooks>(4);
+    ThreadBudget::reset(u64::MAX);
+    assert_eq!(gnark::verify(&vk, &proof, &inputs), Ok(true));
+    assert_eq!(
+        ThreadBudget::used(),
+        schedule.msm_g1_cost(11)
+            + schedule.msm_g1_cost(7)
+            + schedule.msm_g1_cost(5)
+            + schedule.msm_g1_cost(2)
+            + schedule.ec_pairing_cost(2)
+    );
+    ThreadBudget::reset(0);
+    assert_eq!(
+        gnark::verify(&vk, &proof, &inputs),
+        Err(Error::Hook(OUT_OF_GAS))
+    );
+}
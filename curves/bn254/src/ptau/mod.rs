@@ -0,0 +1,472 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [snarkjs](https://github.com/iden3/snarkjs) powers of tau (`.ptau`) files.
+//!
+//! A `.ptau` file is a snarkjs binary file: the `ptau` magic, a version and a
+//! list of sections, each being a type, a size and a payload. Field elements are
+//! 32 bytes little-endian in Montgomery form and points are written uncompressed,
+//! with the point at infinity as all zeros.
+//!
+//! [`read`] loads the header and the `tauG1`, `tauG2`, `alphaTauG1`, `betaTauG1`
+//! and `betaG2` sections, checking that every point is on the curve and in the
+//! prime order subgroup. [`verify`] then checks that consecutive elements of each
+//! vector share the same ratio, folding every check with random linear
+//! combinations in two G1 multi scalar multiplications, two G2 ones and a single
+//! five pairs pairing check, all dispatched to the [`CurveHooks`].
+
+use std::io::{self, BufReader, Read, Take};
+
+use ark_ff::{BigInt, Fp256, MontBackend, MontConfig, PrimeField, UniformRand, Zero};
+use ark_models_ext::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr, CurveGroup,
+};
+use ark_std::{rand::Rng, vec::Vec};
+use educe::Educe;
+
+use crate::{
+    Bn254, CurveHooks, Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine, G2Projective, HookError,
+    TryCurveOps, TryPairing,
+};
+
+#[cfg(test)]
+pub(crate) mod tests;
+
+/// Size of an encoded base field element.
+pub const FQ_LEN: usize = 32;
+/// Size of an encoded G1 point.
+pub const G1_LEN: usize = 2 * FQ_LEN;
+/// Size of an encoded G2 point.
+pub const G2_LEN: usize = 4 * FQ_LEN;
+/// Highest power supported by snarkjs.
+pub const MAX_POWER: u32 = 28;
+
+const MAGIC: &[u8; 4] = b"ptau";
+const VERSION: u32 = 1;
+const HEADER_SECTION: u32 = 1;
+const TAU_G1_SECTION: u32 = 2;
+const TAU_G2_SECTION: u32 = 3;
+const ALPHA_TAU_G1_SECTION: u32 = 4;
+const BETA_TAU_G1_SECTION: u32 = 5;
+const BETA_G2_SECTION: u32 = 6;
+
+/// Error returned by the reader and by the verifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// Reading the input failed.
+    Io(io::ErrorKind),
+    /// File doesn't start with the expected magic.
+    InvalidMagic,
+    /// File version is not supported.
+    UnsupportedVersion(u32),
+    /// Section appears more than once.
+    DuplicateSection(u32),
+    /// Required section is missing.
+    MissingSection(u32),
+    /// Section size doesn't match its content.
+    InvalidSectionSize(u32),
+    /// File is not for the BN254 curve.
+    UnsupportedCurve,
+    /// Power is zero or greater than [`MAX_POWER`].
+    InvalidPower(u32),
+    /// Number of points doesn't match the power.
+    InvalidLength,
+    /// Number is not a canonical field element.
+    InvalidFieldElement,
+    /// Point is not on the curve.
+    PointNotOnCurve,
+    /// Point is not in the prime order subgroup.
+    PointNotInSubgroup,
+    /// Failure reported by the hooks.
+    Hook(HookError),
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::Io(kind) => write!(f, "read failure: {kind}"),
+            Error::InvalidMagic => write!(f, "invalid magic"),
+            Error::UnsupportedVersion(version) => write!(f, "unsupported version {version}"),
+            Error::DuplicateSection(section) => write!(f, "duplicate section {section}"),
+            Error::MissingSection(section) => write!(f, "missing section {section}"),
+            Error::InvalidSectionSize(section) => write!(f, "invalid size of section {section}"),
+            Error::UnsupportedCurve => write!(f, "unsupported curve"),
+            Error::InvalidPower(power) => write!(f, "invalid power {power}"),
+            Error::InvalidLength => write!(f, "number of points doesn't match the power"),
+            Error::InvalidFieldElement => write!(f, "invalid field element"),
+            Error::PointNotOnCurve => write!(f, "point not on curve"),
+            Error::PointNotInSubgroup => write!(f, "point not in subgroup"),
+            Error::Hook(err) => write!(f, "hook failure: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<HookError> for Error {
+    fn from(err: HookError) -> Self {
+        Error::Hook(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err.kind())
+    }
+}
+
+/// Powers of tau of a snarkjs ceremony, with `N = 2^power`.
+#[derive(Educe)]
+#[educe(Clone, Debug, PartialEq, Eq)]
+pub struct Ptau<H: CurveHooks> {
+    /// Power of the file.
+    pub power: u32,
+    /// Power of the ceremony the file was extracted from.
+    pub ceremony_power: u32,
+    /// `tau^i * g1` for `i < 2N - 1`.
+    pub tau_g1: Vec<G1Affine<H>>,
+    /// `tau^i * g2` for `i < N`.
+    pub tau_g2: Vec<G2Affine<H>>,
+    /// `alpha * tau^i * g1` for `i < N`.
+    pub alpha_tau_g1: Vec<G1Affine<H>>,
+    /// `beta * tau^i * g1` for `i < N`.
+    pub beta_tau_g1: Vec<G1Affine<H>>,
+    /// `beta * g2`.
+    pub beta_g2: G2Affine<H>,
+}
+
+/// Reader of the sections of a snarkjs binary file.
+pub(crate) struct BinFile<R> {
+    reader: BufReader<R>,
+    sections: u32,
+}
+
+impl<R: Read> BinFile<R> {
+    /// Reads the file header.
+    pub(crate) fn new(reader: R, magic: &[u8; 4], version: u32) -> Result<Self, Error> {
+        let mut reader = BufReader::new(reader);
+        let mut header = [0; 4];
+        reader.read_exact(&mut header)?;
+        if &header != magic {
+            return Err(Error::InvalidMagic);
+        }
+        let found = read_u32(&mut reader)?;
+        if found != version {
+            return Err(Error::UnsupportedVersion(found));
+        }
+        let sections = read_u32(&mut reader)?;
+        Ok(Self { reader, sections })
+    }
+
+    /// Returns the type and the reader of the next section, if any.
+    pub(crate) fn next_section(&mut self) -> Result<Option<Section<'_, R>>, Error> {
+        if self.sections == 0 {
+            return Ok(None);
+        }
+        self.sections -= 1;
+        let id = read_u32(&mut self.reader)?;
+        let mut size = [0; 8];
+        self.reader.read_exact(&mut size)?;
+        let reader = (&mut self.reader).take(u64::from_le_bytes(size));
+        Ok(Some(Section { id, reader }))
+    }
+}
+
+/// Payload of a section.
+pub(crate) struct Section<'a, R> {
+    pub(crate) id: u32,
+    reader: Take<&'a mut BufReader<R>>,
+}
+
+impl<R: Read> Section<'_, R> {
+    /// Size of the payload left to read.
+    pub(crate) fn remaining(&self) -> u64 {
+        self.reader.limit()
+    }
+
+    /// Checks that the payload left is exactly `len` bytes long.
+    pub(crate) fn expect_remaining(&self, len: u64) -> Result<(), Error> {
+        if self.remaining() != len {
+            return Err(Error::InvalidSectionSize(self.id));
+        }
+        Ok(())
+    }
+
+    /// Discards the rest of the payload.
+    pub(crate) fn skip(&mut self) -> Result<(), Error> {
+        io::copy(&mut self.reader, &mut io::sink())?;
+        match self.remaining() {
+            0 => Ok(()),
+            _ => Err(Error::Io(io::ErrorKind::UnexpectedEof)),
+        }
+    }
+
+    /// Checks that the whole payload has been read.
+    pub(crate) fn finish(self) -> Result<(), Error> {
+        self.expect_remaining(0)
+    }
+
+    pub(crate) fn bytes<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        if self.remaining() < N as u64 {
+            return Err(Error::InvalidSectionSize(self.id));
+        }
+        let mut bytes = [0; N];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Reads `len` bytes. Memory grows with the bytes actually read, so lengths
+    /// taken from the file cannot force large allocations.
+    #[cfg_attr(not(feature = "zkey"), allow(dead_code))]
+    pub(crate) fn data(&mut self, len: usize) -> Result<Vec<u8>, Error> {
+        if self.remaining() < len as u64 {
+            return Err(Error::InvalidSectionSize(self.id));
+        }
+        let mut data = Vec::new();
+        (&mut self.reader).take(len as u64).read_to_end(&mut data)?;
+        if data.len() != len {
+            return Err(Error::Io(io::ErrorKind::UnexpectedEof));
+        }
+        Ok(data)
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, Error> {
+        self.bytes().map(u32::from_le_bytes)
+    }
+
+    /// Reads the size of the prime and the prime itself, which must be `F`'s
+    /// modulus.
+    pub(crate) fn prime<F: PrimeField<BigInt = BigInt<4>>>(&mut self) -> Result<(), Error> {
+        if self.u32()? as usize != FQ_LEN {
+            return Err(Error::UnsupportedCurve);
+        }
+        if limbs(&self.bytes()?) != F::MODULUS {
+            return Err(Error::UnsupportedCurve);
+        }
+        Ok(())
+    }
+
+    /// Reads a field element in Montgomery form, which for both snarkjs and
+    /// arkworks uses the `2^256` radix.
+    pub(crate) fn field<P: MontConfig<4>>(&mut self) -> Result<Fp256<MontBackend<P, 4>>, Error> {
+        let value = limbs(&self.bytes()?);
+        if value >= P::MODULUS {
+            return Err(Error::InvalidFieldElement);
+        }
+        Ok(Fp256::new_unchecked(value))
+    }
+
+    /// Reads a field element in standard form.
+    #[cfg_attr(not(feature = "zkey"), allow(dead_code))]
+    pub(crate) fn integer<P: MontConfig<4>>(&mut self) -> Result<Fp256<MontBackend<P, 4>>, Error> {
+        Fp256::from_bigint(limbs(&self.bytes()?)).ok_or(Error::InvalidFieldElement)
+    }
+
+    pub(crate) fn g1<H: CurveHooks>(&mut self) -> Result<G1Affine<H>, Error> {
+        let x: Fq = self.field()?;
+        let y: Fq = self.field()?;
+        check_point(x, y)
+    }
+
+    pub(crate) fn g2<H: CurveHooks>(&mut self) -> Result<G2Affine<H>, Error> {
+        let x = Fq2::new(self.field()?, self.field()?);
+        let y = Fq2::new(self.field()?, self.field()?);
+        check_point(x, y)
+    }
+
+    /// Reads the `len` points filling the rest of the payload. As for [`Self::data`],
+    /// the vector is not preallocated.
+    pub(crate) fn g1_vec<H: CurveHooks>(&mut self, len: usize) -> Result<Vec<G1Affine<H>>, Error> {
+        self.expect_remaining((len as u64).saturating_mul(G1_LEN as u64))?;
+        let mut points = Vec::new();
+        for _ in 0..len {
+            points.push(self.g1()?);
+        }
+        Ok(points)
+    }
+
+    /// Reads the `len` points filling the rest of the payload, see [`Self::g1_vec`].
+    pub(crate) fn g2_vec<H: CurveHooks>(&mut self, len: usize) -> Result<Vec<G2Affine<H>>, Error> {
+        self.expect_remaining((len as u64).saturating_mul(G2_LEN as u64))?;
+        let mut points = Vec::new();
+        for _ in 0..len {
+            points.push(self.g2()?);
+        }
+        Ok(points)
+    }
+}
+
+fn read_u32(reader: &mut impl Read) -> Result<u32, Error> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn limbs(bytes: &[u8; FQ_LEN]) -> BigInt<4> {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+        *limb = u64::from_le_bytes(chunk.try_into().expect("chunk is 8 bytes long"));
+    }
+    BigInt(limbs)
+}
+
+fn check_point<P: SWCurveConfig>(x: P::BaseField, y: P::BaseField) -> Result<Affine<P>, Error> {
+    if x.is_zero() && y.is_zero() {
+        return Ok(Affine::identity());
+    }
+    let point = Affine::new_unchecked(x, y);
+    if !point.is_on_curve() {
+        return Err(Error::PointNotOnCurve);
+    }
+    if !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Error::PointNotInSubgroup);
+    }
+    Ok(point)
+}
+
+/// Reads a `.ptau` file.
+///
+/// The reader is buffered internally. Sections other than the powers, like the
+/// contributions and the Lagrange bases, are skipped.
+pub fn read<H: CurveHooks, R: Read>(reader: R) -> Result<Ptau<H>, Error> {
+    let mut file = BinFile::new(reader, MAGIC, VERSION)?;
+    let mut header = None;
+    let mut tau_g1 = None;
+    let mut tau_g2 = None;
+    let mut alpha_tau_g1 = None;
+    let mut beta_tau_g1 = None;
+    let mut beta_g2 = None;
+    while let Some(mut section) = file.next_section()? {
+        // Powers sections follow the header in the files written by snarkjs.
+        let n = header.map(|(power, _)| 1usize << power);
+        let duplicate = match (section.id, n) {
+            (HEADER_SECTION, _) => {
+                section.prime::<Fq>()?;
+                let power = section.u32()?;
+                if power == 0 || power > MAX_POWER {
+                    return Err(Error::InvalidPower(power));
+                }
+                // Files predating the ceremony power end right after the power.
+                let ceremony_power = match section.remaining() {
+                    0 => power,
+                    _ => section.u32()?,
+                };
+                header.replace((power, ceremony_power)).is_some()
+            }
+            (TAU_G1_SECTION, Some(n)) => tau_g1.replace(section.g1_vec(2 * n - 1)?).is_some(),
+            (TAU_G2_SECTION, Some(n)) => tau_g2.replace(section.g2_vec(n)?).is_some(),
+            (ALPHA_TAU_G1_SECTION, Some(n)) => alpha_tau_g1.replace(section.g1_vec(n)?).is_some(),
+            (BETA_TAU_G1_SECTION, Some(n)) => beta_tau_g1.replace(section.g1_vec(n)?).is_some(),
+            (BETA_G2_SECTION, Some(_)) => beta_g2.replace(section.g2_vec(1)?[0]).is_some(),
+            (TAU_G1_SECTION..=BETA_G2_SECTION, None) => {
+                return Err(Error::MissingSection(HEADER_SECTION))
+            }
+            _ => {
+                section.skip()?;
+                false
+            }
+        };
+        if duplicate {
+            return Err(Error::DuplicateSection(section.id));
+        }
+        section.finish()?;
+    }
+    let (power, ceremony_power) = header.ok_or(Error::MissingSection(HEADER_SECTION))?;
+    Ok(Ptau {
+        power,
+        ceremony_power,
+        tau_g1: tau_g1.ok_or(Error::MissingSection(TAU_G1_SECTION))?,
+        tau_g2: tau_g2.ok_or(Error::MissingSection(TAU_G2_SECTION))?,
+        alpha_tau_g1: alpha_tau_g1.ok_or(Error::MissingSection(ALPHA_TAU_G1_SECTION))?,
+        beta_tau_g1: beta_tau_g1.ok_or(Error::MissingSection(BETA_TAU_G1_SECTION))?,
+        beta_g2: beta_g2.ok_or(Error::MissingSection(BETA_G2_SECTION))?,
+    })
+}
+
+/// Checks that the powers are consistent.
+///
+/// With `r_i` random scalars drawn from `rng`, the checks
+/// * `e(sum_i r_i * tau^(i + 1) * P, g2) = e(sum_i r_i * tau^i * P, tau * g2)` for
+///   the `tauG1`, `alphaTauG1` and `betaTauG1` vectors, sharing the two sums;
+/// * `e(g1, sum_i r_i * tau^(i + 1) * g2) = e(tau * g1, sum_i r_i * tau^i * g2)`;
+/// * `e(beta * g1, g2) = e(g1, beta * g2)`;
+///
+/// are folded in a single pairing check. The vectors must start from the
+/// generators and the points are assumed valid, as returned by [`read`].
+pub fn verify<H: CurveHooks, R: Rng>(ptau: &Ptau<H>, rng: &mut R) -> Result<bool, Error> {
+    let n = 1usize
+        .checked_shl(ptau.power)
+        .filter(|_| ptau.power > 0)
+        .ok_or(Error::InvalidPower(ptau.power))?;
+    if ptau.tau_g1.len() != 2 * n - 1
+        || ptau.tau_g2.len() != n
+        || ptau.alpha_tau_g1.len() != n
+        || ptau.beta_tau_g1.len() != n
+    {
+        return Err(Error::InvalidLength);
+    }
+    let (g1, g2) = (G1Affine::<H>::generator(), G2Affine::<H>::generator());
+    let (tau_g1, tau_g2) = (ptau.tau_g1[1], ptau.tau_g2[1]);
+    if ptau.tau_g1[0] != g1
+        || ptau.tau_g2[0] != g2
+        || tau_g1.is_zero()
+        || tau_g1 == g1
+        || tau_g2.is_zero()
+        || ptau.alpha_tau_g1[0].is_zero()
+        || ptau.beta_tau_g1[0].is_zero()
+        || ptau.beta_g2.is_zero()
+    {
+        return Ok(false);
+    }
+
+    // The G1 vectors share the same ratio, so their consecutive pairs are folded
+    // together with a single vector of random scalars.
+    let g1_pairs = [&ptau.tau_g1, &ptau.alpha_tau_g1, &ptau.beta_tau_g1];
+    let (mut low, mut high) = (Vec::new(), Vec::new());
+    for powers in g1_pairs {
+        low.extend_from_slice(&powers[..powers.len() - 1]);
+        high.extend_from_slice(&powers[1..]);
+    }
+    let scalars: Vec<Fr> = (0..low.len()).map(|_| Fr::rand(rng)).collect();
+    let low_g1 = G1Projective::<H>::try_msm(&low, &scalars)?;
+    let high_g1 = G1Projective::<H>::try_msm(&high, &scalars)?;
+
+    let scalars: Vec<Fr> = (0..n - 1).map(|_| Fr::rand(rng)).collect();
+    let low_g2 = G2Projective::<H>::try_msm(&ptau.tau_g2[..n - 1], &scalars)?;
+    let high_g2 = G2Projective::<H>::try_msm(&ptau.tau_g2[1..], &scalars)?;
+
+    // Independent weights for the G2 and the beta checks.
+    let (s, t) = (Fr::rand(rng), Fr::rand(rng));
+    let g1_s = g1.into_group().try_mul(&s)?;
+    let g2_side = ptau.beta_tau_g1[0].into_group().try_mul(&t)? - high_g1;
+    let g1_t = g1.into_group().try_mul(&t)?;
+    let lhs = G1Projective::<H>::normalize_batch(&[
+        low_g1,
+        g2_side,
+        tau_g1.into_group().try_mul(&s)?,
+        -g1_s,
+        -g1_t,
+    ]);
+    let rhs = [
+        tau_g2,
+        g2,
+        low_g2.into_affine(),
+        high_g2.into_affine(),
+        ptau.beta_g2,
+    ];
+    Ok(Bn254::<H>::pairing_check(lhs, rhs)?)
+}
@@ -0,0 +1,353 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::{
+    metering::{GasSchedule, MeteredHooks, ThreadBudget, OUT_OF_GAS},
+    native::NativeHooks,
+};
+use ark_ff::{One, UniformRand};
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+use hex_literal::hex;
+
+/// The G1 generator `(1, 2)` as written by snarkjs, i.e. `(R, 2R) mod q`.
+const G1_GENERATOR: [u8; G1_LEN] = hex!(
+    "9d0d8fc58d435dd33d0bc7f528eb780a2c4679786fa36e662fdf079ac1770a0e"
+    "3a1b1e8b1b87baa67b168eeb51d6f114588cf2f0de46ddcc5ebe0f3483ef141c"
+);
+
+/// Sections of a binary file, as type and payload.
+pub(crate) type Sections = Vec<(u32, Vec<u8>)>;
+
+pub(crate) fn write_field<P: MontConfig<4>>(
+    value: &Fp256<MontBackend<P, 4>>,
+    output: &mut Vec<u8>,
+) {
+    for limb in value.0 .0 {
+        output.extend(limb.to_le_bytes());
+    }
+}
+
+pub(crate) fn write_g1<H: CurveHooks>(point: &G1Affine<H>, output: &mut Vec<u8>) {
+    let (x, y) = point.xy().unwrap_or_default();
+    write_field(&x, output);
+    write_field(&y, output);
+}
+
+pub(crate) fn write_g2<H: CurveHooks>(point: &G2Affine<H>, output: &mut Vec<u8>) {
+    let (x, y) = point.xy().unwrap_or_default();
+    for value in [x.c0, x.c1, y.c0, y.c1] {
+        write_field(&value, output);
+    }
+}
+
+/// Writes a snarkjs binary file with the given sections.
+pub(crate) fn write_bin_file(
+    magic: &[u8; 4],
+    version: u32,
+    sections: &[(u32, Vec<u8>)],
+) -> Vec<u8> {
+    let mut output = magic.to_vec();
+    output.extend(version.to_le_bytes());
+    output.extend((sections.len() as u32).to_le_bytes());
+    for (id, payload) in sections {
+        output.extend(id.to_le_bytes());
+        output.extend((payload.len() as u64).to_le_bytes());
+        output.extend(payload);
+    }
+    output
+}
+
+pub(crate) fn write_prime<F: PrimeField<BigInt = BigInt<4>>>(output: &mut Vec<u8>) {
+    output.extend((FQ_LEN as u32).to_le_bytes());
+    for limb in F::MODULUS.0 {
+        output.extend(limb.to_le_bytes());
+    }
+}
+
+/// Powers of tau for the given secrets.
+pub(crate) fn setup_ptau<H: CurveHooks>(power: u32, tau: Fr, alpha: Fr, beta: Fr) -> Ptau<H> {
+    let n = 1usize << power;
+    let mut powers = Vec::with_capacity(2 * n - 1);
+    let mut acc = Fr::one();
+    for _ in 0..2 * n - 1 {
+        powers.push(acc);
+        acc *= tau;
+    }
+    let g1 = |scalars: &mut dyn Iterator<Item = Fr>| {
+        let points: Vec<_> = scalars.map(|s| G1Affine::<H>::generator() * s).collect();
+        G1Projective::<H>::normalize_batch(&points)
+    };
+    let g2 = |scalars: &mut dyn Iterator<Item = Fr>| {
+        let points: Vec<_> = scalars.map(|s| G2Affine::<H>::generator() * s).collect();
+        G2Projective::<H>::normalize_batch(&points)
+    };
+    Ptau {
+        power,
+        ceremony_power: power,
+        tau_g1: g1(&mut powers.iter().copied()),
+        tau_g2: g2(&mut powers[..n].iter().copied()),
+        alpha_tau_g1: g1(&mut powers[..n].iter().map(|p| alpha * p)),
+        beta_tau_g1: g1(&mut powers[..n].iter().map(|p| beta * p)),
+        beta_g2: (G2Affine::<H>::generator() * beta).into_affine(),
+    }
+}
+
+pub(crate) fn random_ptau<H: CurveHooks>(power: u32, seed: u64) -> Ptau<H> {
+    let rng = &mut StdRng::seed_from_u64(seed);
+    setup_ptau(power, Fr::rand(rng), Fr::rand(rng), Fr::rand(rng))
+}
+
+fn ptau_sections<H: CurveHooks>(ptau: &Ptau<H>) -> Sections {
+    let mut header = Vec::new();
+    write_prime::<Fq>(&mut header);
+    header.extend(ptau.power.to_le_bytes());
+    header.extend(ptau.ceremony_power.to_le_bytes());
+    let g1 = |points: &[G1Affine<H>]| {
+        let mut output = Vec::new();
+        points.iter().for_each(|point| write_g1(point, &mut output));
+        output
+    };
+    let g2 = |points: &[G2Affine<H>]| {
+        let mut output = Vec::new();
+        points.iter().for_each(|point| write_g2(point, &mut output));
+        output
+    };
+    vec![
+        (HEADER_SECTION, header),
+        (TAU_G1_SECTION, g1(&ptau.tau_g1)),
+        (TAU_G2_SECTION, g2(&ptau.tau_g2)),
+        (ALPHA_TAU_G1_SECTION, g1(&ptau.alpha_tau_g1)),
+        (BETA_TAU_G1_SECTION, g1(&ptau.beta_tau_g1)),
+        (BETA_G2_SECTION, g2(&[ptau.beta_g2])),
+        // Contributions, skipped by the reader.
+        (7, vec![0; 4]),
+    ]
+}
+
+pub(crate) fn write_ptau<H: CurveHooks>(ptau: &Ptau<H>) -> Vec<u8> {
+    write_bin_file(MAGIC, VERSION, &ptau_sections(ptau))
+}
+
+#[test]
+fn points_are_read_in_montgomery_form() {
+    let file = write_bin_file(MAGIC, VERSION, &[(7, G1_GENERATOR.to_vec())]);
+    let mut file = BinFile::new(file.as_slice(), MAGIC, VERSION).unwrap();
+    let mut section = file.next_section().unwrap().unwrap();
+    assert_eq!(
+        section.g1::<NativeHooks>(),
+        Ok(G1Affine::<NativeHooks>::generator())
+    );
+    assert_eq!(section.finish(), Ok(()));
+
+    let mut output = Vec::new();
+    write_g1(&G1Affine::<NativeHooks>::generator(), &mut output);
+    assert_eq!(output, G1_GENERATOR);
+}
+
+#[test]
+fn ptau_files_are_read_and_verified() {
+    let rng = &mut StdRng::seed_from_u64(1);
+    let ptau = random_ptau::<NativeHooks>(3, 0);
+    assert_eq!(ptau.tau_g1.len(), 15);
+
+    let bytes = write_ptau(&ptau);
+    assert_eq!(read(bytes.as_slice()), Ok(ptau.clone()));
+    assert_eq!(verify(&ptau, rng), Ok(true));
+
+    // Files predating the ceremony power.
+    let mut sections = ptau_sections(&ptau);
+    sections[0].1.truncate(40);
+    sections.swap(1, 6);
+    let file = write_bin_file(MAGIC, VERSION, &sections);
+    assert_eq!(read(file.as_slice()), Ok(ptau));
+}
+
+#[test]
+fn inconsistent_powers_are_rejected() {
+    let rng = &mut StdRng::seed_from_u64(1);
+    let ptau = random_ptau::<NativeHooks>(3, 0);
+    let other = random_ptau::<NativeHooks>(3, 1);
+
+    let mut wrong = ptau.clone();
+    wrong.tau_g1[13] = other.tau_g1[13];
+    assert_eq!(verify(&wrong, rng), Ok(false));
+
+    let mut wrong = ptau.clone();
+    wrong.tau_g2[5] = other.tau_g2[5];
+    assert_eq!(verify(&wrong, rng), Ok(false));
+
+    let mut wrong = ptau.clone();
+    wrong.alpha_tau_g1[2] = other.alpha_tau_g1[2];
+    assert_eq!(verify(&wrong, rng), Ok(false));
+
+    let mut wrong = ptau.clone();
+    wrong.beta_tau_g1 = other.beta_tau_g1.clone();
+    assert_eq!(verify(&wrong, rng), Ok(false));
+
+    let mut wrong = ptau.clone();
+    wrong.beta_g2 = other.beta_g2;
+    assert_eq!(verify(&wrong, rng), Ok(false));
+
+    // Same ratio, but not starting from the generator.
+    let mut wrong = ptau.clone();
+    wrong.tau_g1 = ptau.alpha_tau_g1.clone();
+    wrong.tau_g1.extend_from_slice(&ptau.tau_g1[8..]);
+    assert_eq!(verify(&wrong, rng), Ok(false));
+
+    // Trivial tau.
+    let trivial = setup_ptau::<NativeHooks>(1, Fr::one(), Fr::one(), Fr::one());
+    assert_eq!(verify(&trivial, rng), Ok(false));
+
+    let mut wrong = ptau;
+    wrong.alpha_tau_g1.pop();
+    assert_eq!(verify(&wrong, rng), Err(Error::InvalidLength));
+    wrong.power = 0;
+    assert_eq!(verify(&wrong, rng), Err(Error::InvalidPower(0)));
+}
+
+#[test]
+fn malformed_files_are_rejected() {
+    let ptau = random_ptau::<NativeHooks>(1, 0);
+    let sections = ptau_sections(&ptau);
+    let read = |file: &[u8]| read::<NativeHooks, _>(file);
+    let with_sections = |edit: &dyn Fn(&mut Sections)| {
+        let mut sections = sections.clone();
+        edit(&mut sections);
+        write_bin_file(MAGIC, VERSION, &sections)
+    };
+
+    assert_eq!(
+        read(&write_bin_file(b"zkey", VERSION, &sections)),
+        Err(Error::InvalidMagic)
+    );
+    assert_eq!(
+        read(&write_bin_file(MAGIC, 2, &sections)),
+        Err(Error::UnsupportedVersion(2))
+    );
+    let file = write_ptau(&ptau);
+    assert_eq!(
+        read(&file[..file.len() - 1]),
+        Err(Error::Io(io::ErrorKind::UnexpectedEof))
+    );
+    assert_eq!(
+        read(&with_sections(&|s| {
+            s.remove(2);
+        })),
+        Err(Error::MissingSection(TAU_G2_SECTION))
+    );
+    assert_eq!(
+        read(&with_sections(&|s| s.swap(0, 1))),
+        Err(Error::MissingSection(HEADER_SECTION))
+    );
+    assert_eq!(
+        read(&with_sections(&|s| s.push(s[5].clone()))),
+        Err(Error::DuplicateSection(BETA_G2_SECTION))
+    );
+    assert_eq!(
+        read(&with_sections(&|s| s[2].1.truncate(G2_LEN))),
+        Err(Error::InvalidSectionSize(TAU_G2_SECTION))
+    );
+    assert_eq!(
+        read(&with_sections(&|s| s[0].1.push(0))),
+        Err(Error::InvalidSectionSize(HEADER_SECTION))
+    );
+    assert_eq!(
+        read(&with_sections(&|s| s[0].1[4] ^= 1)),
+        Err(Error::UnsupportedCurve)
+    );
+    assert_eq!(
+        read(&with_sections(&|s| s[0].1[36..40].fill(0))),
+        Err(Error::InvalidPower(0))
+    );
+    assert_eq!(
+        read(&with_sections(&|s| s[1].1[G1_LEN] ^= 1)),
+        Err(Error::PointNotOnCurve)
+    );
+    assert_eq!(
+        read(&with_sections(&|s| s[4].1[..FQ_LEN].fill(0xff))),
+        Err(Error::InvalidFieldElement)
+    );
+    // A point of the twist outside the prime order subgroup.
+    let rng = &mut StdRng::seed_from_u64(2);
+    let off_subgroup = loop {
+        let x = Fq2::rand(rng);
+        if let Some(point) = G2Affine::<NativeHooks>::get_point_from_x_unchecked(x, false) {
+            break point;
+        }
+    };
+    assert_eq!(
+        read(&with_sections(&|s| {
+            s[5].1.clear();
+            write_g2(&off_subgroup, &mut s[5].1);
+        })),
+        Err(Error::PointNotInSubgroup)
+    );
+}
+
+/// Appends a section claiming `size` bytes, of which only `payload` is written.
+fn write_truncated_section(file: &mut Vec<u8>, id: u32, size: u64, payload: &[u8]) {
+    let sections = u32::from_le_bytes(file[8..12].try_into().unwrap());
+    file[8..12].copy_from_slice(&(sections + 1).to_le_bytes());
+    file.extend(id.to_le_bytes());
+    file.extend(size.to_le_bytes());
+    file.extend(payload);
+}
+
+#[test]
+fn section_sizes_do_not_drive_allocations() {
+    let mut file = write_bin_file(MAGIC, VERSION, &[]);
+    write_truncated_section(&mut file, TAU_G1_SECTION, u64::MAX, &[0; 8]);
+    let mut reader = BinFile::new(file.as_slice(), MAGIC, VERSION).unwrap();
+    let mut section = reader.next_section().unwrap().unwrap();
+    assert_eq!(
+        section.data(usize::MAX / 2).map(|data| data.len()),
+        Err(Error::Io(io::ErrorKind::UnexpectedEof))
+    );
+
+    // A maximal power with truncated points.
+    let mut header = Vec::new();
+    write_prime::<Fq>(&mut header);
+    header.extend(MAX_POWER.to_le_bytes());
+    let mut file = write_bin_file(MAGIC, VERSION, &[(HEADER_SECTION, header)]);
+    let size = ((2u64 << MAX_POWER) - 1) * G1_LEN as u64;
+    write_truncated_section(&mut file, TAU_G1_SECTION, size, &G1_GENERATOR);
+    assert_eq!(
+        read::<NativeHooks, _>(file.as_slice()),
+        Err(Error::Io(io::ErrorKind::UnexpectedEof))
+    );
+}
+
+#[test]
+fn verify_uses_the_hooks() {
+    type Hooks = MeteredHooks<NativeHooks, ThreadBudget>;
+    let schedule = GasSchedule::EIP_1108;
+    let rng = &mut StdRng::seed_from_u64(1);
+    ThreadBudget::reset(u64::MAX);
+    let ptau = random_ptau::<Hooks>(3, 0);
+
+    // Shared G1 sums over `tauG1`, `alphaTauG1` and `betaTauG1`, the G2 sums, the
+    // checks weights and the final five pairs check.
+    let expected = 2 * schedule.msm_g1_cost(14 + 7 + 7)
+        + 2 * schedule.msm_g2_cost(7)
+        + 4 * schedule.ec_mul_cost()
+        + schedule.ec_pairing_cost(5);
+    ThreadBudget::reset(u64::MAX);
+    assert_eq!(verify(&ptau, rng), Ok(true));
+    assert_eq!(ThreadBudget::used(), expected);
+
+    ThreadBudget::reset(expected - 1);
+    assert_eq!(verify(&ptau, rng), Err(Error::Hook(OUT_OF_GAS)));
+}
@@ -0,0 +1,579 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [snarkjs](https://github.com/iden3/snarkjs) / circom JSON format.
+//!
+//! Imports and exports the `verification_key.json`, `proof.json` and `public.json`
+//! files produced by snarkjs for the Groth16, PLONK and fflonk protocols.
+//!
+//! Field elements are decimal strings, points are projective triples whose `z` is
+//! either one or, for the point at infinity, zero and `Fq2` elements are `[c0, c1]`
+//! arrays. Import is strict: field elements must be canonical, without leading zeros,
+//! and points must be on the curve and in the prime order subgroup.
+//!
+//! Export produces the very same layout written by snarkjs.
+
+use ark_ff::{BigInt, FftField, Field, One, PrimeField, Zero};
+use ark_models_ext::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr,
+};
+use ark_std::{
+    str::FromStr,
+    string::{String, ToString},
+    vec::Vec,
+};
+use serde_json::{json, Value};
+
+use crate::{
+    fflonk, groth16, Bn254, CurveHooks, Fq, Fq12, Fq2, Fr, G1Affine, G2Affine, HookError,
+    TryPairing,
+};
+
+pub use crate::{
+    fflonk::{Proof as FflonkProof, VerifyingKey as FflonkVerifyingKey},
+    plonk::snarkjs::{Proof as PlonkProof, VerifyingKey as PlonkVerifyingKey},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Error returned by the parser.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// Input is not valid JSON.
+    Json(String),
+    /// Required field is missing.
+    MissingField(&'static str),
+    /// Field has an unexpected value or shape.
+    InvalidField(&'static str),
+    /// Number is not a canonical field element.
+    InvalidFieldElement,
+    /// Point is not on the curve.
+    PointNotOnCurve,
+    /// Point is not in the prime order subgroup.
+    PointNotInSubgroup,
+    /// Failure reported by the hooks.
+    Hook(HookError),
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::Json(err) => write!(f, "invalid json: {err}"),
+            Error::MissingField(name) => write!(f, "missing field `{name}`"),
+            Error::InvalidField(name) => write!(f, "invalid field `{name}`"),
+            Error::InvalidFieldElement => write!(f, "invalid field element"),
+            Error::PointNotOnCurve => write!(f, "point not on curve"),
+            Error::PointNotInSubgroup => write!(f, "point not in subgroup"),
+            Error::Hook(err) => write!(f, "hook failure: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<HookError> for Error {
+    fn from(err: HookError) -> Self {
+        Error::Hook(err)
+    }
+}
+
+/// Parses a Groth16 `verification_key.json`.
+///
+/// The `vk_alphabeta_12` field, if present, must match `e(alpha, beta)`.
+pub fn groth16_vk_from_json<H: CurveHooks>(json: &str) -> Result<groth16::VerifyingKey<H>, Error> {
+    let value = parse_json(json)?;
+    check_header(&value, "groth16")?;
+    let vk = groth16::VerifyingKey {
+        alpha_g1: parse_g1(get(&value, "vk_alpha_1")?)?,
+        beta_g2: parse_g2(get(&value, "vk_beta_2")?)?,
+        gamma_g2: parse_g2(get(&value, "vk_gamma_2")?)?,
+        delta_g2: parse_g2(get(&value, "vk_delta_2")?)?,
+        gamma_abc_g1: parse_array(get(&value, "IC")?, "IC", parse_g1)?,
+    };
+    if vk.gamma_abc_g1.is_empty() {
+        return Err(Error::InvalidField("IC"));
+    }
+    if parse_usize(get(&value, "nPublic")?, "nPublic")? != vk.num_public_inputs() {
+        return Err(Error::InvalidField("nPublic"));
+    }
+    if let Some(alphabeta) = value.get("vk_alphabeta_12") {
+        if parse_fq12(alphabeta)? != alpha_beta(&vk)? {
+            return Err(Error::InvalidField("vk_alphabeta_12"));
+        }
+    }
+    Ok(vk)
+}
+
+/// Exports a Groth16 verifying key as `verification_key.json`.
+///
+/// Computes `vk_alphabeta_12` through the pairing hooks.
+pub fn groth16_vk_to_json<H: CurveHooks>(vk: &groth16::VerifyingKey<H>) -> Result<String, Error> {
+    let value = json!({
+        "protocol": "groth16",
+        "curve": CURVE,
+        "nPublic": vk.num_public_inputs(),
+        "vk_alpha_1": write_g1(&vk.alpha_g1),
+        "vk_beta_2": write_g2(&vk.beta_g2),
+        "vk_gamma_2": write_g2(&vk.gamma_g2),
+        "vk_delta_2": write_g2(&vk.delta_g2),
+        "vk_alphabeta_12": write_fq12(&alpha_beta(vk)?),
+        "IC": vk.gamma_abc_g1.iter().map(write_g1).collect::<Vec<_>>(),
+    });
+    Ok(to_json(&value))
+}
+
+/// Parses a Groth16 `proof.json`.
+pub fn groth16_proof_from_json<H: CurveHooks>(json: &str) -> Result<groth16::Proof<H>, Error> {
+    let value = parse_json(json)?;
+    check_header(&value, "groth16")?;
+    Ok(groth16::Proof {
+        a: parse_g1(get(&value, "pi_a")?)?,
+        b: parse_g2(get(&value, "pi_b")?)?,
+        c: parse_g1(get(&value, "pi_c")?)?,
+    })
+}
+
+/// Exports a Groth16 proof as `proof.json`.
+pub fn groth16_proof_to_json<H: CurveHooks>(proof: &groth16::Proof<H>) -> String {
+    let value = json!({
+        "pi_a": write_g1(&proof.a),
+        "pi_b": write_g2(&proof.b),
+        "pi_c": write_g1(&proof.c),
+        "protocol": "groth16",
+        "curve": CURVE,
+    });
+    to_json(&value)
+}
+
+/// Parses a PLONK `verification_key.json`.
+pub fn plonk_vk_from_json<H: CurveHooks>(json: &str) -> Result<PlonkVerifyingKey<H>, Error> {
+    let value = parse_json(json)?;
+    check_header(&value, "plonk")?;
+    let (power, w) = parse_domain(&value)?;
+    let vk = PlonkVerifyingKey {
+        n_public: parse_usize(get(&value, "nPublic")?, "nPublic")?,
+        power,
+        k1: parse_fr(get(&value, "k1")?)?,
+        k2: parse_fr(get(&value, "k2")?)?,
+        qm: parse_g1(get(&value, "Qm")?)?,
+        ql: parse_g1(get(&value, "Ql")?)?,
+        qr: parse_g1(get(&value, "Qr")?)?,
+        qo: parse_g1(get(&value, "Qo")?)?,
+        qc: parse_g1(get(&value, "Qc")?)?,
+        s1: parse_g1(get(&value, "S1")?)?,
+        s2: parse_g1(get(&value, "S2")?)?,
+        s3: parse_g1(get(&value, "S3")?)?,
+        x_2: parse_g2(get(&value, "X_2")?)?,
+        w,
+    };
+    Ok(vk)
+}
+
+/// Exports a PLONK verifying key as `verification_key.json`.
+pub fn plonk_vk_to_json<H: CurveHooks>(vk: &PlonkVerifyingKey<H>) -> String {
+    let value = json!({
+        "protocol": "plonk",
+        "curve": CURVE,
+        "nPublic": vk.n_public,
+        "power": vk.power,
+        "k1": vk.k1.to_string(),
+        "k2": vk.k2.to_string(),
+        "Qm": write_g1(&vk.qm),
+        "Ql": write_g1(&vk.ql),
+        "Qr": write_g1(&vk.qr),
+        "Qo": write_g1(&vk.qo),
+        "Qc": write_g1(&vk.qc),
+        "S1": write_g1(&vk.s1),
+        "S2": write_g1(&vk.s2),
+        "S3": write_g1(&vk.s3),
+        "X_2": write_g2(&vk.x_2),
+        "w": vk.w.to_string(),
+    });
+    to_json(&value)
+}
+
+/// Parses a PLONK `proof.json`.
+pub fn plonk_proof_from_json<H: CurveHooks>(json: &str) -> Result<PlonkProof<H>, Error> {
+    let value = parse_json(json)?;
+    check_header(&value, "plonk")?;
+    Ok(PlonkProof {
+        a: parse_g1(get(&value, "A")?)?,
+        b: parse_g1(get(&value, "B")?)?,
+        c: parse_g1(get(&value, "C")?)?,
+        z: parse_g1(get(&value, "Z")?)?,
+        t1: parse_g1(get(&value, "T1")?)?,
+        t2: parse_g1(get(&value, "T2")?)?,
+        t3: parse_g1(get(&value, "T3")?)?,
+        wxi: parse_g1(get(&value, "Wxi")?)?,
+        wxiw: parse_g1(get(&value, "Wxiw")?)?,
+        eval_a: parse_fr(get(&value, "eval_a")?)?,
+        eval_b: parse_fr(get(&value, "eval_b")?)?,
+        eval_c: parse_fr(get(&value, "eval_c")?)?,
+        eval_s1: parse_fr(get(&value, "eval_s1")?)?,
+        eval_s2: parse_fr(get(&value, "eval_s2")?)?,
+        eval_zw: parse_fr(get(&value, "eval_zw")?)?,
+    })
+}
+
+/// Exports a PLONK proof as `proof.json`.
+pub fn plonk_proof_to_json<H: CurveHooks>(proof: &PlonkProof<H>) -> String {
+    let value = json!({
+        "A": write_g1(&proof.a),
+        "B": write_g1(&proof.b),
+        "C": write_g1(&proof.c),
+        "Z": write_g1(&proof.z),
+        "T1": write_g1(&proof.t1),
+        "T2": write_g1(&proof.t2),
+        "T3": write_g1(&proof.t3),
+        "Wxi": write_g1(&proof.wxi),
+        "Wxiw": write_g1(&proof.wxiw),
+        "eval_a": proof.eval_a.to_string(),
+        "eval_b": proof.eval_b.to_string(),
+        "eval_c": proof.eval_c.to_string(),
+        "eval_s1": proof.eval_s1.to_string(),
+        "eval_s2": proof.eval_s2.to_string(),
+        "eval_zw": proof.eval_zw.to_string(),
+        "protocol": "plonk",
+        "curve": CURVE,
+    });
+    to_json(&value)
+}
+
+/// Parses a fflonk `verification_key.json`.
+pub fn fflonk_vk_from_json<H: CurveHooks>(json: &str) -> Result<FflonkVerifyingKey<H>, Error> {
+    let value = parse_json(json)?;
+    check_header(&value, "fflonk")?;
+    let (power, w) = parse_domain(&value)?;
+    Ok(FflonkVerifyingKey {
+        n_public: parse_usize(get(&value, "nPublic")?, "nPublic")?,
+        power,
+        k1: parse_fr(get(&value, "k1")?)?,
+        k2: parse_fr(get(&value, "k2")?)?,
+        w,
+        w3: parse_fr(get(&value, "w3")?)?,
+        w4: parse_fr(get(&value, "w4")?)?,
+        w8: parse_fr(get(&value, "w8")?)?,
+        wr: parse_fr(get(&value, "wr")?)?,
+        x_2: parse_g2(get(&value, "X_2")?)?,
+        c0: parse_g1(get(&value, "C0")?)?,
+    })
+}
+
+/// Exports a fflonk verifying key as `verification_key.json`.
+pub fn fflonk_vk_to_json<H: CurveHooks>(vk: &FflonkVerifyingKey<H>) -> String {
+    let value = json!({
+        "protocol": "fflonk",
+        "curve": CURVE,
+        "nPublic": vk.n_public,
+        "power": vk.power,
+        "k1": vk.k1.to_string(),
+        "k2": vk.k2.to_string(),
+        "w": vk.w.to_string(),
+        "w3": vk.w3.to_string(),
+        "w4": vk.w4.to_string(),
+        "w8": vk.w8.to_string(),
+        "wr": vk.wr.to_string(),
+        "X_2": write_g2(&vk.x_2),
+        "C0": write_g1(&vk.c0),
+    });
+    to_json(&value)
+}
+
+/// Parses a fflonk `proof.json`.
+pub fn fflonk_proof_from_json<H: CurveHooks>(json: &str) -> Result<FflonkProof<H>, Error> {
+    let value = parse_json(json)?;
+    check_header(&value, "fflonk")?;
+    let polynomials = get(&value, "polynomials")?;
+    let evaluations = get(&value, "evaluations")?;
+    let eval = |name| parse_fr(get(evaluations, name)?);
+    Ok(FflonkProof {
+        c1: parse_g1(get(polynomials, "C1")?)?,
+        c2: parse_g1(get(polynomials, "C2")?)?,
+        w1: parse_g1(get(polynomials, "W1")?)?,
+        w2: parse_g1(get(polynomials, "W2")?)?,
+        evaluations: fflonk::Evaluations {
+            ql: eval("ql")?,
+            qr: eval("qr")?,
+            qm: eval("qm")?,
+            qo: eval("qo")?,
+            qc: eval("qc")?,
+            s1: eval("s1")?,
+            s2: eval("s2")?,
+            s3: eval("s3")?,
+            a: eval("a")?,
+            b: eval("b")?,
+            c: eval("c")?,
+            z: eval("z")?,
+            zw: eval("zw")?,
+            t1w: eval("t1w")?,
+            t2w: eval("t2w")?,
+            inv: eval("inv")?,
+        },
+    })
+}
+
+/// Exports a fflonk proof as `proof.json`.
+pub fn fflonk_proof_to_json<H: CurveHooks>(proof: &FflonkProof<H>) -> String {
+    let e = &proof.evaluations;
+    let value = json!({
+        "polynomials": {
+            "C1": write_g1(&proof.c1),
+            "C2": write_g1(&proof.c2),
+            "W1": write_g1(&proof.w1),
+            "W2": write_g1(&proof.w2),
+        },
+        "evaluations": {
+            "ql": e.ql.to_string(),
+            "qr": e.qr.to_string(),
+            "qm": e.qm.to_string(),
+            "qo": e.qo.to_string(),
+            "qc": e.qc.to_string(),
+            "s1": e.s1.to_string(),
+            "s2": e.s2.to_string(),
+            "s3": e.s3.to_string(),
+            "a": e.a.to_string(),
+            "b": e.b.to_string(),
+            "c": e.c.to_string(),
+            "z": e.z.to_string(),
+            "zw": e.zw.to_string(),
+            "t1w": e.t1w.to_string(),
+            "t2w": e.t2w.to_string(),
+            "inv": e.inv.to_string(),
+        },
+        "protocol": "fflonk",
+        "curve": CURVE,
+    });
+    to_json(&value)
+}
+
+/// Parses a `public.json`.
+pub fn public_inputs_from_json(json: &str) -> Result<Vec<Fr>, Error> {
+    parse_array(&parse_json(json)?, "public", parse_fr)
+}
+
+/// Exports the public inputs as `public.json`.
+pub fn public_inputs_to_json(inputs: &[Fr]) -> String {
+    let value = Value::Array(
+        inputs
+            .iter()
+            .map(|input| json!(input.to_string()))
+            .collect(),
+    );
+    to_json(&value)
+}
+
+/// Curve name used by snarkjs.
+const CURVE: &str = "bn128";
+
+fn alpha_beta<H: CurveHooks>(vk: &groth16::VerifyingKey<H>) -> Result<Fq12, Error> {
+    Ok(Bn254::<H>::try_pairing(vk.alpha_g1, vk.beta_g2)?.0)
+}
+
+fn parse_json(json: &str) -> Result<Value, Error> {
+    serde_json::from_str(json).map_err(|err| Error::Json(err.to_string()))
+}
+
+/// Serializes as snarkjs does, i.e. `JSON.stringify(value, null, 1)`.
+fn to_json(value: &Value) -> String {
+    fn write(value: &Value, depth: usize, out: &mut String) {
+        let (open, close, items): (_, _, Vec<(Option<&String>, &Value)>) = match value {
+            Value::Array(items) => ('[', ']', items.iter().map(|item| (None, item)).collect()),
+            Value::Object(items) => ('{', '}', items.iter().map(|(k, v)| (Some(k), v)).collect()),
+            scalar => return out.push_str(&scalar.to_string()),
+        };
+        out.push(open);
+        if !items.is_empty() {
+            for (i, (key, item)) in items.into_iter().enumerate() {
+                out.push_str(if i == 0 { "\n" } else { ",\n" });
+                out.extend(core::iter::repeat_n(' ', depth + 1));
+                if let Some(key) = key {
+                    out.push_str(&Value::from(key.as_str()).to_string());
+                    out.push_str(": ");
+                }
+                write(item, depth + 1, out);
+            }
+            out.push('\n');
+            out.extend(core::iter::repeat_n(' ', depth));
+        }
+        out.push(close);
+    }
+
+    let mut out = String::new();
+    write(value, 0, &mut out);
+    out
+}
+
+fn get<'a>(value: &'a Value, name: &'static str) -> Result<&'a Value, Error> {
+    value.get(name).ok_or(Error::MissingField(name))
+}
+
+fn check_header(value: &Value, protocol: &'static str) -> Result<(), Error> {
+    if get(value, "protocol")?.as_str() != Some(protocol) {
+        return Err(Error::InvalidField("protocol"));
+    }
+    if !matches!(get(value, "curve")?.as_str(), Some("bn128" | "bn254")) {
+        return Err(Error::InvalidField("curve"));
+    }
+    Ok(())
+}
+
+/// Parses `power` and the generator `w` of the domain of size `2^power`.
+fn parse_domain(value: &Value) -> Result<(u32, Fr), Error> {
+    let power = parse_usize(get(value, "power")?, "power")?;
+    let power = u32::try_from(power)
+        .ok()
+        .filter(|power| *power <= Fr::TWO_ADICITY)
+        .ok_or(Error::InvalidField("power"))?;
+    let w = parse_fr(get(value, "w")?)?;
+    let n = 1u64 << power;
+    if !w.pow([n]).is_one() || (n > 1 && w.pow([n / 2]).is_one()) {
+        return Err(Error::InvalidField("w"));
+    }
+    Ok((power, w))
+}
+
+fn parse_usize(value: &Value, name: &'static str) -> Result<usize, Error> {
+    value
+        .as_u64()
+        .and_then(|value| usize::try_from(value).ok())
+        .ok_or(Error::InvalidField(name))
+}
+
+fn parse_array<T>(
+    value: &Value,
+    name: &'static str,
+    parse: impl Fn(&Value) -> Result<T, Error>,
+) -> Result<Vec<T>, Error> {
+    value
+        .as_array()
+        .ok_or(Error::InvalidField(name))?
+        .iter()
+        .map(parse)
+        .collect()
+}
+
+fn parse_tuple<const N: usize>(value: &Value) -> Result<&[Value; N], Error> {
+    value
+        .as_array()
+        .and_then(|items| items.as_slice().try_into().ok())
+        .ok_or(Error::InvalidFieldElement)
+}
+
+/// Parses a canonical decimal string.
+fn parse_prime<F: PrimeField<BigInt = BigInt<4>>>(value: &Value) -> Result<F, Error> {
+    let digits = value.as_str().ok_or(Error::InvalidFieldElement)?;
+    let canonical = !digits.is_empty()
+        && digits.bytes().all(|byte| byte.is_ascii_digit())
+        && (digits == "0" || !digits.starts_with('0'));
+    if !canonical {
+        return Err(Error::InvalidFieldElement);
+    }
+    BigInt::from_str(digits)
+        .ok()
+        .and_then(F::from_bigint)
+        .ok_or(Error::InvalidFieldElement)
+}
+
+fn parse_fr(value: &Value) -> Result<Fr, Error> {
+    parse_prime(value)
+}
+
+fn parse_fq(value: &Value) -> Result<Fq, Error> {
+    parse_prime(value)
+}
+
+fn parse_fq2(value: &Value) -> Result<Fq2, Error> {
+    let [c0, c1] = parse_tuple(value)?;
+    Ok(Fq2::new(parse_fq(c0)?, parse_fq(c1)?))
+}
+
+fn parse_fq12(value: &Value) -> Result<Fq12, Error> {
+    let parse_fq6 = |value: &Value| {
+        let [c0, c1, c2] = parse_tuple(value)?;
+        Ok::<_, Error>(crate::Fq6::new(
+            parse_fq2(c0)?,
+            parse_fq2(c1)?,
+            parse_fq2(c2)?,
+        ))
+    };
+    let [c0, c1] = parse_tuple(value)?;
+    Ok(Fq12::new(parse_fq6(c0)?, parse_fq6(c1)?))
+}
+
+fn parse_point<P: SWCurveConfig>(
+    value: &Value,
+    parse: impl Fn(&Value) -> Result<P::BaseField, Error>,
+) -> Result<Affine<P>, Error> {
+    let [x, y, z] = parse_tuple(value)?;
+    let (x, y, z) = (parse(x)?, parse(y)?, parse(z)?);
+    if z.is_zero() {
+        // Point at infinity is encoded as `[0, 1, 0]`.
+        if !x.is_zero() || !y.is_one() {
+            return Err(Error::PointNotOnCurve);
+        }
+        return Ok(Affine::identity());
+    }
+    if !z.is_one() {
+        return Err(Error::InvalidFieldElement);
+    }
+    let point = Affine::new_unchecked(x, y);
+    if !point.is_on_curve() {
+        return Err(Error::PointNotOnCurve);
+    }
+    if !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Error::PointNotInSubgroup);
+    }
+    Ok(point)
+}
+
+fn parse_g1<H: CurveHooks>(value: &Value) -> Result<G1Affine<H>, Error> {
+    parse_point(value, parse_fq)
+}
+
+fn parse_g2<H: CurveHooks>(value: &Value) -> Result<G2Affine<H>, Error> {
+    parse_point(value, parse_fq2)
+}
+
+fn write_fq2(value: &Fq2) -> Value {
+    json!([value.c0.to_string(), value.c1.to_string()])
+}
+
+fn write_fq12(value: &Fq12) -> Value {
+    let write_fq6 = |value: &crate::Fq6| {
+        json!([
+            write_fq2(&value.c0),
+            write_fq2(&value.c1),
+            write_fq2(&value.c2)
+        ])
+    };
+    json!([write_fq6(&value.c0), write_fq6(&value.c1)])
+}
+
+fn write_g1<H: CurveHooks>(point: &G1Affine<H>) -> Value {
+    match point.xy() {
+        Some((x, y)) => json!([x.to_string(), y.to_string(), "1"]),
+        None => json!(["0", "1", "0"]),
+    }
+}
+
+fn write_g2<H: CurveHooks>(point: &G2Affine<H>) -> Value {
+    match point.xy() {
+        Some((x, y)) => json!([write_fq2(&x), write_fq2(&y), ["1", "0"]]),
+        None => json!([["0", "0"], ["1", "0"], ["0", "0"]]),
+    }
+}
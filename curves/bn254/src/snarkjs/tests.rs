@@ -0,0 +1,265 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::native::NativeHooks;
+use ark_ff::UniformRand;
+use ark_std::test_rng;
+
+type H = NativeHooks;
+
+// Circom Groth16 artifacts produced by snarkjs (from the RISC Zero test suite).
+const GROTH16_VK: &str = include_str!("test-data/groth16/verification_key.json");
+const GROTH16_PROOF: &str = include_str!("test-data/groth16/proof.json");
+const GROTH16_PUBLIC: &str = include_str!("test-data/groth16/public.json");
+
+fn replace_first(json: &str, from: &str, to: &str) -> String {
+    assert!(json.contains(from));
+    json.replacen(from, to, 1)
+}
+
+#[test]
+fn groth16_artifacts_import_and_verify() {
+    let vk = groth16_vk_from_json::<H>(GROTH16_VK).unwrap();
+    let proof = groth16_proof_from_json::<H>(GROTH16_PROOF).unwrap();
+    let inputs = public_inputs_from_json(GROTH16_PUBLIC).unwrap();
+    assert_eq!(inputs, [Fr::from(33u8)]);
+
+    assert_eq!(groth16::verify(&vk, &proof, &inputs), Ok(true));
+    assert_eq!(groth16::verify(&vk, &proof, &[Fr::from(34u8)]), Ok(false));
+}
+
+#[test]
+fn groth16_artifacts_round_trip() {
+    let vk = groth16_vk_from_json::<H>(GROTH16_VK).unwrap();
+    assert_eq!(groth16_vk_to_json(&vk).unwrap(), GROTH16_VK);
+
+    let proof = groth16_proof_from_json::<H>(GROTH16_PROOF).unwrap();
+    assert_eq!(groth16_proof_to_json(&proof), GROTH16_PROOF);
+
+    let inputs = public_inputs_from_json(GROTH16_PUBLIC).unwrap();
+    assert_eq!(public_inputs_to_json(&inputs), GROTH16_PUBLIC);
+}
+
+#[test]
+fn non_canonical_field_elements_are_rejected() {
+    for input in [
+        "[\"033\"]",
+        "[\"+33\"]",
+        "[\"3_3\"]",
+        "[\"\"]",
+        "[33]",
+        // Scalar field modulus.
+        "[\"21888242871839275222246405745257275088548364400416034343698204186575808495617\"]",
+    ] {
+        assert_eq!(
+            public_inputs_from_json(input),
+            Err(Error::InvalidFieldElement),
+            "{input}"
+        );
+    }
+    assert!(matches!(
+        public_inputs_from_json("[\"1\""),
+        Err(Error::Json(_))
+    ));
+
+    // Base field modulus.
+    let json = replace_first(
+        GROTH16_PROOF,
+        "19752044163435112998099796779947263139365269296294968520404327719124263547111",
+        "21888242871839275222246405745257275088696311157297823662689037894645226208583",
+    );
+    assert_eq!(
+        groth16_proof_from_json::<H>(&json),
+        Err(Error::InvalidFieldElement)
+    );
+}
+
+#[test]
+fn invalid_points_are_rejected() {
+    let json = replace_first(
+        GROTH16_PROOF,
+        "19752044163435112998099796779947263139365269296294968520404327719124263547111",
+        "1",
+    );
+    assert_eq!(
+        groth16_proof_from_json::<H>(&json),
+        Err(Error::PointNotOnCurve)
+    );
+
+    // Point on the twist curve but outside the prime order subgroup.
+    let mut rng = test_rng();
+    let point = loop {
+        let x = Fq2::rand(&mut rng);
+        let y2 = x.square() * x + <crate::g2::Config<H> as SWCurveConfig>::COEFF_B;
+        if let Some(y) = y2.sqrt() {
+            break G2Affine::<H>::new_unchecked(x, y);
+        }
+    };
+    let mut value = parse_json(GROTH16_PROOF).unwrap();
+    value["pi_b"] = write_g2(&point);
+    assert_eq!(
+        groth16_proof_from_json::<H>(&to_json(&value)),
+        Err(Error::PointNotInSubgroup)
+    );
+
+    // Only affine or infinity projective triples are accepted.
+    value["pi_b"] = json!([["0", "0"], ["1", "0"], ["0", "0"]]);
+    assert_eq!(
+        groth16_proof_from_json::<H>(&to_json(&value)).unwrap().b,
+        G2Affine::<H>::identity()
+    );
+    value["pi_b"] = json!([["0", "0"], ["1", "0"], ["2", "0"]]);
+    assert_eq!(
+        groth16_proof_from_json::<H>(&to_json(&value)),
+        Err(Error::InvalidFieldElement)
+    );
+}
+
+#[test]
+fn inconsistent_artifacts_are_rejected() {
+    let json = replace_first(GROTH16_PROOF, "\"groth16\"", "\"plonk\"");
+    assert_eq!(
+        groth16_proof_from_json::<H>(&json),
+        Err(Error::InvalidField("protocol"))
+    );
+
+    let json = replace_first(GROTH16_VK, "\"bn128\"", "\"bls12381\"");
+    assert_eq!(
+        groth16_vk_from_json::<H>(&json),
+        Err(Error::InvalidField("curve"))
+    );
+
+    let json = replace_first(GROTH16_VK, "\"nPublic\": 1", "\"nPublic\": 2");
+    assert_eq!(
+        groth16_vk_from_json::<H>(&json),
+        Err(Error::InvalidField("nPublic"))
+    );
+
+    let json = replace_first(GROTH16_VK, "\"vk_delta_2\"", "\"vk_delta\"");
+    assert_eq!(
+        groth16_vk_from_json::<H>(&json),
+        Err(Error::MissingField("vk_delta_2"))
+    );
+
+    let mut value = parse_json(GROTH16_VK).unwrap();
+    value["vk_alphabeta_12"][0][0][0] = json!("1");
+    assert_eq!(
+        groth16_vk_from_json::<H>(&to_json(&value)),
+        Err(Error::InvalidField("vk_alphabeta_12"))
+    );
+    value.as_object_mut().unwrap().remove("vk_alphabeta_12");
+    assert!(groth16_vk_from_json::<H>(&to_json(&value)).is_ok());
+}
+
+fn random_plonk_vk(power: u32) -> PlonkVerifyingKey<H> {
+    let mut rng = test_rng();
+    let mut g1 = || G1Affine::<H>::rand(&mut rng);
+    PlonkVerifyingKey {
+        n_public: 3,
+        power,
+        k1: Fr::from(2u8),
+        k2: Fr::from(3u8),
+        qm: g1(),
+        ql: g1(),
+        qr: g1(),
+        qo: g1(),
+        qc: g1(),
+        s1: g1(),
+        s2: g1(),
+        s3: G1Affine::<H>::identity(),
+        x_2: G2Affine::<H>::rand(&mut test_rng()),
+        w: Fr::get_root_of_unity(1 << power).unwrap(),
+    }
+}
+
+#[test]
+fn plonk_artifacts_round_trip() {
+    let vk = random_plonk_vk(11);
+    let json = plonk_vk_to_json(&vk);
+    assert_eq!(plonk_vk_from_json::<H>(&json), Ok(vk.clone()));
+    assert_eq!(
+        plonk_vk_to_json(&plonk_vk_from_json::<H>(&json).unwrap()),
+        json
+    );
+
+    let mut rng = test_rng();
+    let mut g1 = || G1Affine::<H>::rand(&mut rng);
+    let proof = PlonkProof {
+        a: g1(),
+        b: g1(),
+        c: g1(),
+        z: g1(),
+        t1: g1(),
+        t2: g1(),
+        t3: g1(),
+        wxi: g1(),
+        wxiw: g1(),
+        eval_a: Fr::from(1u8),
+        eval_b: Fr::from(2u8),
+        eval_c: Fr::from(3u8),
+        eval_s1: Fr::from(4u8),
+        eval_s2: Fr::from(5u8),
+        eval_zw: Fr::from(6u8),
+    };
+    let json = plonk_proof_to_json(&proof);
+    assert_eq!(plonk_proof_from_json::<H>(&json), Ok(proof));
+    assert_eq!(
+        groth16_proof_from_json::<H>(&json),
+        Err(Error::InvalidField("protocol"))
+    );
+}
+
+#[test]
+fn plonk_domain_generator_is_checked() {
+    let mut vk = random_plonk_vk(11);
+    vk.w = vk.w.square();
+    assert_eq!(
+        plonk_vk_from_json::<H>(&plonk_vk_to_json(&vk)),
+        Err(Error::InvalidField("w"))
+    );
+
+    let mut value = parse_json(&plonk_vk_to_json(&random_plonk_vk(11))).unwrap();
+    value["power"] = json!(29);
+    assert_eq!(
+        plonk_vk_from_json::<H>(&to_json(&value)),
+        Err(Error::InvalidField("power"))
+    );
+}
+
+#[test]
+fn fflonk_artifacts_round_trip_and_verify() {
+    let (vk, proof, inputs) = crate::fflonk::tests::prove::<H>(0);
+    let vk_json = fflonk_vk_to_json(&vk);
+    assert_eq!(fflonk_vk_from_json::<H>(&vk_json), Ok(vk.clone()));
+    let proof_json = fflonk_proof_to_json(&proof);
+    assert_eq!(fflonk_proof_from_json::<H>(&proof_json), Ok(proof));
+    let inputs_json = public_inputs_to_json(&inputs);
+
+    let vk = fflonk_vk_from_json::<H>(&vk_json).unwrap();
+    let proof = fflonk_proof_from_json::<H>(&proof_json).unwrap();
+    let inputs = public_inputs_from_json(&inputs_json).unwrap();
+    assert_eq!(crate::fflonk::verify(&vk, &proof, &inputs), Ok(true));
+
+    assert_eq!(
+        plonk_proof_from_json::<H>(&proof_json),
+        Err(Error::InvalidField("protocol"))
+    );
+    let missing = replace_first(&proof_json, "\"t1w\"", "\"t1\"");
+    assert_eq!(
+        fflonk_proof_from_json::<H>(&missing),
+        Err(Error::MissingField("t1w"))
+    );
+}
@@ -0,0 +1,915 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [SnarkPack](https://eprint.iacr.org/2021/529.pdf) aggregation of Groth16 proofs.
+//!
+//! `n` proofs for the same verifying key are aggregated in a proof of size
+//! `O(log n)`: the `A`, `B` and `C` vectors are committed with the pairing based
+//! commitments of a structured reference string ([`Srs`]), and the TIPP and MIPP
+//! inner products `Z = prod_i e(A_i, B_i^(r^i))` and `C = sum_i r^i * C_i` are
+//! proved with a GIPA transcript ending in the KZG openings of the folded
+//! commitment keys.
+//!
+//! The verifier folds the GIPA transcript and checks the final commitments, the
+//! KZG openings in G1 and G2 and the aggregated Groth16 equation
+//!
+//! `Z = e(r_sum * alpha, beta) * e(sum_i r^i * L_i, gamma) * e(C, delta)`
+//!
+//! with a random linear combination resolved by a single eleven pairs multi
+//! Miller loop, while the public inputs combination and the other scalar
+//! multiplications are dispatched to the `msm_g1` and `mul_projective_g1` hooks.
+//!
+//! The aggregate proof format and the Fiat-Shamir transcript are specific to this
+//! crate: aggregates can only be verified if they were produced by [`aggregate_proofs`].
+
+use ark_ff::{BigInt, Field, One, PrimeField, UniformRand, Zero};
+use ark_models_ext::{pairing::PairingOutput, AffineRepr, CurveGroup};
+use ark_std::{rand::Rng, vec, vec::Vec};
+use educe::Educe;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    gnark, groth16, Bn254, CurveHooks, Fq, Fq12, Fr, G1Affine, G1Projective, G2Affine,
+    G2Projective, HookError, TryCurveOps, TryPairing,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Size of an encoded target group element.
+pub const GT_LEN: usize = 12 * 32;
+/// Size of an encoded GIPA round.
+pub const ROUND_LEN: usize = 10 * GT_LEN + 2 * gnark::G1_RAW_LEN;
+/// Size of the encoded aggregate proof without the GIPA rounds.
+pub const FIXED_LEN: usize = 5 * GT_LEN + 7 * gnark::G1_RAW_LEN + 5 * gnark::G2_RAW_LEN;
+
+const TRANSCRIPT_DOMAIN: &[u8] = b"snarkpack-v2";
+
+/// Error returned by the aggregator and by the verifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// Reference string points are not valid or too few powers are available.
+    MalformedSrs,
+    /// Proof points or target group elements are not valid, or the number of
+    /// GIPA rounds doesn't match the number of proofs.
+    MalformedProof,
+    /// Number of proofs is not a power of two greater than one.
+    InvalidProofsCount(usize),
+    /// Number of public inputs doesn't match the verifying key.
+    InvalidPublicInputsLength {
+        /// Number of public inputs expected by the verifying key.
+        expected: usize,
+        /// Number of public inputs provided.
+        found: usize,
+    },
+    /// Failure reported by the hooks.
+    Hook(HookError),
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::MalformedSrs => write!(f, "malformed structured reference string"),
+            Error::MalformedProof => write!(f, "malformed proof"),
+            Error::InvalidProofsCount(count) => write!(f, "invalid number of proofs ({count})"),
+            Error::InvalidPublicInputsLength { expected, found } => write!(
+                f,
+                "invalid public inputs length (expected {expected}, found {found})"
+            ),
+            Error::Hook(err) => write!(f, "hook failure: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl From<HookError> for Error {
+    fn from(err: HookError) -> Self {
+        Error::Hook(err)
+    }
+}
+
+/// Target group element.
+pub type Gt<H> = PairingOutput<Bn254<H>>;
+
+/// Pairing commitment `(T, U)` to a vector, one element for each key.
+pub type Commitment<H> = (Gt<H>, Gt<H>);
+
+/// Powers of the two secrets of the reference string in G1 and G2.
+///
+/// Aggregating `n` proofs requires `2n` powers in G1 and `n` in G2, starting from
+/// the generators.
+#[derive(Educe)]
+#[educe(Clone, Debug, PartialEq, Eq)]
+pub struct Srs<H: CurveHooks> {
+    /// `g^(alpha^i)`.
+    pub g_alpha_powers: Vec<G1Affine<H>>,
+    /// `g^(beta^i)`.
+    pub g_beta_powers: Vec<G1Affine<H>>,
+    /// `h^(alpha^i)`.
+    pub h_alpha_powers: Vec<G2Affine<H>>,
+    /// `h^(beta^i)`.
+    pub h_beta_powers: Vec<G2Affine<H>>,
+}
+
+impl<H: CurveHooks> Srs<H> {
+    /// Extracts the key used by the verifier, which doesn't depend on the number
+    /// of proofs.
+    pub fn verifier_srs(&self) -> Result<VerifierSrs<H>, Error> {
+        match (
+            self.g_alpha_powers.as_slice(),
+            self.g_beta_powers.as_slice(),
+            self.h_alpha_powers.as_slice(),
+            self.h_beta_powers.as_slice(),
+        ) {
+            ([g, g_alpha, ..], [g_b, g_beta, ..], [h, h_alpha, ..], [h_b, h_beta, ..])
+                if g == g_b && h == h_b =>
+            {
+                Ok(VerifierSrs {
+                    g: *g,
+                    h: *h,
+                    g_alpha: *g_alpha,
+                    g_beta: *g_beta,
+                    h_alpha: *h_alpha,
+                    h_beta: *h_beta,
+                })
+            }
+            _ => Err(Error::MalformedSrs),
+        }
+    }
+}
+
+/// Reference string elements used by the verifier.
+#[derive(Educe)]
+#[educe(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct VerifierSrs<H: CurveHooks> {
+    /// G1 generator.
+    pub g: G1Affine<H>,
+    /// G2 generator.
+    pub h: G2Affine<H>,
+    /// `g^alpha`.
+    pub g_alpha: G1Affine<H>,
+    /// `g^beta`.
+    pub g_beta: G1Affine<H>,
+    /// `h^alpha`.
+    pub h_alpha: G2Affine<H>,
+    /// `h^beta`.
+    pub h_beta: G2Affine<H>,
+}
+
+impl<H: CurveHooks> VerifierSrs<H> {
+    fn is_valid(&self) -> bool {
+        [self.g, self.g_alpha, self.g_beta]
+            .iter()
+            .all(|point| !point.is_zero() && groth16::is_valid(point))
+            && [self.h, self.h_alpha, self.h_beta]
+                .iter()
+                .all(|point| !point.is_zero() && groth16::is_valid(point))
+    }
+}
+
+/// Cross commitments and cross products of a GIPA round.
+///
+/// The left terms pair the right half of the proofs with the left half of the
+/// keys, the right terms the other way around.
+#[derive(Educe)]
+#[educe(Clone, Debug, PartialEq, Eq)]
+pub struct GipaRound<H: CurveHooks> {
+    /// Left commitment to `A` and `B`.
+    pub com_ab_l: Commitment<H>,
+    /// Right commitment to `A` and `B`.
+    pub com_ab_r: Commitment<H>,
+    /// Left commitment to `C`.
+    pub com_c_l: Commitment<H>,
+    /// Right commitment to `C`.
+    pub com_c_r: Commitment<H>,
+    /// Left pairing product of `A` and `B`.
+    pub z_ab_l: Gt<H>,
+    /// Right pairing product of `A` and `B`.
+    pub z_ab_r: Gt<H>,
+    /// Left inner product of `C` and the powers of `r`.
+    pub z_c_l: G1Affine<H>,
+    /// Right inner product of `C` and the powers of `r`.
+    pub z_c_r: G1Affine<H>,
+}
+
+/// Aggregate proof.
+#[derive(Educe)]
+#[educe(Clone, Debug, PartialEq, Eq)]
+pub struct AggregateProof<H: CurveHooks> {
+    /// Commitment to `A` and `B`.
+    pub com_ab: Commitment<H>,
+    /// Commitment to `C`.
+    pub com_c: Commitment<H>,
+    /// `prod_i e(A_i, B_i^(r^i))`.
+    pub ip_ab: Gt<H>,
+    /// `sum_i r^i * C_i`.
+    pub agg_c: G1Affine<H>,
+    /// GIPA rounds, `log2(n)` of them.
+    pub rounds: Vec<GipaRound<H>>,
+    /// Folded `A`.
+    pub final_a: G1Affine<H>,
+    /// Folded `B`.
+    pub final_b: G2Affine<H>,
+    /// Folded `C`.
+    pub final_c: G1Affine<H>,
+    /// Folded commitment key in G2.
+    pub final_vkey: (G2Affine<H>, G2Affine<H>),
+    /// Folded commitment key in G1.
+    pub final_wkey: (G1Affine<H>, G1Affine<H>),
+    /// KZG openings of the G2 key.
+    pub vkey_opening: (G2Affine<H>, G2Affine<H>),
+    /// KZG openings of the G1 key.
+    pub wkey_opening: (G1Affine<H>, G1Affine<H>),
+}
+
+impl<H: CurveHooks> AggregateProof<H> {
+    fn is_valid(&self) -> bool {
+        let g1 = self
+            .rounds
+            .iter()
+            .flat_map(|round| [round.z_c_l, round.z_c_r])
+            .chain([self.agg_c, self.final_a, self.final_c])
+            .chain([self.final_wkey.0, self.final_wkey.1])
+            .chain([self.wkey_opening.0, self.wkey_opening.1]);
+        let g2 = [self.final_b, self.final_vkey.0, self.final_vkey.1]
+            .into_iter()
+            .chain([self.vkey_opening.0, self.vkey_opening.1]);
+        let gt = self
+            .rounds
+            .iter()
+            .flat_map(|round| {
+                [
+                    round.com_ab_l.0,
+                    round.com_ab_l.1,
+                    round.com_ab_r.0,
+                    round.com_ab_r.1,
+                    round.com_c_l.0,
+                    round.com_c_l.1,
+                    round.com_c_r.0,
+                    round.com_c_r.1,
+                    round.z_ab_l,
+                    round.z_ab_r,
+                ]
+            })
+            .chain([self.com_ab.0, self.com_ab.1, self.com_c.0, self.com_c.1])
+            .chain([self.ip_ab]);
+        g1.into_iter().all(|point| groth16::is_valid(&point))
+            && g2.into_iter().all(|point| groth16::is_valid(&point))
+            && gt.into_iter().all(|gt| is_valid_gt(&gt))
+    }
+}
+
+fn is_valid_gt<H: CurveHooks>(gt: &Gt<H>) -> bool {
+    !gt.0.is_zero() && gt.0.pow(Fr::MODULUS).is_one()
+}
+
+/// SHA-256 Fiat-Shamir transcript, each challenge being derived from a fresh
+/// labelled transcript.
+struct Transcript(Sha256);
+
+impl Transcript {
+    fn new(label: &[u8]) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(TRANSCRIPT_DOMAIN);
+        hasher.update(label);
+        Self(hasher)
+    }
+
+    fn bytes(mut self, bytes: &[u8]) -> Self {
+        self.0.update(bytes);
+        self
+    }
+
+    fn fr(self, value: &Fr) -> Self {
+        self.bytes(&write_prime(value))
+    }
+
+    fn g1<H: CurveHooks>(self, point: &G1Affine<H>) -> Self {
+        self.bytes(&gnark::g1_to_raw(point))
+    }
+
+    fn g2<H: CurveHooks>(self, point: &G2Affine<H>) -> Self {
+        self.bytes(&gnark::g2_to_raw(point))
+    }
+
+    fn gt<H: CurveHooks>(self, gt: &Gt<H>) -> Self {
+        gt.0.to_base_prime_field_elements()
+            .fold(self, |transcript, value| {
+                transcript.bytes(&write_prime(&value))
+            })
+    }
+
+    fn round<H: CurveHooks>(self, round: &GipaRound<H>) -> Self {
+        self.gt(&round.z_ab_l)
+            .gt(&round.z_ab_r)
+            .g1(&round.z_c_l)
+            .g1(&round.z_c_r)
+            .gt(&round.com_ab_l.0)
+            .gt(&round.com_ab_l.1)
+            .gt(&round.com_ab_r.0)
+            .gt(&round.com_ab_r.1)
+            .gt(&round.com_c_l.0)
+            .gt(&round.com_c_l.1)
+            .gt(&round.com_c_r.0)
+            .gt(&round.com_c_r.1)
+    }
+
+    /// Derives a non zero challenge, appending a counter until one is found.
+    fn challenge(self) -> Fr {
+        (0u64..)
+            .map(|counter| {
+                let mut hasher = self.0.clone();
+                hasher.update(counter.to_be_bytes());
+                Fr::from_be_bytes_mod_order(&hasher.finalize())
+            })
+            .find(|challenge| !challenge.is_zero())
+            .expect("a non zero challenge is eventually found")
+    }
+}
+
+fn hcom_challenge<H: CurveHooks>(com_ab: &Commitment<H>, com_c: &Commitment<H>) -> Fr {
+    Transcript::new(b"hcom")
+        .gt(&com_ab.0)
+        .gt(&com_ab.1)
+        .gt(&com_c.0)
+        .gt(&com_c.1)
+        .challenge()
+}
+
+fn r_challenge<I: AsRef<[Fr]>>(hcom: &Fr, public_inputs: &[I]) -> Fr {
+    public_inputs
+        .iter()
+        .flat_map(|inputs| inputs.as_ref())
+        .fold(Transcript::new(b"random-r").fr(hcom), Transcript::fr)
+        .challenge()
+}
+
+fn gipa_challenge<H: CurveHooks>(hcom: &Fr, ip_ab: &Gt<H>, agg_c: &G1Affine<H>, r: &Fr) -> Fr {
+    Transcript::new(b"gipa-0")
+        .fr(hcom)
+        .gt(ip_ab)
+        .g1(agg_c)
+        .fr(r)
+        .challenge()
+}
+
+fn round_challenge<H: CurveHooks>(index: usize, c_inv: &Fr, round: &GipaRound<H>) -> Fr {
+    Transcript::new(b"gipa")
+        .bytes(&(index as u64).to_be_bytes())
+        .fr(c_inv)
+        .round(round)
+        .challenge()
+}
+
+/// Challenge of the KZG openings, binding the last GIPA round and the final
+/// values.
+fn kzg_challenge<H: CurveHooks>(proof: &AggregateProof<H>, last_challenge: &Fr) -> Fr {
+    let last = proof.rounds.last().expect("at least a round");
+    Transcript::new(b"random-z")
+        .fr(last_challenge)
+        .round(last)
+        .g1(&proof.final_a)
+        .g2(&proof.final_b)
+        .g1(&proof.final_c)
+        .g2(&proof.final_vkey.0)
+        .g2(&proof.final_vkey.1)
+        .g1(&proof.final_wkey.0)
+        .g1(&proof.final_wkey.1)
+        .challenge()
+}
+
+fn check_proofs_count(count: usize) -> Result<(), Error> {
+    if count < 2 || !count.is_power_of_two() {
+        return Err(Error::InvalidProofsCount(count));
+    }
+    Ok(())
+}
+
+fn check_public_inputs<I: AsRef<[Fr]>>(expected: usize, public_inputs: &[I]) -> Result<(), Error> {
+    match public_inputs
+        .iter()
+        .map(|inputs| inputs.as_ref().len())
+        .find(|found| *found != expected)
+    {
+        Some(found) => Err(Error::InvalidPublicInputsLength { expected, found }),
+        None => Ok(()),
+    }
+}
+
+/// `[1, x, x^2, ..., x^(n - 1)]`.
+fn powers(x: Fr, n: usize) -> Vec<Fr> {
+    let mut acc = Fr::one();
+    (0..n)
+        .map(|_| {
+            let power = acc;
+            acc *= x;
+            power
+        })
+        .collect()
+}
+
+/// Evaluates `prod_j (1 + x_j * (shift * z)^(2^j))` at `z`, with the challenges
+/// `x_j` in reverse order.
+fn product_form(challenges: &[Fr], z: Fr, shift: Fr) -> Fr {
+    let mut power = z * shift;
+    let mut res = Fr::one();
+    for x in challenges {
+        res *= Fr::one() + *x * power;
+        power.square_in_place();
+    }
+    res
+}
+
+/// Coefficients of `prod_j (1 + x_j * (shift * X)^(2^j))`, with the challenges
+/// `x_j` in reverse order.
+fn product_form_coefficients(challenges: &[Fr], shift: Fr) -> Vec<Fr> {
+    let mut coefficients = vec![Fr::one()];
+    let mut power = shift;
+    for x in challenges {
+        let factor = *x * power;
+        let high: Vec<Fr> = coefficients.iter().map(|c| *c * factor).collect();
+        coefficients.extend(high);
+        power.square_in_place();
+    }
+    coefficients
+}
+
+/// Coefficients of `(f(X) - f(z)) / (X - z)`.
+fn kzg_quotient(coefficients: &[Fr], z: Fr) -> Vec<Fr> {
+    let mut quotient = vec![Fr::zero(); coefficients.len() - 1];
+    let mut acc = Fr::zero();
+    for (q, c) in quotient.iter_mut().zip(&coefficients[1..]).rev() {
+        acc = acc * z + c;
+        *q = acc;
+    }
+    quotient
+}
+
+/// Folds the two halves of `values` as `left + scalar * right`.
+fn compress<G: TryCurveOps<ScalarField = Fr>>(
+    values: &[G::Affine],
+    scalar: &Fr,
+) -> Result<Vec<G::Affine>, HookError> {
+    let (left, right) = values.split_at(values.len() / 2);
+    let folded = left
+        .iter()
+        .zip(right)
+        .map(|(left, right)| Ok(right.into_group().try_mul(scalar)? + left))
+        .collect::<Result<Vec<G>, HookError>>()?;
+    Ok(G::normalize_batch(&folded))
+}
+
+/// Scales `values[i]` by `scalars[i]`.
+fn scale<G: TryCurveOps<ScalarField = Fr>>(
+    values: &[G::Affine],
+    scalars: &[Fr],
+) -> Result<Vec<G::Affine>, HookError> {
+    let scaled = values
+        .iter()
+        .zip(scalars)
+        .map(|(value, scalar)| value.into_group().try_mul(scalar))
+        .collect::<Result<Vec<G>, HookError>>()?;
+    Ok(G::normalize_batch(&scaled))
+}
+
+fn commit_ab<H: CurveHooks>(
+    vkey: (&[G2Affine<H>], &[G2Affine<H>]),
+    wkey: (&[G1Affine<H>], &[G1Affine<H>]),
+    a: &[G1Affine<H>],
+    b: &[G2Affine<H>],
+) -> Result<Commitment<H>, HookError> {
+    let t = Bn254::<H>::try_multi_pairing(
+        a.iter().chain(wkey.0).copied(),
+        vkey.0.iter().chain(b).copied(),
+    )?;
+    let u = Bn254::<H>::try_multi_pairing(
+        a.iter().chain(wkey.1).copied(),
+        vkey.1.iter().chain(b).copied(),
+    )?;
+    Ok((t, u))
+}
+
+fn commit_c<H: CurveHooks>(
+    vkey: (&[G2Affine<H>], &[G2Affine<H>]),
+    c: &[G1Affine<H>],
+) -> Result<Commitment<H>, HookError> {
+    let t = Bn254::<H>::try_multi_pairing(c.iter().copied(), vkey.0.iter().copied())?;
+    let u = Bn254::<H>::try_multi_pairing(c.iter().copied(), vkey.1.iter().copied())?;
+    Ok((t, u))
+}
+
+/// Aggregates the proofs for the given public inputs.
+///
+/// The number of proofs must be a power of two greater than one, and the
+/// reference string must hold at least `2n` powers in G1 and `n` in G2.
+pub fn aggregate_proofs<H: CurveHooks, I: AsRef<[Fr]>>(
+    srs: &Srs<H>,
+    proofs: &[groth16::Proof<H>],
+    public_inputs: &[I],
+) -> Result<AggregateProof<H>, Error> {
+    let n = proofs.len();
+    check_proofs_count(n)?;
+    if public_inputs.len() != n {
+        return Err(Error::InvalidProofsCount(public_inputs.len()));
+    }
+    if srs.g_alpha_powers.len() < 2 * n
+        || srs.g_beta_powers.len() < 2 * n
+        || srs.h_alpha_powers.len() < n
+        || srs.h_beta_powers.len() < n
+    {
+        return Err(Error::MalformedSrs);
+    }
+    let a: Vec<_> = proofs.iter().map(|proof| proof.a).collect();
+    let b: Vec<_> = proofs.iter().map(|proof| proof.b).collect();
+    let c: Vec<_> = proofs.iter().map(|proof| proof.c).collect();
+    let vkey = (&srs.h_alpha_powers[..n], &srs.h_beta_powers[..n]);
+    let wkey = (&srs.g_alpha_powers[n..2 * n], &srs.g_beta_powers[n..2 * n]);
+
+    let com_ab = commit_ab(vkey, wkey, &a, &b)?;
+    let com_c = commit_c(vkey, &c)?;
+    let hcom = hcom_challenge(&com_ab, &com_c);
+    let r = r_challenge(&hcom, public_inputs);
+    let r_inv = r.inverse().expect("challenges are not zero");
+
+    let r_powers = powers(r, n);
+    let r_inv_powers = powers(r_inv, n);
+    let b_r = scale::<G2Projective<H>>(&b, &r_powers)?;
+    let ip_ab = Bn254::<H>::try_multi_pairing(a.iter().copied(), b_r.iter().copied())?;
+    let agg_c = G1Projective::<H>::try_msm(&c, &r_powers)?.into_affine();
+
+    // GIPA over `A`, `B^(r^i)`, `C` and the powers of `r`, with the G1 key
+    // rescaled by `r^-i` so that the commitment to `A` and `B` is unchanged.
+    let (mut m_a, mut m_b, mut m_c, mut m_r) = (a, b_r, c, r_powers);
+    let mut vkey = (vkey.0.to_vec(), vkey.1.to_vec());
+    let mut wkey = (
+        scale::<G1Projective<H>>(wkey.0, &r_inv_powers)?,
+        scale::<G1Projective<H>>(wkey.1, &r_inv_powers)?,
+    );
+    let mut rounds = Vec::new();
+    let mut challenges = Vec::new();
+    let mut challenges_inv = Vec::new();
+    let mut c_inv = gipa_challenge(&hcom, &ip_ab, &agg_c, &r);
+    while m_a.len() > 1 {
+        let split = m_a.len() / 2;
+        let (a_l, a_r) = m_a.split_at(split);
+        let (b_l, b_r) = m_b.split_at(split);
+        let (c_l, c_r) = m_c.split_at(split);
+        let (r_l, r_r) = m_r.split_at(split);
+        let vkey_l = (&vkey.0[..split], &vkey.1[..split]);
+        let vkey_r = (&vkey.0[split..], &vkey.1[split..]);
+        let wkey_l = (&wkey.0[..split], &wkey.1[..split]);
+        let wkey_r = (&wkey.0[split..], &wkey.1[split..]);
+
+        let round = GipaRound {
+            com_ab_l: commit_ab(vkey_l, wkey_r, a_r, b_l)?,
+            com_ab_r: commit_ab(vkey_r, wkey_l, a_l, b_r)?,
+            com_c_l: commit_c(vkey_l, c_r)?,
+            com_c_r: commit_c(vkey_r, c_l)?,
+            z_ab_l: Bn254::<H>::try_multi_pairing(a_r.iter().copied(), b_l.iter().copied())?,
+            z_ab_r: Bn254::<H>::try_multi_pairing(a_l.iter().copied(), b_r.iter().copied())?,
+            z_c_l: G1Projective::<H>::try_msm(c_r, r_l)?.into_affine(),
+            z_c_r: G1Projective::<H>::try_msm(c_l, r_r)?.into_affine(),
+        };
+        c_inv = round_challenge(rounds.len(), &c_inv, &round);
+        let c = c_inv.inverse().expect("challenges are not zero");
+
+        m_a = compress::<G1Projective<H>>(&m_a, &c)?;
+        m_b = compress::<G2Projective<H>>(&m_b, &c_inv)?;
+        m_c = compress::<G1Projective<H>>(&m_c, &c)?;
+        m_r = r_l.iter().zip(r_r).map(|(l, r)| *l + c_inv * r).collect();
+        vkey = (
+            compress::<G2Projective<H>>(&vkey.0, &c_inv)?,
+            compress::<G2Projective<H>>(&vkey.1, &c_inv)?,
+        );
+        wkey = (
+            compress::<G1Projective<H>>(&wkey.0, &c)?,
+            compress::<G1Projective<H>>(&wkey.1, &c)?,
+        );
+        rounds.push(round);
+        challenges.push(c);
+        challenges_inv.push(c_inv);
+    }
+
+    let mut proof = AggregateProof {
+        com_ab,
+        com_c,
+        ip_ab,
+        agg_c,
+        rounds,
+        final_a: m_a[0],
+        final_b: m_b[0],
+        final_c: m_c[0],
+        final_vkey: (vkey.0[0], vkey.1[0]),
+        final_wkey: (wkey.0[0], wkey.1[0]),
+        vkey_opening: (G2Affine::<H>::zero(), G2Affine::<H>::zero()),
+        wkey_opening: (G1Affine::<H>::zero(), G1Affine::<H>::zero()),
+    };
+    let z = kzg_challenge(&proof, &challenges[challenges.len() - 1]);
+    challenges.reverse();
+    challenges_inv.reverse();
+
+    // `v` commits to `f_v(X) = prod_j (1 + x_j^-1 * X^(2^j))` and `w` to
+    // `f_w(X) = X^n * prod_j (1 + x_j * (X / r)^(2^j))`.
+    let f_v = product_form_coefficients(&challenges_inv, Fr::one());
+    let q_v = kzg_quotient(&f_v, z);
+    proof.vkey_opening = (
+        G2Projective::<H>::try_msm(&srs.h_alpha_powers[..q_v.len()], &q_v)?.into_affine(),
+        G2Projective::<H>::try_msm(&srs.h_beta_powers[..q_v.len()], &q_v)?.into_affine(),
+    );
+    let mut f_w = vec![Fr::zero(); n];
+    f_w.extend(product_form_coefficients(&challenges, r_inv));
+    let q_w = kzg_quotient(&f_w, z);
+    proof.wkey_opening = (
+        G1Projective::<H>::try_msm(&srs.g_alpha_powers[..q_w.len()], &q_w)?.into_affine(),
+        G1Projective::<H>::try_msm(&srs.g_beta_powers[..q_w.len()], &q_w)?.into_affine(),
+    );
+    Ok(proof)
+}
+
+/// Verifies the aggregate proof of `public_inputs.len()` proofs against the
+/// prepared verifying key.
+///
+/// The GIPA checks, the KZG openings and the aggregated Groth16 equation are
+/// weighted by random scalars drawn from `rng` and checked with a single multi
+/// Miller loop.
+pub fn verify_aggregate_proof<H: CurveHooks, I: AsRef<[Fr]>, R: Rng>(
+    srs: &VerifierSrs<H>,
+    pvk: &groth16::PreparedVerifyingKey<H>,
+    public_inputs: &[I],
+    proof: &AggregateProof<H>,
+    rng: &mut R,
+) -> Result<bool, Error> {
+    let n = public_inputs.len();
+    check_proofs_count(n)?;
+    check_public_inputs(pvk.vk.num_public_inputs(), public_inputs)?;
+    if !srs.is_valid() {
+        return Err(Error::MalformedSrs);
+    }
+    if proof.rounds.len() != n.trailing_zeros() as usize || !proof.is_valid() {
+        return Err(Error::MalformedProof);
+    }
+
+    let hcom = hcom_challenge(&proof.com_ab, &proof.com_c);
+    let r = r_challenge(&hcom, public_inputs);
+    let r_inv = r.inverse().expect("challenges are not zero");
+    let mut c_inv = gipa_challenge(&hcom, &proof.ip_ab, &proof.agg_c, &r);
+    let mut challenges = Vec::with_capacity(proof.rounds.len());
+    let mut challenges_inv = Vec::with_capacity(proof.rounds.len());
+    for (index, round) in proof.rounds.iter().enumerate() {
+        c_inv = round_challenge(index, &c_inv, round);
+        challenges.push(c_inv.inverse().expect("challenges are not zero"));
+        challenges_inv.push(c_inv);
+    }
+    let z = kzg_challenge(proof, &challenges[challenges.len() - 1]);
+
+    // Fold the GIPA transcript, the left terms being weighted by the challenges
+    // and the right ones by their inverses.
+    let (mut t_ab, mut u_ab) = proof.com_ab;
+    let (mut t_c, mut u_c) = proof.com_c;
+    let mut z_ab = proof.ip_ab;
+    let mut z_c_bases = vec![proof.agg_c];
+    let mut z_c_scalars = vec![Fr::one()];
+    for ((round, c), c_inv) in proof.rounds.iter().zip(&challenges).zip(&challenges_inv) {
+        t_ab += round.com_ab_l.0 * c + round.com_ab_r.0 * c_inv;
+        u_ab += round.com_ab_l.1 * c + round.com_ab_r.1 * c_inv;
+        t_c += round.com_c_l.0 * c + round.com_c_r.0 * c_inv;
+        u_c += round.com_c_l.1 * c + round.com_c_r.1 * c_inv;
+        z_ab += round.z_ab_l * c + round.z_ab_r * c_inv;
+        z_c_bases.extend([round.z_c_l, round.z_c_r]);
+        z_c_scalars.extend([*c, *c_inv]);
+    }
+    challenges.reverse();
+    challenges_inv.reverse();
+
+    // MIPP: the folded `C` times the folded powers of `r` must match.
+    let z_c = G1Projective::<H>::try_msm(&z_c_bases, &z_c_scalars)?;
+    let final_r = product_form(&challenges_inv, r, Fr::one());
+    if proof.final_c.into_group().try_mul(&final_r)? != z_c {
+        return Ok(false);
+    }
+
+    // Evaluations of the commitment keys polynomials at `z`.
+    let y_v = product_form(&challenges_inv, z, Fr::one());
+    let y_w = product_form(&challenges, z, r_inv) * z.pow([n as u64]);
+
+    // Aggregated public inputs combination `sum_i r^i * L_i`.
+    let r_powers = powers(r, n);
+    let r_sum = r_powers.iter().sum::<Fr>();
+    let mut inputs = vec![Fr::zero(); pvk.vk.gamma_abc_g1.len()];
+    inputs[0] = r_sum;
+    for (power, public_inputs) in r_powers.iter().zip(public_inputs) {
+        for (acc, input) in inputs[1..].iter_mut().zip(public_inputs.as_ref()) {
+            *acc += *power * input;
+        }
+    }
+    let g_ic = G1Projective::<H>::try_msm(&pvk.vk.gamma_abc_g1, &inputs)?;
+
+    // Checks, weighted by `rho_i`:
+    // 1. `e(A, B) = Z_ab`;
+    // 2. `e(A, v_1) * e(w_1, B) = T_ab` and 3. `e(A, v_2) * e(w_2, B) = U_ab`;
+    // 4. `e(C, v_1) = T_c` and 5. `e(C, v_2) = U_c`;
+    // 6. `e(-g, v_1) * e(y_v * g, h) * e(g^alpha - z * g, pi_v1) = 1` and 7. same
+    //    with `v_2`, `g^beta` and `pi_v2`;
+    // 8. `e(y_w * g - w_1 - z * pi_w1, h) * e(pi_w1, h^alpha) = 1` and 9. same with
+    //    `w_2`, `pi_w2` and `h^beta`;
+    // and the aggregated Groth16 equation with weight one.
+    let rho: [Fr; 9] = core::array::from_fn(|_| Fr::rand(rng));
+    let (final_a, final_c, g) = (proof.final_a, proof.final_c, srs.g);
+    let (w_1, w_2) = proof.final_wkey;
+    let (pi_w1, pi_w2) = proof.wkey_opening;
+    let g1 = [
+        G1Projective::<H>::try_msm(&[final_a, w_1, w_2], &[rho[0], rho[1], rho[2]])?,
+        G1Projective::<H>::try_msm(&[final_a, final_c, g], &[rho[1], rho[3], -rho[5]])?,
+        G1Projective::<H>::try_msm(&[final_a, final_c, g], &[rho[2], rho[4], -rho[6]])?,
+        G1Projective::<H>::try_msm(
+            &[g, w_1, pi_w1, w_2, pi_w2],
+            &[
+                (rho[5] + rho[6]) * y_v + (rho[7] + rho[8]) * y_w,
+                -rho[7],
+                -rho[7] * z,
+                -rho[8],
+                -rho[8] * z,
+            ],
+        )?,
+        G1Projective::<H>::try_msm(&[srs.g_alpha, g], &[rho[5], -rho[5] * z])?,
+        G1Projective::<H>::try_msm(&[srs.g_beta, g], &[rho[6], -rho[6] * z])?,
+        pi_w1.into_group().try_mul(&rho[7])?,
+        pi_w2.into_group().try_mul(&rho[8])?,
+        pvk.vk.alpha_g1.into_group().try_mul(&r_sum)?,
+        g_ic,
+        proof.agg_c.into_group(),
+    ];
+    let g2 = [
+        proof.final_b,
+        proof.final_vkey.0,
+        proof.final_vkey.1,
+        srs.h,
+        proof.vkey_opening.0,
+        proof.vkey_opening.1,
+        srs.h_alpha,
+        srs.h_beta,
+        pvk.vk.beta_g2,
+        pvk.vk.gamma_g2,
+        pvk.vk.delta_g2,
+    ];
+    let expected =
+        z_ab * rho[0] + t_ab * rho[1] + u_ab * rho[2] + t_c * rho[3] + u_c * rho[4] + proof.ip_ab;
+    let res = Bn254::<H>::try_multi_pairing(G1Projective::<H>::normalize_batch(&g1), g2)?;
+    Ok(res == expected)
+}
+
+/// Encodes the aggregate proof.
+///
+/// Points use the raw layout of [`crate::gnark`] and target group elements are
+/// written as their twelve base field coordinates, each as 32 big-endian bytes.
+pub fn proof_to_bytes<H: CurveHooks>(proof: &AggregateProof<H>) -> Vec<u8> {
+    let mut output = Vec::with_capacity(FIXED_LEN + proof.rounds.len() * ROUND_LEN);
+    for gt in [proof.com_ab.0, proof.com_ab.1, proof.com_c.0, proof.com_c.1] {
+        write_gt(&gt, &mut output);
+    }
+    write_gt(&proof.ip_ab, &mut output);
+    output.extend(gnark::g1_to_raw(&proof.agg_c));
+    for round in &proof.rounds {
+        for gt in [
+            round.com_ab_l.0,
+            round.com_ab_l.1,
+            round.com_ab_r.0,
+            round.com_ab_r.1,
+            round.com_c_l.0,
+            round.com_c_l.1,
+            round.com_c_r.0,
+            round.com_c_r.1,
+            round.z_ab_l,
+            round.z_ab_r,
+        ] {
+            write_gt(&gt, &mut output);
+        }
+        output.extend(gnark::g1_to_raw(&round.z_c_l));
+        output.extend(gnark::g1_to_raw(&round.z_c_r));
+    }
+    output.extend(gnark::g1_to_raw(&proof.final_a));
+    output.extend(gnark::g2_to_raw(&proof.final_b));
+    output.extend(gnark::g1_to_raw(&proof.final_c));
+    output.extend(gnark::g2_to_raw(&proof.final_vkey.0));
+    output.extend(gnark::g2_to_raw(&proof.final_vkey.1));
+    output.extend(gnark::g1_to_raw(&proof.final_wkey.0));
+    output.extend(gnark::g1_to_raw(&proof.final_wkey.1));
+    output.extend(gnark::g2_to_raw(&proof.vkey_opening.0));
+    output.extend(gnark::g2_to_raw(&proof.vkey_opening.1));
+    output.extend(gnark::g1_to_raw(&proof.wkey_opening.0));
+    output.extend(gnark::g1_to_raw(&proof.wkey_opening.1));
+    output
+}
+
+/// Decodes an aggregate proof written by [`proof_to_bytes`].
+///
+/// Points are checked to be on the curve, subgroup membership is checked by the
+/// verifier.
+pub fn proof_from_bytes<H: CurveHooks>(bytes: &[u8]) -> Result<AggregateProof<H>, Error> {
+    let rounds_len = bytes
+        .len()
+        .checked_sub(FIXED_LEN)
+        .ok_or(Error::MalformedProof)?;
+    if rounds_len % ROUND_LEN != 0 {
+        return Err(Error::MalformedProof);
+    }
+    let mut reader = Reader(bytes);
+    let com_ab = (reader.gt()?, reader.gt()?);
+    let com_c = (reader.gt()?, reader.gt()?);
+    let ip_ab = reader.gt()?;
+    let agg_c = reader.g1()?;
+    let rounds = (0..rounds_len / ROUND_LEN)
+        .map(|_| {
+            Ok(GipaRound {
+                com_ab_l: (reader.gt()?, reader.gt()?),
+                com_ab_r: (reader.gt()?, reader.gt()?),
+                com_c_l: (reader.gt()?, reader.gt()?),
+                com_c_r: (reader.gt()?, reader.gt()?),
+                z_ab_l: reader.gt()?,
+                z_ab_r: reader.gt()?,
+                z_c_l: reader.g1()?,
+                z_c_r: reader.g1()?,
+            })
+        })
+        .collect::<Result<_, Error>>()?;
+    Ok(AggregateProof {
+        com_ab,
+        com_c,
+        ip_ab,
+        agg_c,
+        rounds,
+        final_a: reader.g1()?,
+        final_b: reader.g2()?,
+        final_c: reader.g1()?,
+        final_vkey: (reader.g2()?, reader.g2()?),
+        final_wkey: (reader.g1()?, reader.g1()?),
+        vkey_opening: (reader.g2()?, reader.g2()?),
+        wkey_opening: (reader.g1()?, reader.g1()?),
+    })
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let (head, tail) = self.0.split_first_chunk().ok_or(Error::MalformedProof)?;
+        self.0 = tail;
+        Ok(*head)
+    }
+
+    fn g1<H: CurveHooks>(&mut self) -> Result<G1Affine<H>, Error> {
+        gnark::g1_from_raw(&self.take()?).map_err(|_| Error::MalformedProof)
+    }
+
+    fn g2<H: CurveHooks>(&mut self) -> Result<G2Affine<H>, Error> {
+        gnark::g2_from_raw(&self.take()?).map_err(|_| Error::MalformedProof)
+    }
+
+    fn gt<H: CurveHooks>(&mut self) -> Result<Gt<H>, Error> {
+        let coordinates = (0..12)
+            .map(|_| read_prime(&self.take::<32>()?).ok_or(Error::MalformedProof))
+            .collect::<Result<Vec<Fq>, Error>>()?;
+        Fq12::from_base_prime_field_elems(coordinates)
+            .map(PairingOutput)
+            .ok_or(Error::MalformedProof)
+    }
+}
+
+fn write_gt<H: CurveHooks>(gt: &Gt<H>, output: &mut Vec<u8>) {
+    for value in gt.0.to_base_prime_field_elements() {
+        output.extend(write_prime(&value));
+    }
+}
+
+/// Reads a canonical big-endian field element.
+fn read_prime<F: PrimeField<BigInt = BigInt<4>>>(bytes: &[u8; 32]) -> Option<F> {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().rev().zip(bytes.chunks_exact(8)) {
+        *limb = u64::from_be_bytes(chunk.try_into().expect("chunk is 8 bytes long"));
+    }
+    F::from_bigint(BigInt(limbs))
+}
+
+fn write_prime<F: PrimeField<BigInt = BigInt<4>>>(value: &F) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    let limbs = value.into_bigint().0;
+    for (limb, chunk) in limbs.iter().rev().zip(bytes.chunks_exact_mut(8)) {
+        chunk.copy_from_slice(&limb.to_be_bytes());
+    }
+    bytes
+}
@@ -0,0 +1,271 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::{
+    groth16::{
+        prepare_verifying_key,
+        tests::{setup_and_prove_many, Batch},
+        PreparedVerifyingKey, Proof,
+    },
+    metering::{GasSchedule, MeteredHooks, ThreadBudget, OUT_OF_GAS},
+    native::NativeHooks,
+};
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+
+type Instance<H> = (Srs<H>, PreparedVerifyingKey<H>, Batch<H>);
+
+/// Generates an insecure reference string from random secrets.
+fn setup_srs<H: CurveHooks>(rng: &mut StdRng, n: usize) -> Srs<H> {
+    let alpha = Fr::rand(rng);
+    let beta = Fr::rand(rng);
+    let g1 = |secret: Fr| {
+        let points: Vec<_> = powers(secret, 2 * n)
+            .iter()
+            .map(|power| G1Affine::<H>::generator() * power)
+            .collect();
+        G1Projective::<H>::normalize_batch(&points)
+    };
+    let g2 = |secret: Fr| {
+        let points: Vec<_> = powers(secret, n)
+            .iter()
+            .map(|power| G2Affine::<H>::generator() * power)
+            .collect();
+        G2Projective::<H>::normalize_batch(&points)
+    };
+    Srs {
+        g_alpha_powers: g1(alpha),
+        g_beta_powers: g1(beta),
+        h_alpha_powers: g2(alpha),
+        h_beta_powers: g2(beta),
+    }
+}
+
+fn instance<H: CurveHooks>(n: usize) -> Instance<H> {
+    let (vk, batch) = setup_and_prove_many(0, n);
+    let srs = setup_srs(&mut StdRng::seed_from_u64(1), n);
+    (srs, prepare_verifying_key(vk).unwrap(), batch)
+}
+
+fn aggregate<H: CurveHooks>(srs: &Srs<H>, batch: &Batch<H>) -> Result<AggregateProof<H>, Error> {
+    let (proofs, inputs): (Vec<Proof<H>>, Vec<Vec<Fr>>) = batch.iter().cloned().unzip();
+    aggregate_proofs(srs, &proofs, &inputs)
+}
+
+fn inputs<H: CurveHooks>(batch: &Batch<H>) -> Vec<Vec<Fr>> {
+    batch.iter().map(|(_, inputs)| inputs.clone()).collect()
+}
+
+#[test]
+fn aggregate_proofs_are_verified() {
+    let rng = &mut StdRng::seed_from_u64(2);
+    for n in [2, 4, 8] {
+        let (srs, pvk, batch) = instance::<NativeHooks>(n);
+        let proof = aggregate(&srs, &batch).unwrap();
+        assert_eq!(proof.rounds.len(), n.trailing_zeros() as usize);
+
+        let v_srs = srs.verifier_srs().unwrap();
+        assert_eq!(
+            verify_aggregate_proof(&v_srs, &pvk, &inputs(&batch), &proof, rng),
+            Ok(true)
+        );
+    }
+}
+
+#[test]
+fn wrong_public_inputs_are_rejected() {
+    let rng = &mut StdRng::seed_from_u64(2);
+    let (srs, pvk, batch) = instance::<NativeHooks>(4);
+    let proof = aggregate(&srs, &batch).unwrap();
+    let v_srs = srs.verifier_srs().unwrap();
+
+    let mut wrong_inputs = inputs(&batch);
+    wrong_inputs[2][1] += Fr::one();
+    assert_eq!(
+        verify_aggregate_proof(&v_srs, &pvk, &wrong_inputs, &proof, rng),
+        Ok(false)
+    );
+
+    // Proofs are bound to their position in the batch.
+    let mut swapped = inputs(&batch);
+    swapped.swap(0, 3);
+    assert_eq!(
+        verify_aggregate_proof(&v_srs, &pvk, &swapped, &proof, rng),
+        Ok(false)
+    );
+
+    // An aggregate of a proof with the wrong inputs doesn't verify either.
+    let mut wrong_batch = batch.clone();
+    wrong_batch[1].1 = batch[0].1.clone();
+    let wrong_proof = aggregate(&srs, &wrong_batch).unwrap();
+    assert_eq!(
+        verify_aggregate_proof(&v_srs, &pvk, &inputs(&wrong_batch), &wrong_proof, rng),
+        Ok(false)
+    );
+}
+
+#[test]
+fn tampered_proofs_are_rejected() {
+    let rng = &mut StdRng::seed_from_u64(2);
+    let (srs, pvk, batch) = instance::<NativeHooks>(4);
+    let proof = aggregate(&srs, &batch).unwrap();
+    let v_srs = srs.verifier_srs().unwrap();
+    let inputs = inputs(&batch);
+    let verify = |proof: &AggregateProof<NativeHooks>, rng: &mut StdRng| {
+        verify_aggregate_proof(&v_srs, &pvk, &inputs, proof, rng)
+    };
+
+    let mut tampered = proof.clone();
+    tampered.final_a = (tampered.final_a + v_srs.g).into_affine();
+    assert_eq!(verify(&tampered, rng), Ok(false));
+
+    let mut tampered = proof.clone();
+    tampered.rounds[1].z_c_r = tampered.rounds[1].z_c_l;
+    assert_eq!(verify(&tampered, rng), Ok(false));
+
+    let mut tampered = proof.clone();
+    tampered.rounds[0].z_ab_l += tampered.ip_ab;
+    assert_eq!(verify(&tampered, rng), Ok(false));
+
+    let mut tampered = proof.clone();
+    tampered.wkey_opening = (tampered.wkey_opening.1, tampered.wkey_opening.0);
+    assert_eq!(verify(&tampered, rng), Ok(false));
+
+    let mut tampered = proof.clone();
+    tampered.vkey_opening.0 = tampered.final_vkey.0;
+    assert_eq!(verify(&tampered, rng), Ok(false));
+
+    let mut tampered = proof.clone();
+    tampered.ip_ab = PairingOutput(Fq12::rand(rng));
+    assert_eq!(verify(&tampered, rng), Err(Error::MalformedProof));
+
+    let mut tampered = proof.clone();
+    tampered.rounds.pop();
+    assert_eq!(verify(&tampered, rng), Err(Error::MalformedProof));
+
+    let mut tampered = proof;
+    tampered.agg_c = G1Affine::new_unchecked(tampered.agg_c.x, tampered.agg_c.y + tampered.agg_c.y);
+    assert_eq!(verify(&tampered, rng), Err(Error::MalformedProof));
+}
+
+#[test]
+fn malformed_inputs_are_rejected() {
+    let rng = &mut StdRng::seed_from_u64(2);
+    let (srs, pvk, batch) = instance::<NativeHooks>(4);
+    let proof = aggregate(&srs, &batch).unwrap();
+    let v_srs = srs.verifier_srs().unwrap();
+    let inputs = inputs(&batch);
+
+    assert_eq!(
+        aggregate(&srs, &batch[..3].to_vec()),
+        Err(Error::InvalidProofsCount(3))
+    );
+    assert_eq!(
+        aggregate(&srs, &batch[..1].to_vec()),
+        Err(Error::InvalidProofsCount(1))
+    );
+    assert_eq!(
+        verify_aggregate_proof(&v_srs, &pvk, &inputs[..3], &proof, rng),
+        Err(Error::InvalidProofsCount(3))
+    );
+    assert_eq!(
+        verify_aggregate_proof(&v_srs, &pvk, &inputs[..2], &proof, rng),
+        Err(Error::MalformedProof)
+    );
+
+    let mut short_inputs = inputs.clone();
+    short_inputs[3].pop();
+    assert_eq!(
+        verify_aggregate_proof(&v_srs, &pvk, &short_inputs, &proof, rng),
+        Err(Error::InvalidPublicInputsLength {
+            expected: 2,
+            found: 1
+        })
+    );
+
+    let mut short_srs = srs.clone();
+    short_srs.g_alpha_powers.truncate(7);
+    assert_eq!(aggregate(&short_srs, &batch), Err(Error::MalformedSrs));
+    short_srs.g_alpha_powers.truncate(1);
+    assert_eq!(short_srs.verifier_srs(), Err(Error::MalformedSrs));
+
+    let bad_srs = VerifierSrs {
+        h_alpha: G2Affine::zero(),
+        ..v_srs
+    };
+    assert_eq!(
+        verify_aggregate_proof(&bad_srs, &pvk, &inputs, &proof, rng),
+        Err(Error::MalformedSrs)
+    );
+}
+
+#[test]
+fn proof_bytes_round_trip() {
+    let (srs, _, batch) = instance::<NativeHooks>(8);
+    let proof = aggregate(&srs, &batch).unwrap();
+
+    let bytes = proof_to_bytes(&proof);
+    assert_eq!(bytes.len(), FIXED_LEN + 3 * ROUND_LEN);
+    assert_eq!(proof_from_bytes::<NativeHooks>(&bytes), Ok(proof));
+
+    assert_eq!(
+        proof_from_bytes::<NativeHooks>(&bytes[1..]),
+        Err(Error::MalformedProof)
+    );
+    assert_eq!(
+        proof_from_bytes::<NativeHooks>(&bytes[..FIXED_LEN - 1]),
+        Err(Error::MalformedProof)
+    );
+    let mut non_canonical = bytes;
+    non_canonical[..32].fill(0xff);
+    assert_eq!(
+        proof_from_bytes::<NativeHooks>(&non_canonical),
+        Err(Error::MalformedProof)
+    );
+}
+
+#[test]
+fn verify_uses_the_hooks() {
+    type Hooks = MeteredHooks<NativeHooks, ThreadBudget>;
+    let schedule = GasSchedule::EIP_1108;
+    let rng = &mut StdRng::seed_from_u64(2);
+    ThreadBudget::reset(u64::MAX);
+    let (srs, pvk, batch) = instance::<Hooks>(8);
+    let v_srs = srs.verifier_srs().unwrap();
+    let inputs = inputs(&batch);
+    let proof = aggregate(&srs, &batch).unwrap();
+
+    // Folding of the MIPP transcript and public inputs combination, followed by
+    // the pairing checks operands and the final eleven pairs check.
+    let expected = schedule.msm_g1_cost(7)
+        + 4 * schedule.ec_mul_cost()
+        + schedule.msm_g1_cost(3)
+        + 3 * schedule.msm_g1_cost(3)
+        + schedule.msm_g1_cost(5)
+        + 2 * schedule.msm_g1_cost(2)
+        + schedule.ec_pairing_cost(11);
+    ThreadBudget::reset(u64::MAX);
+    assert_eq!(
+        verify_aggregate_proof(&v_srs, &pvk, &inputs, &proof, rng),
+        Ok(true)
+    );
+    assert_eq!(ThreadBudget::used(), expected);
+
+    ThreadBudget::reset(expected - 1);
+    assert_eq!(
+        verify_aggregate_proof(&v_srs, &pvk, &inputs, &proof, rng),
+        Err(Error::Hook(OUT_OF_GAS))
+    );
+}
@@ -0,0 +1,705 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [snarkjs](https://github.com/iden3/snarkjs) Groth16 proving keys (`.zkey`
+//! files) and their phase 2 contributions.
+//!
+//! A `.zkey` file shares the binary layout of the [`ptau`] files, with the `zkey`
+//! magic. [`read`] loads the Groth16 header, the `IC`, `A`, `B1`, `B2`, `C` (the
+//! private inputs bases, `L` below) and `H` sections and the list of
+//! contributions, checking that every point is on the curve and in the prime
+//! order subgroup.
+//!
+//! [`verify`] checks a key against the constraint system (see [`r1cs`]) and the
+//! powers of tau it was built from, as `snarkjs zkey verify` does, deriving the
+//! initial key from them instead of trusting one:
+//! * the sizes match the constraints and `alpha`, `beta` and `gamma` come from
+//!   the powers of tau;
+//! * `IC`, `A`, `B1` and `B2` are the evaluations at `tau` of the constraints
+//!   polynomials, with the constraints `A_(m + s) = [s]` appended for every public
+//!   input `s`, as snarkjs does;
+//! * every contribution extends the transcript, proves the knowledge of its
+//!   secret and updates `delta` accordingly;
+//! * `delta` is the one of the last contribution in both groups;
+//! * `L` holds the evaluations of the private inputs polynomials divided by
+//!   `delta`;
+//! * `H` holds the odd Lagrange bases of the doubled domain, from the powers of
+//!   tau, divided by `delta`.
+//!
+//! The circuit hash rooting the transcript is taken from the key, not recomputed.
+//!
+//! Every pairing check and multi scalar multiplication is dispatched to the
+//! [`CurveHooks`]. Beacon contributions are checked like the other ones, without
+//! deriving their secret from the beacon.
+
+use std::io::{self, Read};
+
+use ark_ff::{BigInt, FftField, Field, PrimeField, UniformRand, Zero};
+use ark_models_ext::{short_weierstrass::SWCurveConfig, AffineRepr, CurveConfig, CurveGroup};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::{rand::Rng, vec, vec::Vec};
+use educe::Educe;
+
+use crate::{
+    g2, gnark, groth16,
+    ptau::{self, BinFile, Ptau},
+    Bn254, CurveHooks, Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine, G2Projective, HookError,
+    TryCurveOps, TryPairing,
+};
+
+pub mod r1cs;
+#[cfg(test)]
+mod tests;
+
+pub use r1cs::R1cs;
+
+/// Size of the hashes of the contributions.
+pub const HASH_LEN: usize = 64;
+
+const MAGIC: &[u8; 4] = b"zkey";
+const VERSION: u32 = 1;
+const GROTH16_PROTOCOL: u32 = 1;
+const HEADER_SECTION: u32 = 1;
+const GROTH16_HEADER_SECTION: u32 = 2;
+const IC_SECTION: u32 = 3;
+const COEFFS_SECTION: u32 = 4;
+const A_SECTION: u32 = 5;
+const B_G1_SECTION: u32 = 6;
+const B_G2_SECTION: u32 = 7;
+const L_SECTION: u32 = 8;
+const H_SECTION: u32 = 9;
+const CONTRIBUTIONS_SECTION: u32 = 10;
+
+/// Error returned by the reader and by the verifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// Reading the binary file failed.
+    File(ptau::Error),
+    /// Key is not for the Groth16 protocol.
+    UnsupportedProtocol(u32),
+    /// Number of variables, of public inputs or domain size are not consistent.
+    InvalidHeader,
+    /// Parameters of the contribution with the given index are malformed.
+    InvalidContributionParams(usize),
+    /// Constraint system is malformed.
+    InvalidR1cs,
+    /// Sizes of the key don't match the constraint system.
+    InconsistentR1cs,
+    /// Powers of tau are too few for the domain of the key.
+    PtauTooSmall,
+    /// `alpha`, `beta` or `gamma` don't come from the powers of tau.
+    InconsistentPtau,
+    /// `IC` section is not consistent with the constraints and the powers of tau.
+    InvalidICSection,
+    /// `A` section is not consistent with the constraints and the powers of tau.
+    InvalidASection,
+    /// `B1` or `B2` section is not consistent with the constraints and the powers
+    /// of tau.
+    InvalidBSection,
+    /// Contribution with the given index doesn't extend the transcript.
+    InvalidTranscript(usize),
+    /// Contribution with the given index doesn't prove the knowledge of its
+    /// secret.
+    InvalidProofOfKnowledge(usize),
+    /// Contribution with the given index doesn't update `delta` with its secret.
+    InvalidDeltaUpdate(usize),
+    /// `delta` is not the one of the last contribution in both groups.
+    InvalidDelta,
+    /// `L` section is not consistent with the constraints, the powers of tau and
+    /// `delta`.
+    InvalidLSection,
+    /// `H` section is not consistent with the powers of tau and `delta`.
+    InvalidHSection,
+    /// Failure reported by the hooks.
+    Hook(HookError),
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::File(err) => write!(f, "invalid file: {err}"),
+            Error::UnsupportedProtocol(protocol) => write!(f, "unsupported protocol {protocol}"),
+            Error::InvalidHeader => write!(f, "invalid header"),
+            Error::InvalidContributionParams(index) => {
+                write!(f, "invalid parameters of contribution {index}")
+            }
+            Error::InvalidR1cs => write!(f, "invalid constraint system"),
+            Error::InconsistentR1cs => write!(f, "key doesn't match the constraint system"),
+            Error::PtauTooSmall => write!(f, "powers of tau too small for the key"),
+            Error::InconsistentPtau => write!(f, "key not built from the powers of tau"),
+            Error::InvalidICSection => write!(f, "invalid IC section"),
+            Error::InvalidASection => write!(f, "invalid A section"),
+            Error::InvalidBSection => write!(f, "invalid B sections"),
+            Error::InvalidTranscript(index) => {
+                write!(f, "invalid transcript of contribution {index}")
+            }
+            Error::InvalidProofOfKnowledge(index) => {
+                write!(f, "invalid proof of knowledge of contribution {index}")
+            }
+            Error::InvalidDeltaUpdate(index) => {
+                write!(f, "invalid delta update of contribution {index}")
+            }
+            Error::InvalidDelta => write!(f, "invalid delta"),
+            Error::InvalidLSection => write!(f, "invalid L section"),
+            Error::InvalidHSection => write!(f, "invalid H section"),
+            Error::Hook(err) => write!(f, "hook failure: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<HookError> for Error {
+    fn from(err: HookError) -> Self {
+        Error::Hook(err)
+    }
+}
+
+impl From<ptau::Error> for Error {
+    fn from(err: ptau::Error) -> Self {
+        Error::File(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::File(err.into())
+    }
+}
+
+/// Groth16 proving key of a snarkjs circuit.
+#[derive(Educe)]
+#[educe(Clone, Debug, PartialEq, Eq)]
+pub struct Zkey<H: CurveHooks> {
+    /// Number of variables, including the constant one.
+    pub n_vars: u32,
+    /// Number of public inputs.
+    pub n_public: u32,
+    /// Size of the evaluation domain.
+    pub domain_size: u32,
+    /// Verifying key.
+    pub vk: groth16::VerifyingKey<H>,
+    /// `beta` in G1.
+    pub beta_g1: G1Affine<H>,
+    /// `delta` in G1.
+    pub delta_g1: G1Affine<H>,
+    /// `A_i(tau)` in G1 for every variable.
+    pub a: Vec<G1Affine<H>>,
+    /// `B_i(tau)` in G1 for every variable.
+    pub b_g1: Vec<G1Affine<H>>,
+    /// `B_i(tau)` in G2 for every variable.
+    pub b_g2: Vec<G2Affine<H>>,
+    /// Private inputs bases, divided by `delta`.
+    pub l: Vec<G1Affine<H>>,
+    /// Quotient bases, divided by `delta`.
+    pub h: Vec<G1Affine<H>>,
+    /// Hash of the circuit, the root of the contributions transcript.
+    pub cs_hash: [u8; HASH_LEN],
+    /// Phase 2 contributions, in order.
+    pub contributions: Vec<Contribution<H>>,
+}
+
+/// Phase 2 contribution, multiplying `delta` by the secret `x`.
+#[derive(Educe)]
+#[educe(Clone, Debug, PartialEq, Eq)]
+pub struct Contribution<H: CurveHooks> {
+    /// `delta` in G1 after the contribution.
+    pub delta_after: G1Affine<H>,
+    /// Random point `s` of the proof of knowledge.
+    pub g1_s: G1Affine<H>,
+    /// `x * s`.
+    pub g1_sx: G1Affine<H>,
+    /// `x * sp`, `sp` being derived from the transcript.
+    pub g2_spx: G2Affine<H>,
+    /// Transcript hash up to the contribution.
+    pub transcript: [u8; HASH_LEN],
+    /// Zero for regular contributions, one for beacons.
+    pub kind: u32,
+    /// Name given by the contributor.
+    pub name: Option<String>,
+    /// Exponent of the number of iterations of the beacon.
+    pub beacon_iterations_exp: Option<u8>,
+    /// Beacon hash.
+    pub beacon_hash: Option<Vec<u8>>,
+}
+
+/// Header of the Groth16 section.
+struct Header<H: CurveHooks> {
+    n_vars: usize,
+    n_public: usize,
+    domain_size: usize,
+    alpha_g1: G1Affine<H>,
+    beta_g1: G1Affine<H>,
+    beta_g2: G2Affine<H>,
+    gamma_g2: G2Affine<H>,
+    delta_g1: G1Affine<H>,
+    delta_g2: G2Affine<H>,
+}
+
+impl<H: CurveHooks> Header<H> {
+    fn read<R: Read>(section: &mut ptau::Section<'_, R>) -> Result<Self, Error> {
+        section.prime::<Fq>()?;
+        section.prime::<Fr>()?;
+        let n_vars = section.u32()? as usize;
+        let n_public = section.u32()? as usize;
+        let domain_size = section.u32()? as usize;
+        if n_public >= n_vars
+            || !domain_size.is_power_of_two()
+            || domain_size > 1 << ptau::MAX_POWER
+        {
+            return Err(Error::InvalidHeader);
+        }
+        Ok(Self {
+            n_vars,
+            n_public,
+            domain_size,
+            alpha_g1: section.g1()?,
+            beta_g1: section.g1()?,
+            beta_g2: section.g2()?,
+            gamma_g2: section.g2()?,
+            delta_g1: section.g1()?,
+            delta_g2: section.g2()?,
+        })
+    }
+}
+
+fn read_contributions<H: CurveHooks, R: Read>(
+    section: &mut ptau::Section<'_, R>,
+) -> Result<([u8; HASH_LEN], Vec<Contribution<H>>), Error> {
+    let cs_hash = section.bytes()?;
+    let count = section.u32()? as usize;
+    let mut contributions = Vec::new();
+    for index in 0..count {
+        let mut contribution = Contribution {
+            delta_after: section.g1()?,
+            g1_s: section.g1()?,
+            g1_sx: section.g1()?,
+            g2_spx: section.g2()?,
+            transcript: section.bytes()?,
+            kind: section.u32()?,
+            name: None,
+            beacon_iterations_exp: None,
+            beacon_hash: None,
+        };
+        let len = section.u32()? as usize;
+        let params = section.data(len)?;
+        contribution
+            .read_params(&params)
+            .ok_or(Error::InvalidContributionParams(index))?;
+        contributions.push(contribution);
+    }
+    Ok((cs_hash, contributions))
+}
+
+impl<H: CurveHooks> Contribution<H> {
+    /// Appends the public key of the contribution to the transcript, with points in
+    /// the uncompressed big-endian encoding.
+    fn update_transcript(&self, transcript: &mut blake2b_simd::State) {
+        transcript.update(&gnark::g1_to_raw(&self.delta_after));
+        transcript.update(&gnark::g1_to_raw(&self.g1_s));
+        transcript.update(&gnark::g1_to_raw(&self.g1_sx));
+        transcript.update(&gnark::g2_to_raw(&self.g2_spx));
+        transcript.update(&self.transcript);
+    }
+
+    /// Reads the parameters, a list of `(type, value)` sorted by type.
+    fn read_params(&mut self, mut params: &[u8]) -> Option<()> {
+        let mut last = 0;
+        while let Some((&kind, rest)) = params.split_first() {
+            if kind <= last {
+                return None;
+            }
+            last = kind;
+            let (&first, rest) = rest.split_first()?;
+            params = match kind {
+                2 => {
+                    self.beacon_iterations_exp = Some(first);
+                    rest
+                }
+                // Names and hashes are prefixed by their length.
+                1 | 3 if rest.len() >= first as usize => {
+                    let (value, rest) = rest.split_at(first as usize);
+                    match kind {
+                        1 => self.name = Some(String::from_utf8_lossy(value).into_owned()),
+                        _ => self.beacon_hash = Some(value.to_vec()),
+                    }
+                    rest
+                }
+                _ => return None,
+            };
+        }
+        Some(())
+    }
+}
+
+/// Reads a `.zkey` file.
+///
+/// The reader is buffered internally. The coefficients section, only needed by
+/// the prover, is skipped.
+pub fn read<H: CurveHooks, R: Read>(reader: R) -> Result<Zkey<H>, Error> {
+    let mut file = BinFile::new(reader, MAGIC, VERSION)?;
+    let mut protocol = None;
+    let mut header = None;
+    let mut ic = None;
+    let mut a = None;
+    let mut b_g1 = None;
+    let mut b_g2 = None;
+    let mut l = None;
+    let mut h = None;
+    let mut contributions = None;
+    while let Some(mut section) = file.next_section()? {
+        // Points sections follow the headers in the files written by snarkjs.
+        let sizes = header
+            .as_ref()
+            .map(|header: &Header<H>| (header.n_vars, header.n_public, header.domain_size));
+        let duplicate = match (section.id, sizes) {
+            (HEADER_SECTION, _) => match section.u32()? {
+                GROTH16_PROTOCOL => protocol.replace(GROTH16_PROTOCOL).is_some(),
+                other => return Err(Error::UnsupportedProtocol(other)),
+            },
+            (GROTH16_HEADER_SECTION, _) => header.replace(Header::read(&mut section)?).is_some(),
+            (IC_SECTION, Some((_, n_public, _))) => {
+                ic.replace(section.g1_vec(n_public + 1)?).is_some()
+            }
+            (A_SECTION, Some((n_vars, _, _))) => a.replace(section.g1_vec(n_vars)?).is_some(),
+            (B_G1_SECTION, Some((n_vars, _, _))) => b_g1.replace(section.g1_vec(n_vars)?).is_some(),
+            (B_G2_SECTION, Some((n_vars, _, _))) => b_g2.replace(section.g2_vec(n_vars)?).is_some(),
+            (L_SECTION, Some((n_vars, n_public, _))) => {
+                l.replace(section.g1_vec(n_vars - n_public - 1)?).is_some()
+            }
+            (H_SECTION, Some((_, _, domain_size))) => {
+                h.replace(section.g1_vec(domain_size)?).is_some()
+            }
+            (IC_SECTION..=H_SECTION, None) if section.id != COEFFS_SECTION => {
+                return Err(ptau::Error::MissingSection(GROTH16_HEADER_SECTION).into())
+            }
+            (CONTRIBUTIONS_SECTION, _) => contributions
+                .replace(read_contributions(&mut section)?)
+                .is_some(),
+            _ => {
+                section.skip()?;
+                false
+            }
+        };
+        if duplicate {
+            return Err(ptau::Error::DuplicateSection(section.id).into());
+        }
+        section.finish()?;
+    }
+    let missing = |id| Error::File(ptau::Error::MissingSection(id));
+    protocol.ok_or(missing(HEADER_SECTION))?;
+    let header = header.ok_or(missing(GROTH16_HEADER_SECTION))?;
+    let (cs_hash, contributions) = contributions.ok_or(missing(CONTRIBUTIONS_SECTION))?;
+    Ok(Zkey {
+        n_vars: header.n_vars as u32,
+        n_public: header.n_public as u32,
+        domain_size: header.domain_size as u32,
+        vk: groth16::VerifyingKey {
+            alpha_g1: header.alpha_g1,
+            beta_g2: header.beta_g2,
+            gamma_g2: header.gamma_g2,
+            delta_g2: header.delta_g2,
+            gamma_abc_g1: ic.ok_or(missing(IC_SECTION))?,
+        },
+        beta_g1: header.beta_g1,
+        delta_g1: header.delta_g1,
+        a: a.ok_or(missing(A_SECTION))?,
+        b_g1: b_g1.ok_or(missing(B_G1_SECTION))?,
+        b_g2: b_g2.ok_or(missing(B_G2_SECTION))?,
+        l: l.ok_or(missing(L_SECTION))?,
+        h: h.ok_or(missing(H_SECTION))?,
+        cs_hash,
+        contributions,
+    })
+}
+
+/// Verifies `zkey` against the constraint system and the powers of tau,
+/// returning the first failed check.
+///
+/// The contributions are checked in order and reported by their index, starting
+/// from zero. The points sections are checked with random linear combinations
+/// drawn from `rng`. The points are assumed valid, as returned by [`read`].
+pub fn verify<H: CurveHooks, R: Rng>(
+    r1cs: &R1cs,
+    ptau: &Ptau<H>,
+    zkey: &Zkey<H>,
+    rng: &mut R,
+) -> Result<(), Error> {
+    let (g1, g2) = (G1Affine::<H>::generator(), G2Affine::<H>::generator());
+    let (n_vars, n_public) = (zkey.n_vars as usize, zkey.n_public as usize);
+    let n = zkey.domain_size as usize;
+    let rows = r1cs.constraints.len() + n_public + 1;
+    if (r1cs.n_wires, r1cs.n_public) != (zkey.n_vars, zkey.n_public)
+        || n != rows.next_power_of_two()
+        || zkey.vk.gamma_abc_g1.len() != n_public + 1
+        || zkey.a.len() != n_vars
+        || zkey.b_g1.len() != n_vars
+        || zkey.b_g2.len() != n_vars
+        || Some(zkey.l.len()) != n_vars.checked_sub(n_public + 1)
+        || zkey.h.len() != n
+    {
+        return Err(Error::InconsistentR1cs);
+    }
+    if ptau.tau_g1.len() < 2 * n || ptau.tau_g2.len() < n || ptau.alpha_tau_g1.len() < n {
+        return Err(Error::PtauTooSmall);
+    }
+    if ptau.alpha_tau_g1.first() != Some(&zkey.vk.alpha_g1)
+        || ptau.beta_tau_g1.first() != Some(&zkey.beta_g1)
+        || ptau.beta_g2 != zkey.vk.beta_g2
+        || zkey.vk.gamma_g2 != g2
+    {
+        return Err(Error::InconsistentPtau);
+    }
+    let domain = Radix2EvaluationDomain::<Fr>::new(n)
+        .filter(|domain| domain.size() == n)
+        .ok_or(Error::InvalidHeader)?;
+
+    // For random `r_i`, the combination of the points of the variables `i` is the
+    // polynomial `sum_c s_c * L_c(X)`, with `s_c = sum_i r_i * M_(c, i)` and `L_c`
+    // the Lagrange polynomials of the domain, whose coefficients are `IFFT(s)`.
+    // The sums are split by public and private variables.
+    let r: Vec<Fr> = (0..n_vars).map(|_| Fr::rand(rng)).collect();
+    let mut evals = [(); 6].map(|_| vec![Fr::zero(); n]);
+    for (row, constraint) in r1cs.constraints.iter().enumerate() {
+        for (matrix, terms) in [&constraint.a, &constraint.b, &constraint.c]
+            .into_iter()
+            .enumerate()
+        {
+            for (wire, coeff) in terms {
+                let wire = *wire as usize;
+                let r = r.get(wire).ok_or(Error::InvalidR1cs)?;
+                let private = usize::from(wire > n_public);
+                evals[3 * private + matrix][row] += *coeff * r;
+            }
+        }
+    }
+    for (wire, r) in r.iter().enumerate().take(n_public + 1) {
+        evals[0][r1cs.constraints.len() + wire] += r;
+    }
+    let [a_pub, b_pub, c_pub, a_priv, b_priv, c_priv] = evals.map(|evals| domain.ifft(&evals));
+    let sum = |x: &[Fr], y: &[Fr]| -> Vec<Fr> { x.iter().zip(y).map(|(x, y)| *x + y).collect() };
+    let (a, b) = (sum(&a_pub, &a_priv), sum(&b_pub, &b_priv));
+    let tau_g1 = &ptau.tau_g1[..n];
+    // `beta * A(tau) + alpha * B(tau) + C(tau)` in G1.
+    let combined = |a: &[Fr], b: &[Fr], c: &[Fr]| -> Result<G1Projective<H>, Error> {
+        Ok(G1Projective::<H>::try_msm(&ptau.beta_tau_g1[..n], a)?
+            + G1Projective::<H>::try_msm(&ptau.alpha_tau_g1[..n], b)?
+            + G1Projective::<H>::try_msm(tau_g1, c)?)
+    };
+
+    let ic = G1Projective::<H>::try_msm(&zkey.vk.gamma_abc_g1, &r[..n_public + 1])?;
+    if ic != combined(&a_pub, &b_pub, &c_pub)? {
+        return Err(Error::InvalidICSection);
+    }
+    if G1Projective::<H>::try_msm(&zkey.a, &r)? != G1Projective::<H>::try_msm(tau_g1, &a)? {
+        return Err(Error::InvalidASection);
+    }
+    if G1Projective::<H>::try_msm(&zkey.b_g1, &r)? != G1Projective::<H>::try_msm(tau_g1, &b)?
+        || G2Projective::<H>::try_msm(&zkey.b_g2, &r)?
+            != G2Projective::<H>::try_msm(&ptau.tau_g2[..n], &b)?
+    {
+        return Err(Error::InvalidBSection);
+    }
+
+    let mut accumulator = blake2b_simd::State::new();
+    accumulator.update(&zkey.cs_hash);
+    let mut delta = g1;
+    for (index, contribution) in zkey.contributions.iter().enumerate() {
+        let mut hasher = accumulator.clone();
+        hasher.update(&gnark::g1_to_raw(&contribution.g1_s));
+        hasher.update(&gnark::g1_to_raw(&contribution.g1_sx));
+        if hasher.finalize().as_bytes() != contribution.transcript {
+            return Err(Error::InvalidTranscript(index));
+        }
+
+        // `e(s, x * sp) = e(x * s, sp)` and `e(delta, x * sp) = e(delta_after, sp)`.
+        let g2_sp = hash_to_g2::<H>(&contribution.transcript)?;
+        let rhs = [contribution.g2_spx, g2_sp];
+        if contribution.g1_s.is_zero()
+            || !Bn254::<H>::pairing_check([contribution.g1_s, -contribution.g1_sx], rhs)?
+        {
+            return Err(Error::InvalidProofOfKnowledge(index));
+        }
+        if contribution.delta_after.is_zero()
+            || !Bn254::<H>::pairing_check([delta, -contribution.delta_after], rhs)?
+        {
+            return Err(Error::InvalidDeltaUpdate(index));
+        }
+
+        contribution.update_transcript(&mut accumulator);
+        delta = contribution.delta_after;
+    }
+    if zkey.delta_g1 != delta
+        || !Bn254::<H>::pairing_check([zkey.delta_g1, -g1], [g2, zkey.vk.delta_g2])?
+    {
+        return Err(Error::InvalidDelta);
+    }
+
+    // `e(sum_i r_i * L_i, delta) = e(beta * A(tau) + alpha * B(tau) + C(tau), g2)`
+    // over the private variables.
+    let l = G1Projective::<H>::try_msm(&zkey.l, &r[n_public + 1..])?;
+    let lhs = G1Projective::<H>::normalize_batch(&[l, -combined(&a_priv, &b_priv, &c_priv)?]);
+    if !Bn254::<H>::pairing_check(lhs, [zkey.vk.delta_g2, g2])? {
+        return Err(Error::InvalidLSection);
+    }
+
+    // `H_j = L_(2j + 1)(tau) / delta` with `L_k` the Lagrange polynomials of the
+    // domain of size `2n` and root `w`. For `u = IFFT_n(r)` the combination
+    // `sum_j r_j * L_(2j + 1)(X)` is `sum_k c_k * X^k` with
+    // `c_k = w^-k * u_(k mod n) / 2`, checked with
+    // `e(sum_j r_j * H_j, delta) = e(sum_k c_k * tau^k * g1, g2)`.
+    let root_inv = Fr::get_root_of_unity(2 * n as u64)
+        .and_then(|root| root.inverse())
+        .ok_or(Error::InvalidHeader)?;
+    let scalars: Vec<Fr> = (0..n).map(|_| Fr::rand(rng)).collect();
+    let coeffs = domain.ifft(&scalars);
+    let mut factor = Fr::from(2u64).inverse().expect("two is invertible");
+    let powers: Vec<Fr> = (0..2 * n)
+        .map(|k| {
+            let power = factor * coeffs[k % n];
+            factor *= root_inv;
+            power
+        })
+        .collect();
+    let h = G1Projective::<H>::try_msm(&zkey.h, &scalars)?;
+    let tau_h = G1Projective::<H>::try_msm(&ptau.tau_g1[..2 * n], &powers)?;
+    let lhs = G1Projective::<H>::normalize_batch(&[h, -tau_h]);
+    if !Bn254::<H>::pairing_check(lhs, [zkey.vk.delta_g2, g2])? {
+        return Err(Error::InvalidHSection);
+    }
+    Ok(())
+}
+
+/// Derives the G2 point `sp` of a contribution from its transcript, as snarkjs
+/// does: the first half of the hash seeds a ChaCha20 stream, which samples the
+/// `x` coordinate and the sign of `y`, and the point is then multiplied by the
+/// cofactor.
+pub(crate) fn hash_to_g2<H: CurveHooks>(hash: &[u8; HASH_LEN]) -> Result<G2Affine<H>, Error> {
+    let mut seed = [0; 8];
+    for (word, chunk) in seed.iter_mut().zip(hash.chunks_exact(4)) {
+        *word = u32::from_be_bytes(chunk.try_into().expect("chunk is 4 bytes long"));
+    }
+    let mut rng = ChaCha::new(seed);
+    let (x, y) = loop {
+        let x = Fq2::new(rng.next_fq(), rng.next_fq());
+        let largest = rng.next_bool();
+        if let Some(y) = (x.square() * x + <g2::Config<H> as SWCurveConfig>::COEFF_B).sqrt() {
+            break (
+                x,
+                if fq2_is_negative(&y) == largest {
+                    y
+                } else {
+                    -y
+                },
+            );
+        }
+    };
+    let point = G2Affine::<H>::new_unchecked(x, y).into_group();
+    let cofactor = <g2::Config<H> as CurveConfig>::COFACTOR;
+    Ok(H::bn254_mul_projective_g2(&point, cofactor)?.into_affine())
+}
+
+/// Sign of `Fq2` elements as defined by ffjavascript: `c1` first, `c0` when `c1`
+/// is zero.
+fn fq2_is_negative(value: &Fq2) -> bool {
+    let value = match value.c1.is_zero() {
+        true => value.c0,
+        false => value.c1,
+    };
+    value.into_bigint() > Fq::MODULUS_MINUS_ONE_DIV_TWO
+}
+
+/// ChaCha20 stream of ffjavascript, with a 64 bits block counter and a zero nonce.
+pub(crate) struct ChaCha {
+    state: [u32; 16],
+    block: [u32; 16],
+    index: usize,
+}
+
+impl ChaCha {
+    pub(crate) fn new(seed: [u32; 8]) -> Self {
+        let mut state = [0; 16];
+        state[..4].copy_from_slice(&[0x61707865, 0x3320646e, 0x79622d32, 0x6b206574]);
+        state[4..12].copy_from_slice(&seed);
+        Self {
+            state,
+            block: [0; 16],
+            index: 16,
+        }
+    }
+
+    pub(crate) fn next_u32(&mut self) -> u32 {
+        if self.index == 16 {
+            self.block = self.state;
+            for _ in 0..10 {
+                for [a, b, c, d] in [
+                    [0, 4, 8, 12],
+                    [1, 5, 9, 13],
+                    [2, 6, 10, 14],
+                    [3, 7, 11, 15],
+                    [0, 5, 10, 15],
+                    [1, 6, 11, 12],
+                    [2, 7, 8, 13],
+                    [3, 4, 9, 14],
+                ] {
+                    quarter_round(&mut self.block, a, b, c, d);
+                }
+            }
+            for (word, state) in self.block.iter_mut().zip(self.state) {
+                *word = word.wrapping_add(state);
+            }
+            let counter = (u64::from(self.state[13]) << 32 | u64::from(self.state[12])) + 1;
+            self.state[12] = counter as u32;
+            self.state[13] = (counter >> 32) as u32;
+            self.index = 0;
+        }
+        self.index += 1;
+        self.block[self.index - 1]
+    }
+
+    /// The first word drawn is the most significant one.
+    fn next_u64(&mut self) -> u64 {
+        (u64::from(self.next_u32()) << 32) | u64::from(self.next_u32())
+    }
+
+    fn next_bool(&mut self) -> bool {
+        self.next_u32() & 1 == 1
+    }
+
+    /// Samples the Montgomery form of a field element: 254 bits, least significant
+    /// limb first, until one is below the modulus.
+    fn next_fq(&mut self) -> Fq {
+        loop {
+            let mut limbs = [0; 4];
+            limbs.iter_mut().for_each(|limb| *limb = self.next_u64());
+            limbs[3] &= u64::MAX >> 2;
+            if BigInt(limbs) < Fq::MODULUS {
+                return Fq::new_unchecked(BigInt(limbs));
+            }
+        }
+    }
+}
+
+fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
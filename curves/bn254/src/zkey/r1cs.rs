@@ -0,0 +1,132 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! circom constraint systems (`.r1cs` files).
+//!
+//! The file shares the binary layout of the [`ptau`] files, with the `r1cs` magic.
+//! Coefficients are written in standard form, unlike the points of the keys.
+
+use std::io::Read;
+
+use ark_std::vec::Vec;
+
+use super::Error;
+use crate::{
+    ptau::{self, BinFile},
+    Fr,
+};
+
+const MAGIC: &[u8; 4] = b"r1cs";
+const VERSION: u32 = 1;
+const HEADER_SECTION: u32 = 1;
+const CONSTRAINTS_SECTION: u32 = 2;
+
+/// Linear combination of wires, as `(wire, coefficient)` pairs.
+pub type LinearCombination = Vec<(u32, Fr)>;
+
+/// Constraint `A * B = C`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Constraint {
+    /// Left factor.
+    pub a: LinearCombination,
+    /// Right factor.
+    pub b: LinearCombination,
+    /// Product.
+    pub c: LinearCombination,
+}
+
+/// Rank-1 constraint system of a circuit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct R1cs {
+    /// Number of wires, including the constant one.
+    pub n_wires: u32,
+    /// Number of public inputs, outputs included.
+    pub n_public: u32,
+    /// Constraints, in order.
+    pub constraints: Vec<Constraint>,
+}
+
+fn read_combination<R: Read>(
+    section: &mut ptau::Section<'_, R>,
+    n_wires: u32,
+) -> Result<LinearCombination, Error> {
+    let len = section.u32()?;
+    let mut terms = Vec::new();
+    for _ in 0..len {
+        let wire = section.u32()?;
+        if wire >= n_wires {
+            return Err(Error::InvalidR1cs);
+        }
+        terms.push((wire, section.integer()?));
+    }
+    Ok(terms)
+}
+
+/// Reads a `.r1cs` file.
+///
+/// The reader is buffered internally. The wires to labels map and the custom
+/// gates sections are skipped.
+pub fn read<R: Read>(reader: R) -> Result<R1cs, Error> {
+    let mut file = BinFile::new(reader, MAGIC, VERSION)?;
+    let mut header = None;
+    let mut constraints = None;
+    while let Some(mut section) = file.next_section()? {
+        let duplicate = match (section.id, header) {
+            (HEADER_SECTION, _) => {
+                section.prime::<Fr>()?;
+                let n_wires = section.u32()?;
+                let n_public = section.u32()?.checked_add(section.u32()?);
+                let _n_private = section.u32()?;
+                let _n_labels = section.bytes::<8>()?;
+                let n_constraints = section.u32()?;
+                match n_public {
+                    Some(n_public) if n_public < n_wires => {
+                        header.replace((n_wires, n_public, n_constraints)).is_some()
+                    }
+                    _ => return Err(Error::InvalidR1cs),
+                }
+            }
+            (CONSTRAINTS_SECTION, Some((n_wires, _, n_constraints))) => {
+                let mut list = Vec::new();
+                for _ in 0..n_constraints {
+                    list.push(Constraint {
+                        a: read_combination(&mut section, n_wires)?,
+                        b: read_combination(&mut section, n_wires)?,
+                        c: read_combination(&mut section, n_wires)?,
+                    });
+                }
+                constraints.replace(list).is_some()
+            }
+            (CONSTRAINTS_SECTION, None) => {
+                return Err(ptau::Error::MissingSection(HEADER_SECTION).into())
+            }
+            _ => {
+                section.skip()?;
+                false
+            }
+        };
+        if duplicate {
+            return Err(ptau::Error::DuplicateSection(section.id).into());
+        }
+        section.finish()?;
+    }
+    let missing = |id| Error::File(ptau::Error::MissingSection(id));
+    let (n_wires, n_public, _) = header.ok_or(missing(HEADER_SECTION))?;
+    Ok(R1cs {
+        n_wires,
+        n_public,
+        constraints: constraints.ok_or(missing(CONSTRAINTS_SECTION))?,
+    })
+}
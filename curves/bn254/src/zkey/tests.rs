@@ -0,0 +1,668 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::{
+    metering::{GasSchedule, MeteredHooks, ThreadBudget, OUT_OF_GAS},
+    native::NativeHooks,
+    ptau::tests::{
+        random_ptau, setup_ptau, write_bin_file, write_g1, write_g2, write_prime, Sections,
+    },
+    G2Projective,
+};
+use ark_ff::One;
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+use hex_literal::hex;
+
+// `snarkjs groth16 setup` output for the circom multiplier `c <== a * b`, before
+// any contribution, from the ark-circom test vectors.
+const CIRCOM_ZKEY: &[u8] = include_bytes!("test-data/test.zkey");
+const CIRCOM_R1CS: &[u8] = include_bytes!("test-data/mycircuit.r1cs");
+#[cfg(feature = "snarkjs")]
+const CIRCOM_VK: &str = include_str!("test-data/verification_key.json");
+
+const POWER: u32 = 4;
+const N_VARS: usize = 6;
+const N_PUBLIC: usize = 2;
+const N_CONSTRAINTS: usize = 4;
+const DOMAIN_SIZE: usize = 8;
+
+fn g1_points<H: CurveHooks>(scalars: &[Fr]) -> Vec<G1Affine<H>> {
+    let points: Vec<_> = scalars
+        .iter()
+        .map(|scalar| G1Affine::<H>::generator() * scalar)
+        .collect();
+    G1Projective::<H>::normalize_batch(&points)
+}
+
+/// Random constraint system, with two wires in each factor.
+fn circuit(rng: &mut StdRng) -> R1cs {
+    let mut combination = || -> r1cs::LinearCombination {
+        (0..2)
+            .map(|_| (rng.gen_range(0..N_VARS as u32), Fr::rand(rng)))
+            .collect()
+    };
+    let constraints = (0..N_CONSTRAINTS)
+        .map(|_| r1cs::Constraint {
+            a: combination(),
+            b: combination(),
+            c: combination(),
+        })
+        .collect();
+    R1cs {
+        n_wires: N_VARS as u32,
+        n_public: N_PUBLIC as u32,
+        constraints,
+    }
+}
+
+/// Random circuit, powers of tau and the initial key built from them, as
+/// `snarkjs groth16 setup` does.
+fn setup<H: CurveHooks>(seed: u64) -> (R1cs, Ptau<H>, Zkey<H>) {
+    let rng = &mut StdRng::seed_from_u64(seed);
+    let r1cs = circuit(rng);
+    let (tau, alpha, beta) = (Fr::rand(rng), Fr::rand(rng), Fr::rand(rng));
+    let ptau = setup_ptau::<H>(POWER, tau, alpha, beta);
+
+    // `A_i(tau)`, `B_i(tau)` and `C_i(tau)` for every variable.
+    let lagrange = Radix2EvaluationDomain::<Fr>::new(DOMAIN_SIZE)
+        .unwrap()
+        .evaluate_all_lagrange_coefficients(tau);
+    let mut polys = [[Fr::zero(); N_VARS]; 3];
+    for (row, constraint) in r1cs.constraints.iter().enumerate() {
+        for (poly, terms) in polys
+            .iter_mut()
+            .zip([&constraint.a, &constraint.b, &constraint.c])
+        {
+            for (wire, coeff) in terms {
+                poly[*wire as usize] += *coeff * lagrange[row];
+            }
+        }
+    }
+    for wire in 0..=N_PUBLIC {
+        polys[0][wire] += lagrange[N_CONSTRAINTS + wire];
+    }
+    let [a, b, c] = polys;
+    let k: Vec<_> = (0..N_VARS)
+        .map(|i| beta * a[i] + alpha * b[i] + c[i])
+        .collect();
+
+    let lagrange = Radix2EvaluationDomain::<Fr>::new(2 * DOMAIN_SIZE)
+        .unwrap()
+        .evaluate_all_lagrange_coefficients(tau);
+    let h: Vec<_> = (0..DOMAIN_SIZE).map(|j| lagrange[2 * j + 1]).collect();
+    let b_g2: Vec<_> = b.iter().map(|b| G2Affine::<H>::generator() * b).collect();
+    let mut cs_hash = [0; HASH_LEN];
+    cs_hash.iter_mut().for_each(|byte| *byte = rng.gen());
+    let zkey = Zkey {
+        n_vars: N_VARS as u32,
+        n_public: N_PUBLIC as u32,
+        domain_size: DOMAIN_SIZE as u32,
+        vk: groth16::VerifyingKey {
+            alpha_g1: ptau.alpha_tau_g1[0],
+            beta_g2: ptau.beta_g2,
+            gamma_g2: G2Affine::<H>::generator(),
+            delta_g2: G2Affine::<H>::generator(),
+            gamma_abc_g1: g1_points(&k[..=N_PUBLIC]),
+        },
+        beta_g1: ptau.beta_tau_g1[0],
+        delta_g1: G1Affine::<H>::generator(),
+        a: g1_points(&a),
+        b_g1: g1_points(&b),
+        b_g2: G2Projective::<H>::normalize_batch(&b_g2),
+        l: g1_points(&k[N_PUBLIC + 1..]),
+        h: g1_points(&h),
+        cs_hash,
+        contributions: Vec::new(),
+    };
+    (r1cs, ptau, zkey)
+}
+
+fn r1cs_sections(r1cs: &R1cs) -> Sections {
+    let mut header = Vec::new();
+    write_prime::<Fr>(&mut header);
+    let n_private = r1cs.n_wires - r1cs.n_public - 1;
+    // All public inputs are written as outputs.
+    for value in [r1cs.n_wires, r1cs.n_public, 0, n_private] {
+        header.extend(value.to_le_bytes());
+    }
+    header.extend(u64::from(r1cs.n_wires).to_le_bytes());
+    header.extend((r1cs.constraints.len() as u32).to_le_bytes());
+
+    let mut constraints = Vec::new();
+    for constraint in &r1cs.constraints {
+        for terms in [&constraint.a, &constraint.b, &constraint.c] {
+            constraints.extend((terms.len() as u32).to_le_bytes());
+            for (wire, coeff) in terms {
+                constraints.extend(wire.to_le_bytes());
+                for limb in coeff.into_bigint().0 {
+                    constraints.extend(limb.to_le_bytes());
+                }
+            }
+        }
+    }
+    let labels = (0..u64::from(r1cs.n_wires))
+        .flat_map(u64::to_le_bytes)
+        .collect();
+    vec![(1, header), (2, constraints), (3, labels)]
+}
+
+/// Prover coefficients of the section 4 of a `.zkey` file, as
+/// `(matrix, constraint, signal, value)`, where values are doubly Montgomery.
+fn zkey_coefficients(file: &[u8]) -> Vec<(u32, u32, u32, Fr)> {
+    let mut file = BinFile::new(file, MAGIC, VERSION).unwrap();
+    while let Some(mut section) = file.next_section().unwrap() {
+        if section.id != COEFFS_SECTION {
+            section.skip().unwrap();
+            continue;
+        }
+        let len = section.u32().unwrap();
+        return (0..len)
+            .map(|_| {
+                let (matrix, constraint, signal) = (
+                    section.u32().unwrap(),
+                    section.u32().unwrap(),
+                    section.u32().unwrap(),
+                );
+                let value: Fr = section.field().unwrap();
+                (matrix, constraint, signal, value)
+            })
+            .collect();
+    }
+    panic!("no coefficients section");
+}
+
+/// Multiplies `delta` by `x`, proving its knowledge with the random point
+/// `s * g1`.
+fn contribute<H: CurveHooks>(zkey: &mut Zkey<H>, x: Fr, s: Fr, name: &str) {
+    let mut transcript = blake2b_simd::State::new();
+    transcript.update(&zkey.cs_hash);
+    for contribution in &zkey.contributions {
+        contribution.update_transcript(&mut transcript);
+    }
+    let g1_s = (G1Affine::<H>::generator() * s).into_affine();
+    let g1_sx = (g1_s * x).into_affine();
+    transcript.update(&gnark::g1_to_raw(&g1_s));
+    transcript.update(&gnark::g1_to_raw(&g1_sx));
+    let transcript: [u8; HASH_LEN] = transcript.finalize().as_bytes().try_into().unwrap();
+    let g2_sp = hash_to_g2::<H>(&transcript).unwrap();
+
+    let x_inv = x.inverse().unwrap();
+    let l: Vec<_> = zkey.l.iter().map(|point| *point * x_inv).collect();
+    let h: Vec<_> = zkey.h.iter().map(|point| *point * x_inv).collect();
+    zkey.l = G1Projective::<H>::normalize_batch(&l);
+    zkey.h = G1Projective::<H>::normalize_batch(&h);
+    zkey.delta_g1 = (zkey.delta_g1 * x).into_affine();
+    zkey.vk.delta_g2 = (zkey.vk.delta_g2 * x).into_affine();
+    zkey.contributions.push(Contribution {
+        delta_after: zkey.delta_g1,
+        g1_s,
+        g1_sx,
+        g2_spx: (g2_sp * x).into_affine(),
+        transcript,
+        kind: 0,
+        name: Some(name.into()),
+        beacon_iterations_exp: None,
+        beacon_hash: None,
+    });
+}
+
+fn contributed<H: CurveHooks>(initial: &Zkey<H>, contributions: usize, seed: u64) -> Zkey<H> {
+    let rng = &mut StdRng::seed_from_u64(seed);
+    let mut zkey = initial.clone();
+    for index in 0..contributions {
+        contribute(
+            &mut zkey,
+            Fr::rand(rng),
+            Fr::rand(rng),
+            &format!("contributor {index}"),
+        );
+    }
+    zkey
+}
+
+fn zkey_sections<H: CurveHooks>(zkey: &Zkey<H>) -> Sections {
+    let g1 = |points: &[G1Affine<H>], output: &mut Vec<u8>| {
+        points.iter().for_each(|point| write_g1(point, output));
+    };
+    let g1_section = |points: &[G1Affine<H>]| {
+        let mut output = Vec::new();
+        g1(points, &mut output);
+        output
+    };
+    let mut header = Vec::new();
+    write_prime::<Fq>(&mut header);
+    write_prime::<Fr>(&mut header);
+    for value in [zkey.n_vars, zkey.n_public, zkey.domain_size] {
+        header.extend(value.to_le_bytes());
+    }
+    g1(&[zkey.vk.alpha_g1, zkey.beta_g1], &mut header);
+    write_g2(&zkey.vk.beta_g2, &mut header);
+    write_g2(&zkey.vk.gamma_g2, &mut header);
+    write_g1(&zkey.delta_g1, &mut header);
+    write_g2(&zkey.vk.delta_g2, &mut header);
+
+    let mut b_g2 = Vec::new();
+    zkey.b_g2
+        .iter()
+        .for_each(|point| write_g2(point, &mut b_g2));
+
+    let mut contributions = zkey.cs_hash.to_vec();
+    contributions.extend((zkey.contributions.len() as u32).to_le_bytes());
+    for contribution in &zkey.contributions {
+        g1(
+            &[
+                contribution.delta_after,
+                contribution.g1_s,
+                contribution.g1_sx,
+            ],
+            &mut contributions,
+        );
+        write_g2(&contribution.g2_spx, &mut contributions);
+        contributions.extend(contribution.transcript);
+        contributions.extend(contribution.kind.to_le_bytes());
+        let mut params = Vec::new();
+        if let Some(name) = &contribution.name {
+            params.extend([1, name.len() as u8]);
+            params.extend(name.as_bytes());
+        }
+        if let Some(exp) = contribution.beacon_iterations_exp {
+            params.extend([2, exp]);
+        }
+        if let Some(hash) = &contribution.beacon_hash {
+            params.extend([3, hash.len() as u8]);
+            params.extend(hash);
+        }
+        contributions.extend((params.len() as u32).to_le_bytes());
+        contributions.extend(params);
+    }
+
+    vec![
+        (HEADER_SECTION, GROTH16_PROTOCOL.to_le_bytes().to_vec()),
+        (GROTH16_HEADER_SECTION, header),
+        (IC_SECTION, g1_section(&zkey.vk.gamma_abc_g1)),
+        // Coefficients, skipped by the reader.
+        (COEFFS_SECTION, vec![0; 4]),
+        (A_SECTION, g1_section(&zkey.a)),
+        (B_G1_SECTION, g1_section(&zkey.b_g1)),
+        (B_G2_SECTION, b_g2),
+        (L_SECTION, g1_section(&zkey.l)),
+        (H_SECTION, g1_section(&zkey.h)),
+        (CONTRIBUTIONS_SECTION, contributions),
+    ]
+}
+
+fn write_zkey<H: CurveHooks>(zkey: &Zkey<H>) -> Vec<u8> {
+    write_bin_file(MAGIC, VERSION, &zkey_sections(zkey))
+}
+
+#[test]
+fn chacha_matches_the_reference_stream() {
+    // First block of ChaCha20 with zero key, nonce and counter.
+    let expected = hex!(
+        "76b8e0ada0f13d90405d6ae55386bd28bdd219b8a08ded1aa836efcc8b770dc7"
+        "da41597c5157488d7724e03fb8d84a376a43b8f41518a11cc387b669b2ee6586"
+    );
+    let mut rng = ChaCha::new([0; 8]);
+    for chunk in expected.chunks_exact(4) {
+        assert_eq!(rng.next_u32().to_le_bytes(), chunk);
+    }
+    // The counter moves to the next block.
+    assert_eq!(rng.next_u32().to_le_bytes(), hex!("9f07e7be"));
+}
+
+#[test]
+fn hashes_are_mapped_to_g2() {
+    let point = hash_to_g2::<NativeHooks>(&[1; HASH_LEN]).unwrap();
+    assert!(point.is_on_curve());
+    assert!(point.is_in_correct_subgroup_assuming_on_curve());
+    assert!(!point.is_zero());
+    assert_eq!(hash_to_g2(&[1; HASH_LEN]), Ok(point));
+    assert_ne!(hash_to_g2(&[2; HASH_LEN]), Ok(point));
+
+    // Only the first half of the hash seeds the stream.
+    let mut hash = [1; HASH_LEN];
+    hash[32..].fill(0);
+    assert_eq!(hash_to_g2(&hash), Ok(point));
+}
+
+#[test]
+fn snarkjs_files_are_read() {
+    let r1cs = r1cs::read(CIRCOM_R1CS).unwrap();
+    assert_eq!(
+        r1cs,
+        R1cs {
+            n_wires: 4,
+            n_public: 1,
+            constraints: vec![r1cs::Constraint {
+                a: vec![(2, -Fr::one())],
+                b: vec![(3, Fr::one())],
+                c: vec![(1, -Fr::one())],
+            }],
+        }
+    );
+
+    let zkey = read::<NativeHooks, _>(CIRCOM_ZKEY).unwrap();
+    assert_eq!((zkey.n_vars, zkey.n_public, zkey.domain_size), (4, 1, 4));
+    assert!(zkey.contributions.is_empty());
+    assert_eq!(zkey.delta_g1, G1Affine::<NativeHooks>::generator());
+    #[cfg(feature = "snarkjs")]
+    assert_eq!(
+        zkey.vk,
+        crate::snarkjs::groth16_vk_from_json(CIRCOM_VK).unwrap()
+    );
+    // The sizes match the constraints, the ceremony is not available.
+    let ptau = random_ptau(3, 0);
+    let rng = &mut StdRng::seed_from_u64(1);
+    assert_eq!(
+        verify(&r1cs, &ptau, &zkey, rng),
+        Err(Error::InconsistentPtau)
+    );
+}
+
+#[test]
+fn snarkjs_coefficients_match_the_constraints() {
+    // The prover coefficients are the `A` and `B` matrices, with the input
+    // constraints `verify` appends.
+    let r1cs = r1cs::read(CIRCOM_R1CS).unwrap();
+    let mut expected = Vec::new();
+    for (row, constraint) in r1cs.constraints.iter().enumerate() {
+        for (matrix, terms) in [&constraint.a, &constraint.b].into_iter().enumerate() {
+            for (wire, coeff) in terms {
+                expected.push((matrix as u32, row as u32, *wire, *coeff));
+            }
+        }
+    }
+    let rows = r1cs.constraints.len() as u32;
+    for wire in 0..=r1cs.n_public {
+        expected.push((0, rows + wire, wire, Fr::one()));
+    }
+    let r = Fr::from_bigint(Fr::R).unwrap();
+    let mut coefficients: Vec<_> = zkey_coefficients(CIRCOM_ZKEY)
+        .into_iter()
+        .map(|(matrix, row, wire, value)| (matrix, row, wire, value / r))
+        .collect();
+    let key = |&(matrix, row, wire, _): &(u32, u32, u32, Fr)| (matrix, row, wire);
+    expected.sort_by_key(key);
+    coefficients.sort_by_key(key);
+    assert_eq!(coefficients, expected);
+}
+
+#[test]
+fn r1cs_files_are_read() {
+    let (r1cs, _, _) = setup::<NativeHooks>(0);
+    let sections = r1cs_sections(&r1cs);
+    assert_eq!(
+        r1cs::read(write_bin_file(b"r1cs", 1, &sections).as_slice()),
+        Ok(r1cs)
+    );
+    let read = |edit: &dyn Fn(&mut Sections)| {
+        let mut sections = sections.clone();
+        edit(&mut sections);
+        r1cs::read(write_bin_file(b"r1cs", 1, &sections).as_slice())
+    };
+
+    assert_eq!(
+        read(&|s| s.swap(0, 1)),
+        Err(Error::File(ptau::Error::MissingSection(1)))
+    );
+    assert_eq!(
+        read(&|s| {
+            s.remove(1);
+        }),
+        Err(Error::File(ptau::Error::MissingSection(2)))
+    );
+    // Wire out of range.
+    assert_eq!(
+        read(&|s| s[1].1[4..8].copy_from_slice(&(N_VARS as u32).to_le_bytes())),
+        Err(Error::InvalidR1cs)
+    );
+    // As many public inputs as wires.
+    assert_eq!(
+        read(&|s| s[0].1[40..44].copy_from_slice(&(N_VARS as u32).to_le_bytes())),
+        Err(Error::InvalidR1cs)
+    );
+    // Coefficients are in standard form.
+    assert_eq!(
+        read(&|s| s[1].1[8..40].fill(0xff)),
+        Err(Error::File(ptau::Error::InvalidFieldElement))
+    );
+    // More constraints than written.
+    assert_eq!(
+        read(&|s| s[0].1[60..64].copy_from_slice(&(N_CONSTRAINTS as u32 + 1).to_le_bytes())),
+        Err(Error::File(ptau::Error::InvalidSectionSize(2)))
+    );
+}
+
+#[test]
+fn zkey_files_are_read_and_verified() {
+    let rng = &mut StdRng::seed_from_u64(1);
+    let (r1cs, ptau, initial) = setup::<NativeHooks>(0);
+    assert_eq!(read(write_zkey(&initial).as_slice()), Ok(initial.clone()));
+    assert_eq!(verify(&r1cs, &ptau, &initial, rng), Ok(()));
+
+    let mut zkey = contributed(&initial, 3, 0);
+    zkey.contributions[2].kind = 1;
+    zkey.contributions[2].beacon_iterations_exp = Some(10);
+    zkey.contributions[2].beacon_hash = Some(vec![7; 32]);
+    assert_eq!(read(write_zkey(&zkey).as_slice()), Ok(zkey.clone()));
+    assert_eq!(verify(&r1cs, &ptau, &zkey, rng), Ok(()));
+}
+
+#[test]
+fn failing_contributions_are_reported() {
+    let rng = &mut StdRng::seed_from_u64(1);
+    let (r1cs, ptau, initial) = setup::<NativeHooks>(0);
+    let zkey = contributed(&initial, 3, 0);
+    let other = contributed(&setup::<NativeHooks>(1).2, 3, 1);
+    let mut verify = |zkey: &Zkey<NativeHooks>| verify(&r1cs, &ptau, zkey, rng);
+
+    let mut wrong = zkey.clone();
+    wrong.contributions[1].transcript = other.contributions[1].transcript;
+    assert_eq!(verify(&wrong), Err(Error::InvalidTranscript(1)));
+
+    // Changing the public key of a contribution breaks the later transcripts.
+    let mut wrong = zkey.clone();
+    wrong.contributions[0].g2_spx = other.contributions[0].g2_spx;
+    assert_eq!(verify(&wrong), Err(Error::InvalidProofOfKnowledge(0)));
+
+    let mut wrong = zkey.clone();
+    wrong.contributions[2].g2_spx = other.contributions[2].g2_spx;
+    assert_eq!(verify(&wrong), Err(Error::InvalidProofOfKnowledge(2)));
+
+    let mut wrong = zkey.clone();
+    wrong.contributions[2].delta_after = other.contributions[2].delta_after;
+    assert_eq!(verify(&wrong), Err(Error::InvalidDeltaUpdate(2)));
+}
+
+#[test]
+fn inconsistent_keys_are_rejected() {
+    let rng = &mut StdRng::seed_from_u64(1);
+    let (r1cs, ptau, initial) = setup::<NativeHooks>(0);
+    let (other_r1cs, other_ptau, other_initial) = setup::<NativeHooks>(1);
+    let zkey = contributed(&initial, 2, 0);
+    let other = contributed(&other_initial, 2, 1);
+    let mut verify = |r1cs: &R1cs, ptau: &Ptau<NativeHooks>, zkey: &Zkey<NativeHooks>| {
+        verify(r1cs, ptau, zkey, rng)
+    };
+
+    assert_eq!(
+        verify(&r1cs, &other_ptau, &zkey),
+        Err(Error::InconsistentPtau)
+    );
+    let mut small = ptau.clone();
+    small.tau_g1.truncate(2 * DOMAIN_SIZE - 1);
+    assert_eq!(verify(&r1cs, &small, &zkey), Err(Error::PtauTooSmall));
+
+    let mut wrong = r1cs.clone();
+    wrong.n_public -= 1;
+    assert_eq!(verify(&wrong, &ptau, &zkey), Err(Error::InconsistentR1cs));
+    // The input constraints no longer fit the domain.
+    let mut wrong = r1cs.clone();
+    wrong.constraints.extend_from_slice(&r1cs.constraints[..2]);
+    assert_eq!(verify(&wrong, &ptau, &zkey), Err(Error::InconsistentR1cs));
+    let mut wrong = r1cs.clone();
+    wrong.constraints[0].a[0].0 = N_VARS as u32;
+    assert_eq!(verify(&wrong, &ptau, &zkey), Err(Error::InvalidR1cs));
+    // Keys of another circuit over the same powers of tau.
+    assert_eq!(
+        verify(&other_r1cs, &ptau, &zkey),
+        Err(Error::InvalidICSection)
+    );
+
+    let mut wrong = zkey.clone();
+    wrong.vk.gamma_abc_g1[1] = other.vk.gamma_abc_g1[1];
+    assert_eq!(verify(&r1cs, &ptau, &wrong), Err(Error::InvalidICSection));
+    let mut wrong = zkey.clone();
+    wrong.a.swap(3, 4);
+    assert_eq!(verify(&r1cs, &ptau, &wrong), Err(Error::InvalidASection));
+    let mut wrong = zkey.clone();
+    wrong.b_g1[3] = other.b_g1[3];
+    assert_eq!(verify(&r1cs, &ptau, &wrong), Err(Error::InvalidBSection));
+    let mut wrong = zkey.clone();
+    wrong.b_g2[3] = other.b_g2[3];
+    assert_eq!(verify(&r1cs, &ptau, &wrong), Err(Error::InvalidBSection));
+
+    let mut wrong = zkey.clone();
+    wrong.delta_g1 = initial.delta_g1;
+    assert_eq!(verify(&r1cs, &ptau, &wrong), Err(Error::InvalidDelta));
+    let mut wrong = zkey.clone();
+    wrong.vk.delta_g2 = other.vk.delta_g2;
+    assert_eq!(verify(&r1cs, &ptau, &wrong), Err(Error::InvalidDelta));
+
+    let mut wrong = zkey.clone();
+    wrong.l[1] = other.l[1];
+    assert_eq!(verify(&r1cs, &ptau, &wrong), Err(Error::InvalidLSection));
+    // `L` not divided by `delta`.
+    let mut wrong = zkey.clone();
+    wrong.l = initial.l.clone();
+    assert_eq!(verify(&r1cs, &ptau, &wrong), Err(Error::InvalidLSection));
+
+    let mut wrong = zkey.clone();
+    wrong.h.swap(0, 3);
+    assert_eq!(verify(&r1cs, &ptau, &wrong), Err(Error::InvalidHSection));
+    // `H` from different powers of tau.
+    let mut wrong = initial.clone();
+    wrong.h = other_initial.h.clone();
+    assert_eq!(verify(&r1cs, &ptau, &wrong), Err(Error::InvalidHSection));
+}
+
+#[test]
+fn malformed_files_are_rejected() {
+    let (_, _, initial) = setup::<NativeHooks>(0);
+    let zkey = contributed(&initial, 1, 0);
+    let sections = zkey_sections(&zkey);
+    let read = |file: &[u8]| read::<NativeHooks, _>(file);
+    let with_sections = |edit: &dyn Fn(&mut Sections)| {
+        let mut sections = sections.clone();
+        edit(&mut sections);
+        write_bin_file(MAGIC, VERSION, &sections)
+    };
+    let file_error = |err| Err(Error::File(err));
+
+    assert_eq!(
+        read(&write_bin_file(b"ptau", VERSION, &sections)),
+        file_error(ptau::Error::InvalidMagic)
+    );
+    assert_eq!(
+        read(&with_sections(&|s| s[0].1 = 2u32.to_le_bytes().to_vec())),
+        Err(Error::UnsupportedProtocol(2))
+    );
+    assert_eq!(
+        read(&with_sections(&|s| s.swap(1, 2))),
+        file_error(ptau::Error::MissingSection(GROTH16_HEADER_SECTION))
+    );
+    assert_eq!(
+        read(&with_sections(&|s| {
+            s.pop();
+        })),
+        file_error(ptau::Error::MissingSection(CONTRIBUTIONS_SECTION))
+    );
+    assert_eq!(
+        read(&with_sections(&|s| s.push(s[7].clone()))),
+        file_error(ptau::Error::DuplicateSection(L_SECTION))
+    );
+    assert_eq!(
+        read(&with_sections(&|s| s[8].1.truncate(ptau::G1_LEN))),
+        file_error(ptau::Error::InvalidSectionSize(H_SECTION))
+    );
+    // More public inputs than variables.
+    assert_eq!(
+        read(&with_sections(
+            &|s| s[1].1[76..80].copy_from_slice(&[6, 0, 0, 0])
+        )),
+        Err(Error::InvalidHeader)
+    );
+    // Domain size not a power of two.
+    assert_eq!(
+        read(&with_sections(
+            &|s| s[1].1[80..84].copy_from_slice(&[3, 0, 0, 0])
+        )),
+        Err(Error::InvalidHeader)
+    );
+    // Parameters not sorted by type.
+    assert_eq!(
+        read(&with_sections(&|s| {
+            let params = s[9].1.len() - 2 - "contributor 0".len();
+            s[9].1[params] = 0;
+        })),
+        Err(Error::InvalidContributionParams(0))
+    );
+    // Name longer than the parameters.
+    assert_eq!(
+        read(&with_sections(&|s| {
+            let params = s[9].1.len() - 1 - "contributor 0".len();
+            s[9].1[params] = 0xff;
+        })),
+        Err(Error::InvalidContributionParams(0))
+    );
+}
+
+#[test]
+fn verify_uses_the_hooks() {
+    type Hooks = MeteredHooks<NativeHooks, ThreadBudget>;
+    let schedule = GasSchedule::EIP_1108;
+    let rng = &mut StdRng::seed_from_u64(1);
+    ThreadBudget::reset(u64::MAX);
+    let (r1cs, ptau, initial) = setup::<Hooks>(0);
+    let zkey = contributed(&initial, 2, 0);
+
+    // The `IC` (three combinations over the powers), `A`, `B1` and `B2` checks,
+    // then per contribution the cofactor clearing of `sp` and two checks, then
+    // the `delta` check and the `L` (three more combinations) and `H` checks.
+    let combined = 3 * schedule.msm_g1_cost(DOMAIN_SIZE);
+    let expected = schedule.msm_g1_cost(N_PUBLIC + 1)
+        + combined
+        + 2 * (schedule.msm_g1_cost(N_VARS) + schedule.msm_g1_cost(DOMAIN_SIZE))
+        + schedule.msm_g2_cost(N_VARS)
+        + schedule.msm_g2_cost(DOMAIN_SIZE)
+        + 2 * (schedule.ec_mul_g2_cost() + 2 * schedule.ec_pairing_cost(2))
+        + schedule.ec_pairing_cost(2)
+        + schedule.msm_g1_cost(N_VARS - N_PUBLIC - 1)
+        + combined
+        + schedule.ec_pairing_cost(2)
+        + schedule.msm_g1_cost(DOMAIN_SIZE)
+        + schedule.msm_g1_cost(2 * DOMAIN_SIZE)
+        + schedule.ec_pairing_cost(2);
+    ThreadBudget::reset(u64::MAX);
+    assert_eq!(verify(&r1cs, &ptau, &zkey, rng), Ok(()));
+    assert_eq!(ThreadBudget::used(), expected);
+
+    ThreadBudget::reset(expected - 1);
+    assert_eq!(
+        verify(&r1cs, &ptau, &zkey, rng),
+        Err(Error::Hook(OUT_OF_GAS))
+    );
+}
@@ -0,0 +1,100 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Verifiers for the Groth16 proofs wrapping the STARKs of zkVMs.
+//!
+//! Both [RISC Zero](https://github.com/risc0/risc0) and [SP1](https://github.com/succinctlabs/sp1)
+//! compress their execution proofs into a BN254 Groth16 proof of a fixed circuit,
+//! whose public inputs commit to the program and to its outputs:
+//! * [`risc0`] verifies the seals of Groth16 receipts against the receipt claim,
+//!   whose digest is split into two 128-bit public inputs along with the control
+//!   root of the recursion circuit;
+//! * [`sp1`] verifies the Groth16 proofs of the SDK against the program verifying
+//!   key hash and the digest of the public values.
+//!
+//! Encodings match the ones used by the on-chain verifiers of each zkVM, and the
+//! proofs are checked through [`crate::groth16`], with the public inputs linear
+//! combination and the pairing check dispatched to the [`crate::CurveHooks`].
+
+use crate::{gnark, groth16, HookError};
+
+pub mod risc0;
+pub mod sp1;
+
+#[cfg(test)]
+mod tests;
+
+/// Error returned by the verifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// Seal or proof is shorter than expected or has trailing bytes.
+    InvalidLength,
+    /// Seal selector doesn't match the verifier parameters.
+    SelectorMismatch,
+    /// Proof prefix doesn't match the hash of the verifying key.
+    VerifyingKeyHashMismatch,
+    /// Public input is not a canonical field element.
+    InvalidPublicInput,
+    /// Verifying key points are not valid or it doesn't fit the zkVM circuit.
+    MalformedVerifyingKey,
+    /// Proof points are not valid.
+    MalformedProof,
+    /// Failure reported by the hooks.
+    Hook(HookError),
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::InvalidLength => write!(f, "invalid length"),
+            Error::SelectorMismatch => write!(f, "selector mismatch"),
+            Error::VerifyingKeyHashMismatch => write!(f, "verifying key hash mismatch"),
+            Error::InvalidPublicInput => write!(f, "invalid public input"),
+            Error::MalformedVerifyingKey => write!(f, "malformed verifying key"),
+            Error::MalformedProof => write!(f, "malformed proof"),
+            Error::Hook(err) => write!(f, "hook failure: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl From<HookError> for Error {
+    fn from(err: HookError) -> Self {
+        Error::Hook(err)
+    }
+}
+
+impl From<groth16::Error> for Error {
+    fn from(err: groth16::Error) -> Self {
+        match err {
+            groth16::Error::MalformedProof => Error::MalformedProof,
+            groth16::Error::Hook(err) => Error::Hook(err),
+            // Public inputs are built by the verifiers, so their number only
+            // mismatches for keys of other circuits.
+            _ => Error::MalformedVerifyingKey,
+        }
+    }
+}
+
+/// Maps the decoding errors of proof points.
+fn proof_error(err: gnark::Error) -> Error {
+    match err {
+        gnark::Error::InvalidLength => Error::InvalidLength,
+        _ => Error::MalformedProof,
+    }
+}
@@ -0,0 +1,464 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [RISC Zero](https://github.com/risc0/risc0) Groth16 receipts.
+//!
+//! The Groth16 circuit has the five public inputs
+//!
+//! `[control_root_0, control_root_1, claim_0, claim_1, bn254_control_id]`
+//!
+//! where each digest `d` is split into the little-endian integers of `d[..16]`
+//! and `d[16..]`, and the BN254 control id is read as a little-endian integer.
+//!
+//! Digests of structured data are computed as RISC Zero's `tagged_struct`, i.e.
+//! `sha256(sha256(tag) || down || data || len(down))` where `data` words and the
+//! number of `down` digests are little-endian `u32` and `u16`.
+//!
+//! Seals are `A || B || C` with the EIP-197 encoding (see [`crate::eth`]). Seals
+//! submitted to the `RiscZeroGroth16Verifier` contract are prefixed with a
+//! selector identifying the [`VerifierParameters`], see [`encode_seal`].
+
+use ark_ff::{BigInt, MontFp, PrimeField};
+use ark_std::vec::Vec;
+use educe::Educe;
+use sha2::{Digest as _, Sha256};
+
+use super::{proof_error, Error};
+use crate::{gnark, groth16, CurveHooks, Fq2, Fr, G1Affine, G2Affine};
+
+/// Length of a digest.
+pub const DIGEST_LEN: usize = 32;
+/// Length of the selector prefixing encoded seals.
+pub const SELECTOR_LEN: usize = 4;
+/// Length of a seal.
+pub const SEAL_LEN: usize = 2 * gnark::G1_RAW_LEN + gnark::G2_RAW_LEN;
+
+/// SHA-256 digest.
+pub type Digest = [u8; DIGEST_LEN];
+
+/// Computes the digest of a structure with RISC Zero's `tagged_struct`.
+pub fn tagged_struct(tag: &str, down: &[Digest], data: &[u32]) -> Digest {
+    let mut hasher = Sha256::new();
+    hasher.update(Sha256::digest(tag.as_bytes()));
+    down.iter().for_each(|digest| hasher.update(digest));
+    data.iter()
+        .for_each(|word| hasher.update(word.to_le_bytes()));
+    hasher.update((down.len() as u16).to_le_bytes());
+    hasher.finalize().into()
+}
+
+/// Computes the digest of a list with RISC Zero's `tagged_list`.
+pub fn tagged_list(tag: &str, list: &[Digest]) -> Digest {
+    list.iter().rfold([0; DIGEST_LEN], |tail, head| {
+        tagged_struct(tag, &[*head, tail], &[])
+    })
+}
+
+/// How the execution ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    /// Program halted with the given user exit code.
+    Halted(u32),
+    /// Program paused with the given user exit code.
+    Paused(u32),
+    /// Execution was split by the host.
+    SystemSplit,
+    /// Execution reached the session limit.
+    SessionLimit,
+}
+
+impl ExitCode {
+    /// System and user exit codes.
+    pub fn into_pair(self) -> (u32, u32) {
+        match self {
+            ExitCode::Halted(user) => (0, user),
+            ExitCode::Paused(user) => (1, user),
+            ExitCode::SystemSplit => (2, 0),
+            ExitCode::SessionLimit => (2, 2),
+        }
+    }
+}
+
+/// Output of an execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Output {
+    /// Digest of the journal.
+    pub journal_digest: Digest,
+    /// Digest of the list of assumptions, zero when there are none.
+    pub assumptions_digest: Digest,
+}
+
+impl Output {
+    /// Digest of the output.
+    pub fn digest(&self) -> Digest {
+        tagged_struct(
+            "risc0.Output",
+            &[self.journal_digest, self.assumptions_digest],
+            &[],
+        )
+    }
+}
+
+/// Claim proven by a receipt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReceiptClaim {
+    /// Digest of the initial system state, i.e. the image id of the program.
+    pub pre_state_digest: Digest,
+    /// Digest of the final system state.
+    pub post_state_digest: Digest,
+    /// How the execution ended.
+    pub exit_code: ExitCode,
+    /// Digest of the input, zero when not committed.
+    pub input: Digest,
+    /// Digest of the [`Output`], zero when there is none.
+    pub output: Digest,
+}
+
+impl ReceiptClaim {
+    /// Claim of a successful execution of `image_id`, halting with exit code 0 and
+    /// committing the journal with digest `journal_digest` without assumptions.
+    pub fn ok(image_id: Digest, journal_digest: Digest) -> Self {
+        let output = Output {
+            journal_digest,
+            assumptions_digest: [0; DIGEST_LEN],
+        };
+        ReceiptClaim {
+            pre_state_digest: image_id,
+            post_state_digest: system_state_zero_digest(),
+            exit_code: ExitCode::Halted(0),
+            input: [0; DIGEST_LEN],
+            output: output.digest(),
+        }
+    }
+
+    /// Digest of the claim, as committed by the Groth16 public inputs.
+    pub fn digest(&self) -> Digest {
+        let (system, user) = self.exit_code.into_pair();
+        tagged_struct(
+            "risc0.ReceiptClaim",
+            &[
+                self.input,
+                self.pre_state_digest,
+                self.post_state_digest,
+                self.output,
+            ],
+            &[system, user],
+        )
+    }
+}
+
+/// Digest of the final system state of halted executions, i.e. of a zero program
+/// counter and memory merkle root.
+pub fn system_state_zero_digest() -> Digest {
+    tagged_struct("risc0.SystemState", &[[0; DIGEST_LEN]], &[0])
+}
+
+/// Parameters of the Groth16 receipts verifier.
+#[derive(Educe)]
+#[educe(Clone, Debug, PartialEq, Eq)]
+pub struct VerifierParameters<H: CurveHooks> {
+    /// Root of the Merkle tree of the allowed recursion programs.
+    pub control_root: Digest,
+    /// Control id of the circuit converting the STARK identity to BN254.
+    pub bn254_control_id: Digest,
+    /// Groth16 verifying key.
+    pub vk: groth16::VerifyingKey<H>,
+}
+
+impl<H: CurveHooks> VerifierParameters<H> {
+    /// Parameters for the given release of the recursion programs, with the
+    /// verifying key of the RISC Zero ceremony.
+    pub fn new(control_root: Digest, bn254_control_id: Digest) -> Self {
+        VerifierParameters {
+            control_root,
+            bn254_control_id,
+            vk: verifying_key(),
+        }
+    }
+
+    /// Digest of the parameters.
+    pub fn digest(&self) -> Result<Digest, Error> {
+        Ok(tagged_struct(
+            "risc0.Groth16ReceiptVerifierParameters",
+            &[
+                self.control_root,
+                self.bn254_control_id,
+                vk_digest(&self.vk)?,
+            ],
+            &[],
+        ))
+    }
+
+    /// Selector of the parameters, i.e. the first bytes of their digest.
+    pub fn selector(&self) -> Result<[u8; SELECTOR_LEN], Error> {
+        let digest = self.digest()?;
+        let mut selector = [0; SELECTOR_LEN];
+        selector.copy_from_slice(&digest[..SELECTOR_LEN]);
+        Ok(selector)
+    }
+}
+
+/// Groth16 verifying key of the RISC Zero ceremony, as embedded in the
+/// `Groth16Verifier` contract of `risc0-ethereum`.
+pub fn verifying_key<H: CurveHooks>() -> groth16::VerifyingKey<H> {
+    let g2 = |x: [Fq2; 2]| G2Affine::<H>::new_unchecked(x[0], x[1]);
+    groth16::VerifyingKey {
+        alpha_g1: G1Affine::<H>::new_unchecked(
+            MontFp!(
+                "20491192805390485299153009773594534940189261866228447918068658471970481763042"
+            ),
+            MontFp!("9383485363053290200918347156157836566562967994039712273449902621266178545958"),
+        ),
+        beta_g2: g2([
+            Fq2::new(
+                MontFp!(
+                    "6375614351688725206403948262868962793625744043794305715222011528459656738731"
+                ),
+                MontFp!(
+                    "4252822878758300859123897981450591353533073413197771768651442665752259397132"
+                ),
+            ),
+            Fq2::new(
+                MontFp!(
+                    "10505242626370262277552901082094356697409835680220590971873171140371331206856"
+                ),
+                MontFp!(
+                    "21847035105528745403288232691147584728191162732299865338377159692350059136679"
+                ),
+            ),
+        ]),
+        gamma_g2: g2([
+            Fq2::new(
+                MontFp!(
+                    "10857046999023057135944570762232829481370756359578518086990519993285655852781"
+                ),
+                MontFp!(
+                    "11559732032986387107991004021392285783925812861821192530917403151452391805634"
+                ),
+            ),
+            Fq2::new(
+                MontFp!(
+                    "8495653923123431417604973247489272438418190587263600148770280649306958101930"
+                ),
+                MontFp!(
+                    "4082367875863433681332203403145435568316851327593401208105741076214120093531"
+                ),
+            ),
+        ]),
+        delta_g2: g2([
+            Fq2::new(
+                MontFp!(
+                    "12043754404802191763554326994664886008979042643626290185762540825416902247219"
+                ),
+                MontFp!(
+                    "1668323501672964604911431804142266013250380587483576094566949227275849579036"
+                ),
+            ),
+            Fq2::new(
+                MontFp!(
+                    "13740680757317479711909903993315946540841369848973133181051452051592786724563"
+                ),
+                MontFp!(
+                    "7710631539206257456743780535472368339139328733484942210876916214502466455394"
+                ),
+            ),
+        ]),
+        gamma_abc_g1: [
+            (
+                MontFp!(
+                    "8446592859352799428420270221449902464741693648963397251242447530457567083492"
+                ),
+                MontFp!(
+                    "1064796367193003797175961162477173481551615790032213185848276823815288302804"
+                ),
+            ),
+            (
+                MontFp!(
+                    "3179835575189816632597428042194253779818690147323192973511715175294048485951"
+                ),
+                MontFp!(
+                    "20895841676865356752879376687052266198216014795822152491318012491767775979074"
+                ),
+            ),
+            (
+                MontFp!(
+                    "5332723250224941161709478398807683311971555792614491788690328996478511465287"
+                ),
+                MontFp!(
+                    "21199491073419440416471372042641226693637837098357067793586556692319371762571"
+                ),
+            ),
+            (
+                MontFp!(
+                    "12457994489566736295787256452575216703923664299075106359829199968023158780583"
+                ),
+                MontFp!(
+                    "19706766271952591897761291684837117091856807401404423804318744964752784280790"
+                ),
+            ),
+            (
+                MontFp!(
+                    "19617808913178163826953378459323299110911217259216006187355745713323154132237"
+                ),
+                MontFp!(
+                    "21663537384585072695701846972542344484111393047775983928357046779215877070466"
+                ),
+            ),
+            (
+                MontFp!(
+                    "6834578911681792552110317589222010969491336870276623105249474534788043166867"
+                ),
+                MontFp!(
+                    "15060583660288623605191393599883223885678013570733629274538391874953353488393"
+                ),
+            ),
+        ]
+        .into_iter()
+        .map(|(x, y)| G1Affine::<H>::new_unchecked(x, y))
+        .collect(),
+    }
+}
+
+/// Digest of a verifying key, hashing each point as `sha256(x || y)` with the
+/// EIP-197 encoding.
+pub fn vk_digest<H: CurveHooks>(vk: &groth16::VerifyingKey<H>) -> Result<Digest, Error> {
+    let hash = |bytes: &[u8]| -> Digest { Sha256::digest(bytes).into() };
+    // The point at infinity has no coordinates to hash.
+    let valid = [vk.alpha_g1]
+        .iter()
+        .chain(&vk.gamma_abc_g1)
+        .all(|point| !point.infinity && groth16::is_valid(point))
+        && [vk.beta_g2, vk.gamma_g2, vk.delta_g2]
+            .iter()
+            .all(|point| !point.infinity && groth16::is_valid(point));
+    if !valid {
+        return Err(Error::MalformedVerifyingKey);
+    }
+    let ic: Vec<_> = vk
+        .gamma_abc_g1
+        .iter()
+        .map(|point| hash(&gnark::g1_to_raw(point)))
+        .collect();
+    Ok(tagged_struct(
+        "risc0_groth16.VerifyingKey",
+        &[
+            hash(&gnark::g1_to_raw(&vk.alpha_g1)),
+            hash(&gnark::g2_to_raw(&vk.beta_g2)),
+            hash(&gnark::g2_to_raw(&vk.gamma_g2)),
+            hash(&gnark::g2_to_raw(&vk.delta_g2)),
+            tagged_list("risc0_groth16.VerifyingKey.IC", &ic),
+        ],
+        &[],
+    ))
+}
+
+/// Reads a little-endian integer of up to 32 bytes as a canonical scalar.
+fn fr_from_le_bytes(bytes: &[u8]) -> Option<Fr> {
+    let mut limbs = [0; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks(8)) {
+        let mut word = [0; 8];
+        word[..chunk.len()].copy_from_slice(chunk);
+        *limb = u64::from_le_bytes(word);
+    }
+    Fr::from_bigint(BigInt(limbs))
+}
+
+/// Splits a digest into the little-endian integers of its two halves.
+pub fn split_digest(digest: &Digest) -> [Fr; 2] {
+    let (low, high) = digest.split_at(DIGEST_LEN / 2);
+    [low, high].map(|half| fr_from_le_bytes(half).expect("128-bit integers are canonical"))
+}
+
+/// Public inputs of the Groth16 proof of a claim with digest `claim_digest`.
+pub fn public_inputs<H: CurveHooks>(
+    params: &VerifierParameters<H>,
+    claim_digest: &Digest,
+) -> Result<[Fr; 5], Error> {
+    let [control_root_0, control_root_1] = split_digest(&params.control_root);
+    let [claim_0, claim_1] = split_digest(claim_digest);
+    let bn254_control_id =
+        fr_from_le_bytes(&params.bn254_control_id).ok_or(Error::InvalidPublicInput)?;
+    Ok([
+        control_root_0,
+        control_root_1,
+        claim_0,
+        claim_1,
+        bn254_control_id,
+    ])
+}
+
+/// Reads a seal, i.e. the `A`, `B` and `C` points with the EIP-197 encoding.
+pub fn seal_from_bytes<H: CurveHooks>(bytes: &[u8]) -> Result<groth16::Proof<H>, Error> {
+    let bytes: &[u8; SEAL_LEN] = bytes.try_into().map_err(|_| Error::InvalidLength)?;
+    let (a, rest) = bytes.split_at(gnark::G1_RAW_LEN);
+    let (b, c) = rest.split_at(gnark::G2_RAW_LEN);
+    Ok(groth16::Proof {
+        a: gnark::g1_from_raw(a.try_into().expect("split at G1_RAW_LEN")).map_err(proof_error)?,
+        b: gnark::g2_from_raw(b.try_into().expect("split at G2_RAW_LEN")).map_err(proof_error)?,
+        c: gnark::g1_from_raw(c.try_into().expect("split at G1_RAW_LEN")).map_err(proof_error)?,
+    })
+}
+
+/// Writes a seal, i.e. the `A`, `B` and `C` points with the EIP-197 encoding.
+pub fn seal_to_bytes<H: CurveHooks>(proof: &groth16::Proof<H>) -> [u8; SEAL_LEN] {
+    let mut output = [0; SEAL_LEN];
+    let (a, rest) = output.split_at_mut(gnark::G1_RAW_LEN);
+    let (b, c) = rest.split_at_mut(gnark::G2_RAW_LEN);
+    a.copy_from_slice(&gnark::g1_to_raw(&proof.a));
+    b.copy_from_slice(&gnark::g2_to_raw(&proof.b));
+    c.copy_from_slice(&gnark::g1_to_raw(&proof.c));
+    output
+}
+
+/// Prefixes a seal with the selector of the verifier parameters, as expected by
+/// the `RiscZeroGroth16Verifier` contract.
+pub fn encode_seal<H: CurveHooks>(
+    params: &VerifierParameters<H>,
+    seal: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let mut output = params.selector()?.to_vec();
+    output.extend_from_slice(seal);
+    Ok(output)
+}
+
+/// Verifies a seal, without selector, against the digest of the claim.
+pub fn verify_integrity<H: CurveHooks>(
+    params: &VerifierParameters<H>,
+    seal: &[u8],
+    claim_digest: &Digest,
+) -> Result<bool, Error> {
+    let proof = seal_from_bytes(seal)?;
+    let inputs = public_inputs(params, claim_digest)?;
+    Ok(groth16::verify(&params.vk, &proof, &inputs)?)
+}
+
+/// Verifies an encoded seal, as accepted by the `RiscZeroGroth16Verifier`
+/// contract, for the successful execution of `image_id` committing the journal
+/// with digest `journal_digest` (see [`ReceiptClaim::ok`]).
+pub fn verify<H: CurveHooks>(
+    params: &VerifierParameters<H>,
+    seal: &[u8],
+    image_id: &Digest,
+    journal_digest: &Digest,
+) -> Result<bool, Error> {
+    let (selector, seal) = seal
+        .split_first_chunk::<SELECTOR_LEN>()
+        .ok_or(Error::InvalidLength)?;
+    if *selector != params.selector()? {
+        return Err(Error::SelectorMismatch);
+    }
+    let claim = ReceiptClaim::ok(*image_id, *journal_digest);
+    verify_integrity(params, seal, &claim.digest())
+}
@@ -0,0 +1,131 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [SP1](https://github.com/succinctlabs/sp1) Groth16 proofs.
+//!
+//! The gnark Groth16 circuit has two public inputs: the hash of the program
+//! verifying key (`vk.bytes32()` in the SDK) and the digest of the committed
+//! public values, i.e. their SHA-256 or Blake3 hash with the top 3 bits cleared
+//! to fit the scalar field.
+//!
+//! Proofs, as returned by `SP1ProofWithPublicValues::bytes`, are the first 4 bytes
+//! of the SHA-256 hash of the verifying key followed by the `A`, `B` and `C`
+//! points in the gnark raw or compressed format (see [`crate::gnark`]).
+
+use educe::Educe;
+use sha2::{Digest, Sha256};
+
+use super::{proof_error, Error};
+use crate::{gnark, CurveHooks, Fr};
+
+/// Length of the verifying key hash prefixing proofs.
+pub const VK_HASH_PREFIX_LEN: usize = 4;
+
+/// Groth16 verifying key of SP1 v5, as written by gnark `WriteTo`.
+pub const GROTH16_VK_BYTES: &[u8] = include_bytes!("sp1_groth16_vk.bin");
+
+/// Hash function committing the public values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublicValuesHash {
+    /// SHA-256, the default.
+    Sha256,
+    /// Blake3, for programs enabling it.
+    Blake3,
+}
+
+/// SP1 Groth16 verifying key.
+#[derive(Educe)]
+#[educe(Clone, Debug, PartialEq, Eq)]
+pub struct VerifyingKey<H: CurveHooks> {
+    /// The gnark verifying key.
+    pub vk: gnark::Groth16VerifyingKey<H>,
+    /// First bytes of the SHA-256 hash of the encoded key.
+    pub hash_prefix: [u8; VK_HASH_PREFIX_LEN],
+}
+
+/// Reads a verifying key as written by gnark `WriteTo`, checking that it has
+/// the two SP1 public inputs.
+pub fn vk_from_bytes<H: CurveHooks>(bytes: &[u8]) -> Result<VerifyingKey<H>, Error> {
+    let vk = gnark::groth16_vk_from_bytes::<H>(bytes).map_err(|_| Error::MalformedVerifyingKey)?;
+    if vk.vk.num_public_inputs() != 2 || !vk.commitment_keys.is_empty() {
+        return Err(Error::MalformedVerifyingKey);
+    }
+    let mut hash_prefix = [0; VK_HASH_PREFIX_LEN];
+    hash_prefix.copy_from_slice(&Sha256::digest(bytes)[..VK_HASH_PREFIX_LEN]);
+    Ok(VerifyingKey { vk, hash_prefix })
+}
+
+/// The [`GROTH16_VK_BYTES`] verifying key.
+pub fn verifying_key<H: CurveHooks>() -> VerifyingKey<H> {
+    vk_from_bytes(GROTH16_VK_BYTES).expect("embedded key is valid")
+}
+
+/// Digest of the public values committed by the program.
+pub fn public_values_digest(public_values: &[u8], hash: PublicValuesHash) -> [u8; 32] {
+    let mut digest: [u8; 32] = match hash {
+        PublicValuesHash::Sha256 => Sha256::digest(public_values).into(),
+        PublicValuesHash::Blake3 => blake3::hash(public_values).into(),
+    };
+    digest[0] &= 0x1f;
+    digest
+}
+
+/// Public inputs of the Groth16 proof, from the program verifying key hash and
+/// the public values digest (see [`public_values_digest`]).
+pub fn public_inputs(
+    program_vk_hash: &[u8; 32],
+    public_values_digest: &[u8; 32],
+) -> Result<[Fr; 2], Error> {
+    let read = |bytes| gnark::fr_from_bytes(bytes).map_err(|_| Error::InvalidPublicInput);
+    Ok([read(program_vk_hash)?, read(public_values_digest)?])
+}
+
+/// Verifies a proof against the program verifying key hash and the digest of its
+/// public values.
+pub fn verify_digest<H: CurveHooks>(
+    vk: &VerifyingKey<H>,
+    proof: &[u8],
+    program_vk_hash: &[u8; 32],
+    public_values_digest: &[u8; 32],
+) -> Result<bool, Error> {
+    let (prefix, proof) = proof
+        .split_first_chunk::<VK_HASH_PREFIX_LEN>()
+        .ok_or(Error::InvalidLength)?;
+    if *prefix != vk.hash_prefix {
+        return Err(Error::VerifyingKeyHashMismatch);
+    }
+    let proof = gnark::groth16_proof_from_bytes::<H>(proof).map_err(proof_error)?;
+    let inputs = public_inputs(program_vk_hash, public_values_digest)?;
+    Ok(gnark::verify_groth16(&vk.vk, &proof, &inputs)?)
+}
+
+/// Verifies a proof against the program verifying key hash and its public values.
+///
+/// As the SP1 verifier does, the public values are hashed with SHA-256 first and
+/// with Blake3 if the proof doesn't verify.
+pub fn verify<H: CurveHooks>(
+    vk: &VerifyingKey<H>,
+    proof: &[u8],
+    public_values: &[u8],
+    program_vk_hash: &[u8; 32],
+) -> Result<bool, Error> {
+    for hash in [PublicValuesHash::Sha256, PublicValuesHash::Blake3] {
+        let digest = public_values_digest(public_values, hash);
+        if verify_digest(vk, proof, program_vk_hash, &digest)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
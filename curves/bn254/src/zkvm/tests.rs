@@ -0,0 +1,429 @@
+// Copyright 2024 Horizen Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0 or MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::{
+    gnark::{g1_to_compressed, g1_to_raw, g2_to_compressed, g2_to_raw},
+    metering::{GasSchedule, MeteredHooks, ThreadBudget, OUT_OF_GAS},
+    native::NativeHooks,
+    CurveHooks, Fr, FromArk, G1Affine,
+};
+use ark_bn254::Bn254 as ArkBn254;
+use ark_ff::{Field, Zero};
+use ark_models_ext::short_weierstrass::Affine;
+use ark_models_ext::AffineRepr;
+use ark_relations::{
+    lc,
+    r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError},
+};
+use ark_snark::SNARK;
+use ark_std::{
+    rand::{rngs::StdRng, SeedableRng},
+    vec,
+    vec::Vec,
+};
+use sha2::{Digest, Sha256};
+
+type Groth16 = ark_groth16::Groth16<ArkBn254>;
+
+/// Proves knowledge of the squares of the public inputs.
+#[derive(Clone)]
+struct SquaresCircuit(Vec<Fr>);
+
+impl ConstraintSynthesizer<Fr> for SquaresCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        for input in self.0 {
+            let x = cs.new_input_variable(|| Ok(input))?;
+            let x_sq = cs.new_witness_variable(|| Ok(input.square()))?;
+            cs.enforce_constraint(lc!() + x, lc!() + x, lc!() + x_sq)?;
+        }
+        Ok(())
+    }
+}
+
+/// Generates a key for `inputs.len()` public inputs and a proof for `inputs`.
+fn setup_and_prove<H: CurveHooks>(inputs: &[Fr]) -> (groth16::VerifyingKey<H>, groth16::Proof<H>) {
+    let rng = &mut StdRng::seed_from_u64(0);
+    let circuit = SquaresCircuit(vec![Fr::zero(); inputs.len()]);
+    let (pk, vk) = Groth16::circuit_specific_setup(circuit, rng).unwrap();
+    let proof = Groth16::prove(&pk, SquaresCircuit(inputs.to_vec()), rng).unwrap();
+    let vk = groth16::VerifyingKey {
+        alpha_g1: Affine::from_ark(vk.alpha_g1),
+        beta_g2: Affine::from_ark(vk.beta_g2),
+        gamma_g2: Affine::from_ark(vk.gamma_g2),
+        delta_g2: Affine::from_ark(vk.delta_g2),
+        gamma_abc_g1: vk.gamma_abc_g1.into_iter().map(Affine::from_ark).collect(),
+    };
+    let proof = groth16::Proof {
+        a: Affine::from_ark(proof.a),
+        b: Affine::from_ark(proof.b),
+        c: Affine::from_ark(proof.c),
+    };
+    (vk, proof)
+}
+
+fn digest(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+const JOURNAL: &[u8] = b"journal";
+
+/// RISC Zero parameters with a test key, and an encoded seal for [`JOURNAL`].
+fn risc0_instance<H: CurveHooks>() -> (risc0::VerifierParameters<H>, Vec<u8>, risc0::Digest) {
+    let image_id = digest(b"image");
+    let mut params = risc0::VerifierParameters::new(digest(b"control root"), digest(b"control id"));
+    // Control ids are digests of BN254 scalars, hence fit the scalar field.
+    params.bn254_control_id[31] &= 0x0f;
+    let claim = risc0::ReceiptClaim::ok(image_id, digest(JOURNAL));
+    let inputs = risc0::public_inputs(&params, &claim.digest()).unwrap();
+    let (vk, proof) = setup_and_prove(&inputs);
+    params.vk = vk;
+    let seal = risc0::encode_seal(&params, &risc0::seal_to_bytes(&proof)).unwrap();
+    (params, seal, image_id)
+}
+
+#[test]
+fn risc0_digests_match_risc0() {
+    // Test vector of `risc0-binfmt`.
+    let digest1 = risc0::tagged_struct("foo", &[], &[1, 2013265920, 3]);
+    let digest2 = risc0::tagged_struct("bar", &[digest1, digest1], &[2013265920, 5]);
+    let digest3 = risc0::tagged_struct(
+        "baz",
+        &[digest1, digest2, digest1],
+        &[6, 7, 2013265920, 9, 10],
+    );
+    assert_eq!(
+        digest3,
+        hex_literal::hex!("9ff20cc6d365efa2af09181772f49013d05cdee6da896851614cae23aa5dd442")
+    );
+    // `SYSTEM_STATE_ZERO_DIGEST` of the `risc0-ethereum` contracts.
+    assert_eq!(
+        risc0::system_state_zero_digest(),
+        hex_literal::hex!("a3acc27117418996340b84e5a90f3ef4c49d22c79e44aad822ec9c313e1eb8e2")
+    );
+    assert_eq!(risc0::tagged_list("tag", &[]), [0; 32]);
+    assert_eq!(
+        risc0::tagged_list("tag", &[digest1, digest2]),
+        risc0::tagged_struct(
+            "tag",
+            &[
+                digest1,
+                risc0::tagged_struct("tag", &[digest2, [0; 32]], &[])
+            ],
+            &[]
+        )
+    );
+}
+
+#[test]
+fn risc0_digests_are_split_in_halves() {
+    let digest: [u8; 32] = core::array::from_fn(|i| i as u8);
+    let [low, high] = risc0::split_digest(&digest);
+    assert_eq!(
+        low,
+        Fr::from(u128::from_le_bytes(digest[..16].try_into().unwrap()))
+    );
+    assert_eq!(
+        high,
+        Fr::from(u128::from_le_bytes(digest[16..].try_into().unwrap()))
+    );
+}
+
+#[test]
+fn risc0_verifying_key_is_valid() {
+    let vk = risc0::verifying_key::<NativeHooks>();
+    assert_eq!(vk.num_public_inputs(), 5);
+    assert!(groth16::prepare_verifying_key(vk.clone()).is_ok());
+    assert!(risc0::vk_digest(&vk).is_ok());
+
+    let mut infinity = vk;
+    infinity.gamma_abc_g1[0] = G1Affine::<NativeHooks>::zero();
+    assert_eq!(
+        risc0::vk_digest(&infinity),
+        Err(Error::MalformedVerifyingKey)
+    );
+}
+
+#[test]
+fn risc0_verifier_parameters_match_risc0() {
+    let vk = risc0::verifying_key::<NativeHooks>();
+    // `risc0_groth16::verifying_key().digest()` of `risc0-groth16` 2.0.
+    assert_eq!(
+        risc0::vk_digest(&vk),
+        Ok(hex_literal::hex!(
+            "21c5fdd9b4d576b17581f50b755482ba7a2134a3b5186e8e454acfa1f69511ab"
+        ))
+    );
+    // `ALLOWED_CONTROL_ROOT` and `BN254_IDENTITY_CONTROL_ID` of
+    // `risc0-circuit-recursion` 3.0, used by `risc0-zkvm` 2.x.
+    let params = risc0::VerifierParameters::<NativeHooks>::new(
+        hex_literal::hex!("ce52bf56033842021af3cf6db8a50d1b7535c125a34f1a22c6fdcf002c5a1529"),
+        hex_literal::hex!("c07a65145c3cb48b6101962ea607a4dd93c753bb26975cb47feb00d3666e4404"),
+    );
+    // `Groth16ReceiptVerifierParameters::default().digest()` of `risc0-zkvm` 2.x.
+    assert_eq!(
+        params.digest(),
+        Ok(hex_literal::hex!(
+            "bb001d444841d70e8bc0c7d034b349044bf3cf0117afb702b2f1e898b7dd13cc"
+        ))
+    );
+    assert_eq!(params.selector(), Ok(hex_literal::hex!("bb001d44")));
+    let claim = risc0::ReceiptClaim::ok([0; 32], [0; 32]);
+    assert!(risc0::public_inputs(&params, &claim.digest()).is_ok());
+}
+
+#[test]
+fn risc0_seals_are_verified() {
+    let (params, seal, image_id) = risc0_instance::<NativeHooks>();
+    let journal_digest = digest(JOURNAL);
+    assert_eq!(
+        risc0::verify(&params, &seal, &image_id, &journal_digest),
+        Ok(true)
+    );
+    let claim = risc0::ReceiptClaim::ok(image_id, journal_digest);
+    assert_eq!(
+        risc0::verify_integrity(&params, &seal[risc0::SELECTOR_LEN..], &claim.digest()),
+        Ok(true)
+    );
+
+    assert_eq!(
+        risc0::verify(&params, &seal, &image_id, &digest(b"other journal")),
+        Ok(false)
+    );
+    assert_eq!(
+        risc0::verify(&params, &seal, &digest(b"other image"), &journal_digest),
+        Ok(false)
+    );
+    let failed = risc0::ReceiptClaim {
+        exit_code: risc0::ExitCode::Halted(1),
+        ..claim
+    };
+    assert_eq!(
+        risc0::verify_integrity(&params, &seal[risc0::SELECTOR_LEN..], &failed.digest()),
+        Ok(false)
+    );
+
+    let mut other = params.clone();
+    other.control_root[0] ^= 1;
+    assert_eq!(
+        risc0::verify(&other, &seal, &image_id, &journal_digest),
+        Err(Error::SelectorMismatch)
+    );
+    let mut selector = seal.clone();
+    selector[0] ^= 1;
+    assert_eq!(
+        risc0::verify(&params, &selector, &image_id, &journal_digest),
+        Err(Error::SelectorMismatch)
+    );
+}
+
+#[test]
+fn risc0_malformed_seals_are_rejected() {
+    let (params, seal, image_id) = risc0_instance::<NativeHooks>();
+    let journal_digest = digest(JOURNAL);
+    for len in [0, risc0::SELECTOR_LEN, seal.len() - 1, seal.len() + 1] {
+        let mut bytes = seal.clone();
+        bytes.resize(len, 0);
+        assert_eq!(
+            risc0::verify(&params, &bytes, &image_id, &journal_digest),
+            Err(Error::InvalidLength)
+        );
+    }
+
+    // `A.y` off the curve.
+    let mut bytes = seal.clone();
+    bytes[risc0::SELECTOR_LEN + 63] ^= 1;
+    assert_eq!(
+        risc0::verify(&params, &bytes, &image_id, &journal_digest),
+        Err(Error::MalformedProof)
+    );
+
+    let mut non_canonical = params.clone();
+    non_canonical.bn254_control_id = [0xff; 32];
+    let seal = risc0::encode_seal(&non_canonical, &seal[risc0::SELECTOR_LEN..]).unwrap();
+    assert_eq!(
+        risc0::verify(&non_canonical, &seal, &image_id, &journal_digest),
+        Err(Error::InvalidPublicInput)
+    );
+
+    let mut wrong_key = params.clone();
+    wrong_key.vk.gamma_abc_g1.pop();
+    let seal = risc0::encode_seal(&wrong_key, &seal[risc0::SELECTOR_LEN..]).unwrap();
+    assert_eq!(
+        risc0::verify(&wrong_key, &seal, &image_id, &journal_digest),
+        Err(Error::MalformedVerifyingKey)
+    );
+}
+
+/// SP1 key and raw proof for `program_vk_hash` and `PUBLIC_VALUES` hashed with `hash`.
+fn sp1_instance<H: CurveHooks>(
+    hash: sp1::PublicValuesHash,
+) -> (sp1::VerifyingKey<H>, groth16::Proof<H>, [u8; 32]) {
+    let mut program_vk_hash = digest(b"program");
+    program_vk_hash[0] &= 0x1f;
+    let digest = sp1::public_values_digest(PUBLIC_VALUES, hash);
+    let inputs = sp1::public_inputs(&program_vk_hash, &digest).unwrap();
+    let (vk, proof) = setup_and_prove(&inputs);
+    let vk = gnark::Groth16VerifyingKey {
+        vk,
+        beta_g1: G1Affine::<H>::generator(),
+        delta_g1: G1Affine::<H>::generator(),
+        public_and_commitment_committed: vec![],
+        commitment_keys: vec![],
+    };
+    let vk = sp1::vk_from_bytes(&gnark::groth16_vk_to_bytes(&vk, true)).unwrap();
+    (vk, proof, program_vk_hash)
+}
+
+const PUBLIC_VALUES: &[u8] = b"public values";
+
+fn sp1_proof<H: CurveHooks>(
+    vk: &sp1::VerifyingKey<H>,
+    proof: &groth16::Proof<H>,
+    compressed: bool,
+) -> Vec<u8> {
+    let mut bytes = vk.hash_prefix.to_vec();
+    match compressed {
+        true => {
+            bytes.extend(g1_to_compressed(&proof.a));
+            bytes.extend(g2_to_compressed(&proof.b));
+            bytes.extend(g1_to_compressed(&proof.c));
+        }
+        false => {
+            bytes.extend(g1_to_raw(&proof.a));
+            bytes.extend(g2_to_raw(&proof.b));
+            bytes.extend(g1_to_raw(&proof.c));
+        }
+    }
+    bytes
+}
+
+#[test]
+fn sp1_verifying_key_is_embedded() {
+    let vk = sp1::verifying_key::<NativeHooks>();
+    assert_eq!(vk.vk.vk.num_public_inputs(), 2);
+    // Selector of the SP1 v5 `SP1VerifierGroth16` contract.
+    assert_eq!(vk.hash_prefix, hex_literal::hex!("a4594c59"));
+
+    let plonk_vk = include_bytes!("../gnark/test-data/plonk_vk.bin");
+    assert_eq!(
+        sp1::vk_from_bytes::<NativeHooks>(plonk_vk),
+        Err(Error::MalformedVerifyingKey)
+    );
+}
+
+#[test]
+fn sp1_public_values_digest_fits_the_scalar_field() {
+    let sha256 = sp1::public_values_digest(PUBLIC_VALUES, sp1::PublicValuesHash::Sha256);
+    let mut expected = digest(PUBLIC_VALUES);
+    expected[0] &= 0x1f;
+    assert_eq!(sha256, expected);
+
+    let blake3 = sp1::public_values_digest(PUBLIC_VALUES, sp1::PublicValuesHash::Blake3);
+    let mut expected: [u8; 32] = blake3::hash(PUBLIC_VALUES).into();
+    expected[0] &= 0x1f;
+    assert_eq!(blake3, expected);
+
+    assert!(sp1::public_inputs(&sha256, &blake3).is_ok());
+    assert_eq!(
+        sp1::public_inputs(&[0xff; 32], &sha256),
+        Err(Error::InvalidPublicInput)
+    );
+}
+
+#[test]
+fn sp1_proofs_are_verified() {
+    for hash in [sp1::PublicValuesHash::Sha256, sp1::PublicValuesHash::Blake3] {
+        let (vk, proof, program_vk_hash) = sp1_instance::<NativeHooks>(hash);
+        for compressed in [false, true] {
+            let bytes = sp1_proof(&vk, &proof, compressed);
+            assert_eq!(
+                sp1::verify(&vk, &bytes, PUBLIC_VALUES, &program_vk_hash),
+                Ok(true)
+            );
+            let digest = sp1::public_values_digest(PUBLIC_VALUES, hash);
+            assert_eq!(
+                sp1::verify_digest(&vk, &bytes, &program_vk_hash, &digest),
+                Ok(true)
+            );
+            assert_eq!(
+                sp1::verify(&vk, &bytes, b"other values", &program_vk_hash),
+                Ok(false)
+            );
+            assert_eq!(sp1::verify(&vk, &bytes, PUBLIC_VALUES, &digest), Ok(false));
+        }
+    }
+}
+
+#[test]
+fn sp1_malformed_proofs_are_rejected() {
+    let (vk, proof, program_vk_hash) = sp1_instance::<NativeHooks>(sp1::PublicValuesHash::Sha256);
+    let bytes = sp1_proof(&vk, &proof, false);
+
+    let mut prefix = bytes.clone();
+    prefix[0] ^= 1;
+    assert_eq!(
+        sp1::verify(&vk, &prefix, PUBLIC_VALUES, &program_vk_hash),
+        Err(Error::VerifyingKeyHashMismatch)
+    );
+    for len in [0, sp1::VK_HASH_PREFIX_LEN + 1, bytes.len() - 1] {
+        assert_eq!(
+            sp1::verify(&vk, &bytes[..len], PUBLIC_VALUES, &program_vk_hash),
+            Err(Error::InvalidLength)
+        );
+    }
+    let mut off_curve = bytes.clone();
+    off_curve[sp1::VK_HASH_PREFIX_LEN + 63] ^= 1;
+    assert_eq!(
+        sp1::verify(&vk, &off_curve, PUBLIC_VALUES, &program_vk_hash),
+        Err(Error::MalformedProof)
+    );
+}
+
+#[test]
+fn verify_uses_the_hooks() {
+    type Hooks = MeteredHooks<NativeHooks, ThreadBudget>;
+    let schedule = GasSchedule::EIP_1108;
+    let (params, seal, image_id) = risc0_instance::<Hooks>();
+    let journal_digest = digest(JOURNAL);
+
+    ThreadBudget::reset(u64::MAX);
+    assert_eq!(
+        risc0::verify(&params, &seal, &image_id, &journal_digest),
+        Ok(true)
+    );
+    assert_eq!(
+        ThreadBudget::used(),
+        schedule.msm_g1_cost(5) + schedule.ec_pairing_cost(4)
+    );
+    ThreadBudget::reset(schedule.msm_g1_cost(5));
+    assert_eq!(
+        risc0::verify(&params, &seal, &image_id, &journal_digest),
+        Err(Error::Hook(OUT_OF_GAS))
+    );
+
+    // Blake3 committed values are only checked once the SHA-256 ones fail.
+    let (vk, proof, program_vk_hash) = sp1_instance::<Hooks>(sp1::PublicValuesHash::Blake3);
+    let bytes = sp1_proof(&vk, &proof, false);
+    ThreadBudget::reset(u64::MAX);
+    assert_eq!(
+        sp1::verify(&vk, &bytes, PUBLIC_VALUES, &program_vk_hash),
+        Ok(true)
+    );
+    assert_eq!(
+        ThreadBudget::used(),
+        2 * (schedule.msm_g1_cost(2) + schedule.ec_pairing_cost(4))
+    );
+}